@@ -24,7 +24,8 @@ pub enum DelegationType {
 /// A is associated with 2/7 of the stake, B has 1/7 of stake and C
 /// has 4/7 of the stake.
 ///
-/// It's invalid to have less than 2 elements in the array,
+/// It's invalid to have less than 2 elements in the array, to have
+/// a pool with zero parts or the same pool more than once,
 /// and by extension parts need to be equal to the sum of individual
 /// pools parts.
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -38,25 +39,11 @@ pub const DELEGATION_RATIO_MAX_DECLS: usize = 8;
 
 impl DelegationRatio {
     pub fn is_valid(&self) -> bool {
-        // map to u32 before summing to make sure we don't overflow
-        let total: u32 = self.pools.iter().map(|x| x.1 as u32).sum();
-        let has_no_zero = self.pools.iter().find(|x| x.1 == 0).is_none();
-        has_no_zero
-            && self.parts > 1
-            && self.pools.len() > 1
-            && self.pools.len() <= DELEGATION_RATIO_MAX_DECLS
-            && total == (self.parts as u32)
+        Self::validate(self.parts, &self.pools)
     }
 
     pub fn new(parts: u8, pools: Vec<(PoolId, u8)>) -> Option<DelegationRatio> {
-        let total: u32 = pools.iter().map(|x| x.1 as u32).sum();
-        let has_no_zero = pools.iter().find(|x| x.1 == 0).is_none();
-        if has_no_zero
-            && parts > 1
-            && pools.len() > 1
-            && pools.len() <= DELEGATION_RATIO_MAX_DECLS
-            && total == (parts as u32)
-        {
+        if Self::validate(parts, &pools) {
             Some(Self {
                 parts,
                 pools: pools.into(),
@@ -66,6 +53,22 @@ impl DelegationRatio {
         }
     }
 
+    fn validate(parts: u8, pools: &[(PoolId, u8)]) -> bool {
+        // map to u32 before summing to make sure we don't overflow
+        let total: u32 = pools.iter().map(|x| x.1 as u32).sum();
+        let has_no_zero = pools.iter().find(|x| x.1 == 0).is_none();
+        let has_no_duplicate = pools
+            .iter()
+            .enumerate()
+            .all(|(i, x)| pools[..i].iter().all(|y| y.0 != x.0));
+        has_no_zero
+            && has_no_duplicate
+            && parts > 1
+            && pools.len() > 1
+            && pools.len() <= DELEGATION_RATIO_MAX_DECLS
+            && total == (parts as u32)
+    }
+
     pub fn parts(&self) -> u8 {
         self.parts
     }
@@ -476,36 +479,45 @@ mod tests {
 
     #[test]
     pub fn delegation_ratio_correct() {
+        let parts = 8u8;
+        let pools: Vec<(PoolId, u8)> = vec![
+            (StakePoolBuilder::new().build().id(), 2u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
+        ];
+        assert!(DelegationRatio::new(parts, pools).is_some());
+    }
+
+    #[test]
+    pub fn delegation_ratio_duplicated_pools() {
         let fake_pool_id = StakePoolBuilder::new().build().id();
         let parts = 8u8;
         let pools: Vec<(PoolId, u8)> = vec![
             (fake_pool_id.clone(), 2u8),
-            (fake_pool_id.clone(), 3u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
             (fake_pool_id, 3u8),
         ];
-        assert!(DelegationRatio::new(parts, pools).is_some());
+        assert!(DelegationRatio::new(parts, pools).is_none());
     }
 
     #[test]
     pub fn delegation_ratio_zero_parts() {
-        let fake_pool_id = StakePoolBuilder::new().build().id();
         let parts = 0u8;
         let pools: Vec<(PoolId, u8)> = vec![
-            (fake_pool_id.clone(), 2u8),
-            (fake_pool_id.clone(), 3u8),
-            (fake_pool_id, 3u8),
+            (StakePoolBuilder::new().build().id(), 2u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
         ];
         assert!(DelegationRatio::new(parts, pools).is_none());
     }
 
     #[test]
     pub fn delegation_ratio_zero_pool_parts() {
-        let fake_pool_id = StakePoolBuilder::new().build().id();
         let parts = 8u8;
         let pools: Vec<(PoolId, u8)> = vec![
-            (fake_pool_id.clone(), 0u8),
-            (fake_pool_id.clone(), 3u8),
-            (fake_pool_id, 3u8),
+            (StakePoolBuilder::new().build().id(), 0u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
         ];
         assert!(DelegationRatio::new(parts, pools).is_none());
     }
@@ -519,22 +531,21 @@ mod tests {
 
     #[test]
     pub fn delegation_ratio_pool_parts_larger_than_limit() {
-        let fake_pool_id = StakePoolBuilder::new().build().id();
         let parts = (DELEGATION_RATIO_MAX_DECLS + 1) as u8;
-        let pools: Vec<(PoolId, u8)> = iter::from_fn(|| Some((fake_pool_id.clone(), 1u8)))
-            .take(parts as usize)
-            .collect();
+        let pools: Vec<(PoolId, u8)> =
+            iter::from_fn(|| Some((StakePoolBuilder::new().build().id(), 1u8)))
+                .take(parts as usize)
+                .collect();
         assert!(DelegationRatio::new(parts, pools).is_none());
     }
 
     #[test]
     pub fn delegation_ratio_different_total() {
-        let fake_pool_id = StakePoolBuilder::new().build().id();
         let parts = 8u8;
         let pools: Vec<(PoolId, u8)> = vec![
-            (fake_pool_id.clone(), 3u8),
-            (fake_pool_id.clone(), 3u8),
-            (fake_pool_id, 3u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
         ];
         assert!(DelegationRatio::new(parts, pools).is_none());
     }
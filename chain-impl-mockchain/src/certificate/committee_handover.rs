@@ -0,0 +1,130 @@
+use crate::certificate::vote_plan::read_committee_public_keys;
+use crate::transaction::{SingleAccountBindingSignature, TransactionBindingAuthData};
+use crate::vote::CommitteeId;
+use crate::{
+    certificate::{CertificateSlice, VotePlanId},
+    transaction::{Payload, PayloadAuthData, PayloadData, PayloadSlice},
+};
+use chain_core::{
+    mempack::{ReadBuf, ReadError, Readable},
+    property,
+};
+use chain_crypto::Verification;
+use chain_vote::MemberPublicKey;
+use typed_bytes::{ByteArray, ByteBuilder};
+
+#[derive(Debug, Clone)]
+pub struct CommitteeHandoverProof {
+    pub id: CommitteeId,
+    pub signature: SingleAccountBindingSignature,
+}
+
+/// Hand the decryption of the private tally of a vote plan over to a new
+/// committee, whose members received shares of the previous members' keys
+/// (see `chain_vote::OpeningVoteKey::reshare`).
+///
+/// The new member public keys replace the ones of the vote plan for the
+/// tallies that follow, the proposals with a committee of their own keep it.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct CommitteeHandover {
+    id: VotePlanId,
+    committee_public_keys: Vec<MemberPublicKey>,
+}
+
+impl CommitteeHandoverProof {
+    pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
+        bb.bytes(self.id.as_ref()).bytes(self.signature.as_ref())
+    }
+
+    pub fn verify<'a>(&self, verify_data: &TransactionBindingAuthData<'a>) -> Verification {
+        let pk = self.id.public_key();
+        self.signature.verify_slice(&pk, verify_data)
+    }
+}
+
+impl CommitteeHandover {
+    pub fn new(id: VotePlanId, committee_public_keys: Vec<MemberPublicKey>) -> Self {
+        Self {
+            id,
+            committee_public_keys,
+        }
+    }
+
+    pub fn id(&self) -> &VotePlanId {
+        &self.id
+    }
+
+    pub fn committee_public_keys(&self) -> &[MemberPublicKey] {
+        &self.committee_public_keys
+    }
+
+    pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
+        bb.bytes(self.id().as_ref())
+            .iter8(self.committee_public_keys.iter(), |bb, key| {
+                bb.bytes(key.to_bytes().as_ref())
+            })
+    }
+
+    pub fn serialize(&self) -> ByteArray<Self> {
+        self.serialize_in(ByteBuilder::new()).finalize()
+    }
+}
+
+/* Auth/Payload ************************************************************* */
+
+impl Payload for CommitteeHandover {
+    const HAS_DATA: bool = true;
+    const HAS_AUTH: bool = true;
+    type Auth = CommitteeHandoverProof;
+
+    fn payload_data(&self) -> PayloadData<Self> {
+        PayloadData(
+            self.serialize_in(ByteBuilder::new())
+                .finalize_as_vec()
+                .into(),
+            std::marker::PhantomData,
+        )
+    }
+
+    fn payload_auth_data(auth: &Self::Auth) -> PayloadAuthData<Self> {
+        PayloadAuthData(
+            auth.serialize_in(ByteBuilder::new())
+                .finalize_as_vec()
+                .into(),
+            std::marker::PhantomData,
+        )
+    }
+
+    fn to_certificate_slice(p: PayloadSlice<'_, Self>) -> Option<CertificateSlice<'_>> {
+        Some(CertificateSlice::from(p))
+    }
+}
+
+/* Ser/De ******************************************************************* */
+
+impl property::Serialize for CommitteeHandover {
+    type Error = std::io::Error;
+    fn serialize<W: std::io::Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        writer.write_all(self.serialize().as_slice())?;
+        Ok(())
+    }
+}
+
+impl Readable for CommitteeHandoverProof {
+    fn read<'a>(buf: &mut ReadBuf<'a>) -> Result<Self, ReadError> {
+        let id = CommitteeId::read(buf)?;
+        let signature = SingleAccountBindingSignature::read(buf)?;
+        Ok(Self { id, signature })
+    }
+}
+
+impl Readable for CommitteeHandover {
+    fn read<'a>(buf: &mut ReadBuf<'a>) -> Result<Self, ReadError> {
+        let id = <[u8; 32]>::read(buf)?.into();
+        let committee_public_keys = read_committee_public_keys(buf)?;
+        Ok(Self {
+            id,
+            committee_public_keys,
+        })
+    }
+}
@@ -1,27 +1,33 @@
+mod committee_handover;
 mod delegation;
 mod encrypted_vote_tally;
 mod pool;
 mod vote_cast;
+mod vote_cast_batch;
 mod vote_plan;
 mod vote_tally;
 
 #[cfg(any(test, feature = "property-test-api"))]
 mod test;
 
+use crate::fee::FeeAlgorithm;
 use crate::transaction::{Payload, PayloadData, PayloadSlice};
+use crate::value::Value;
 
+pub use self::committee_handover::{CommitteeHandover, CommitteeHandoverProof};
 pub use self::encrypted_vote_tally::{EncryptedVoteTally, EncryptedVoteTallyProof};
 pub use self::vote_cast::VoteCast;
+pub use self::vote_cast_batch::VoteCastBatch;
 pub use self::vote_plan::{
     ExternalProposalDocument, ExternalProposalId, Proposal, Proposals, PushProposal, VoteAction,
-    VotePlan, VotePlanId, VotePlanProof,
+    VotePlan, VotePlanId, VotePlanProof, VotePlanVersion,
 };
 pub use self::vote_tally::{TallyDecryptShares, TallyProof, VoteTally, VoteTallyPayload};
 pub use delegation::{OwnerStakeDelegation, StakeDelegation};
 pub use pool::{
-    GenesisPraosLeaderHash, IndexSignatures, ManagementThreshold, PoolId, PoolOwnersSigned,
-    PoolPermissions, PoolRegistration, PoolRegistrationHash, PoolRetirement, PoolSignature,
-    PoolUpdate,
+    GenesisPraosLeaderHash, IndexSignatures, ManagementThreshold, PoolId, PoolOwnersSignatureError,
+    PoolOwnersSigned, PoolOwnersSignedBuilder, PoolPermissions, PoolRegistration,
+    PoolRegistrationHash, PoolRetirement, PoolSignature, PoolUpdate,
 };
 
 pub enum CertificateSlice<'a> {
@@ -32,8 +38,10 @@ pub enum CertificateSlice<'a> {
     PoolUpdate(PayloadSlice<'a, PoolUpdate>),
     VotePlan(PayloadSlice<'a, VotePlan>),
     VoteCast(PayloadSlice<'a, VoteCast>),
+    VoteCastBatch(PayloadSlice<'a, VoteCastBatch>),
     VoteTally(PayloadSlice<'a, VoteTally>),
     EncryptedVoteTally(PayloadSlice<'a, EncryptedVoteTally>),
+    CommitteeHandover(PayloadSlice<'a, CommitteeHandover>),
 }
 
 impl<'a> From<PayloadSlice<'a, StakeDelegation>> for CertificateSlice<'a> {
@@ -77,6 +85,12 @@ impl<'a> From<PayloadSlice<'a, VoteCast>> for CertificateSlice<'a> {
     }
 }
 
+impl<'a> From<PayloadSlice<'a, VoteCastBatch>> for CertificateSlice<'a> {
+    fn from(payload: PayloadSlice<'a, VoteCastBatch>) -> CertificateSlice<'a> {
+        CertificateSlice::VoteCastBatch(payload)
+    }
+}
+
 impl<'a> From<PayloadSlice<'a, VoteTally>> for CertificateSlice<'a> {
     fn from(payload: PayloadSlice<'a, VoteTally>) -> CertificateSlice<'a> {
         CertificateSlice::VoteTally(payload)
@@ -89,6 +103,12 @@ impl<'a> From<PayloadSlice<'a, EncryptedVoteTally>> for CertificateSlice<'a> {
     }
 }
 
+impl<'a> From<PayloadSlice<'a, CommitteeHandover>> for CertificateSlice<'a> {
+    fn from(payload: PayloadSlice<'a, CommitteeHandover>) -> CertificateSlice<'a> {
+        CertificateSlice::CommitteeHandover(payload)
+    }
+}
+
 impl<'a> CertificateSlice<'a> {
     pub fn into_owned(self) -> Certificate {
         match self {
@@ -103,10 +123,14 @@ impl<'a> CertificateSlice<'a> {
             }
             CertificateSlice::VotePlan(c) => Certificate::VotePlan(c.into_payload()),
             CertificateSlice::VoteCast(c) => Certificate::VoteCast(c.into_payload()),
+            CertificateSlice::VoteCastBatch(c) => Certificate::VoteCastBatch(c.into_payload()),
             CertificateSlice::VoteTally(c) => Certificate::VoteTally(c.into_payload()),
             CertificateSlice::EncryptedVoteTally(c) => {
                 Certificate::EncryptedVoteTally(c.into_payload())
             }
+            CertificateSlice::CommitteeHandover(c) => {
+                Certificate::CommitteeHandover(c.into_payload())
+            }
         }
     }
 }
@@ -120,8 +144,10 @@ pub enum CertificatePayload {
     PoolUpdate(PayloadData<PoolUpdate>),
     VotePlan(PayloadData<VotePlan>),
     VoteCast(PayloadData<VoteCast>),
+    VoteCastBatch(PayloadData<VoteCastBatch>),
     VoteTally(PayloadData<VoteTally>),
     EncryptedVoteTally(PayloadData<EncryptedVoteTally>),
+    CommitteeHandover(PayloadData<CommitteeHandover>),
 }
 
 impl CertificatePayload {
@@ -134,8 +160,26 @@ impl CertificatePayload {
             CertificatePayload::PoolUpdate(payload) => payload.borrow().into(),
             CertificatePayload::VotePlan(payload) => payload.borrow().into(),
             CertificatePayload::VoteCast(payload) => payload.borrow().into(),
+            CertificatePayload::VoteCastBatch(payload) => payload.borrow().into(),
             CertificatePayload::VoteTally(payload) => payload.borrow().into(),
             CertificatePayload::EncryptedVoteTally(payload) => payload.borrow().into(),
+            CertificatePayload::CommitteeHandover(payload) => payload.borrow().into(),
+        }
+    }
+
+    pub fn serialized_size(&self) -> usize {
+        match self {
+            CertificatePayload::StakeDelegation(payload) => payload.0.len(),
+            CertificatePayload::OwnerStakeDelegation(payload) => payload.0.len(),
+            CertificatePayload::PoolRegistration(payload) => payload.0.len(),
+            CertificatePayload::PoolRetirement(payload) => payload.0.len(),
+            CertificatePayload::PoolUpdate(payload) => payload.0.len(),
+            CertificatePayload::VotePlan(payload) => payload.0.len(),
+            CertificatePayload::VoteCast(payload) => payload.0.len(),
+            CertificatePayload::VoteCastBatch(payload) => payload.0.len(),
+            CertificatePayload::VoteTally(payload) => payload.0.len(),
+            CertificatePayload::EncryptedVoteTally(payload) => payload.0.len(),
+            CertificatePayload::CommitteeHandover(payload) => payload.0.len(),
         }
     }
 }
@@ -160,12 +204,18 @@ impl<'a> From<&'a Certificate> for CertificatePayload {
             }
             Certificate::VotePlan(payload) => CertificatePayload::VotePlan(payload.payload_data()),
             Certificate::VoteCast(payload) => CertificatePayload::VoteCast(payload.payload_data()),
+            Certificate::VoteCastBatch(payload) => {
+                CertificatePayload::VoteCastBatch(payload.payload_data())
+            }
             Certificate::VoteTally(payload) => {
                 CertificatePayload::VoteTally(payload.payload_data())
             }
             Certificate::EncryptedVoteTally(payload) => {
                 CertificatePayload::EncryptedVoteTally(payload.payload_data())
             }
+            Certificate::CommitteeHandover(payload) => {
+                CertificatePayload::CommitteeHandover(payload.payload_data())
+            }
         }
     }
 }
@@ -180,8 +230,10 @@ pub enum Certificate {
     PoolUpdate(PoolUpdate),
     VotePlan(VotePlan),
     VoteCast(VoteCast),
+    VoteCastBatch(VoteCastBatch),
     VoteTally(VoteTally),
     EncryptedVoteTally(EncryptedVoteTally),
+    CommitteeHandover(CommitteeHandover),
 }
 
 impl From<StakeDelegation> for Certificate {
@@ -226,6 +278,12 @@ impl From<VoteCast> for Certificate {
     }
 }
 
+impl From<VoteCastBatch> for Certificate {
+    fn from(vote_cast_batch: VoteCastBatch) -> Self {
+        Self::VoteCastBatch(vote_cast_batch)
+    }
+}
+
 impl From<VoteTally> for Certificate {
     fn from(vote_tally: VoteTally) -> Self {
         Self::VoteTally(vote_tally)
@@ -238,6 +296,12 @@ impl From<EncryptedVoteTally> for Certificate {
     }
 }
 
+impl From<CommitteeHandover> for Certificate {
+    fn from(handover: CommitteeHandover) -> Self {
+        Self::CommitteeHandover(handover)
+    }
+}
+
 impl Certificate {
     pub fn need_auth(&self) -> bool {
         match self {
@@ -248,10 +312,24 @@ impl Certificate {
             Certificate::OwnerStakeDelegation(_) => <OwnerStakeDelegation as Payload>::HAS_AUTH,
             Certificate::VotePlan(_) => <VotePlan as Payload>::HAS_AUTH,
             Certificate::VoteCast(_) => <VoteCast as Payload>::HAS_AUTH,
+            Certificate::VoteCastBatch(_) => <VoteCastBatch as Payload>::HAS_AUTH,
             Certificate::VoteTally(_) => <VoteTally as Payload>::HAS_AUTH,
             Certificate::EncryptedVoteTally(_) => <EncryptedVoteTally as Payload>::HAS_AUTH,
+            Certificate::CommitteeHandover(_) => <CommitteeHandover as Payload>::HAS_AUTH,
         }
     }
+
+    /// size in bytes of the certificate once serialized in a transaction
+    /// payload, excluding its authentication data
+    pub fn serialized_size(&self) -> usize {
+        CertificatePayload::from(self).serialized_size()
+    }
+
+    /// fee charged for the certificate itself, on top of the baseline
+    /// and the inputs/outputs part of the transaction fee
+    pub fn fee<F: FeeAlgorithm>(&self, fee_algorithm: &F) -> Value {
+        fee_algorithm.fees_for_certificate(CertificatePayload::from(self).as_slice())
+    }
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -266,15 +344,27 @@ pub enum SignedCertificate {
     PoolRetirement(PoolRetirement, <PoolRetirement as Payload>::Auth),
     PoolUpdate(PoolUpdate, <PoolUpdate as Payload>::Auth),
     VotePlan(VotePlan, <VotePlan as Payload>::Auth),
+    VoteCast(VoteCast, <VoteCast as Payload>::Auth),
+    VoteCastBatch(VoteCastBatch, <VoteCastBatch as Payload>::Auth),
     VoteTally(VoteTally, <VoteTally as Payload>::Auth),
     EncryptedVoteTally(EncryptedVoteTally, <EncryptedVoteTally as Payload>::Auth),
+    CommitteeHandover(CommitteeHandover, <CommitteeHandover as Payload>::Auth),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fee::{LinearFee, PerCertificateFee};
+    use crate::testing::{
+        builders::{
+            build_stake_delegation_cert, build_stake_pool_registration_cert, StakePoolBuilder,
+        },
+        data::AddressData,
+    };
+    use chain_addr::Discrimination;
     use quickcheck::TestResult;
     use quickcheck_macros::quickcheck;
+    use std::num::NonZeroU64;
 
     #[quickcheck]
     pub fn need_auth(certificate: Certificate) -> TestResult {
@@ -286,9 +376,43 @@ mod tests {
             Certificate::OwnerStakeDelegation(_) => false,
             Certificate::VotePlan(_) => true,
             Certificate::VoteCast(_) => false,
+            Certificate::VoteCastBatch(_) => false,
             Certificate::VoteTally(_) => true,
             Certificate::EncryptedVoteTally(_) => true,
+            Certificate::CommitteeHandover(_) => true,
         };
         TestResult::from_bool(certificate.need_auth() == expected_result)
     }
+
+    #[quickcheck]
+    pub fn serialized_size_of_stake_delegation(stake_delegation: StakeDelegation) -> bool {
+        Certificate::StakeDelegation(stake_delegation.clone()).serialized_size()
+            == stake_delegation.serialize().as_slice().len()
+    }
+
+    #[quickcheck]
+    pub fn serialized_size_of_pool_registration(pool_registration: PoolRegistration) -> bool {
+        Certificate::PoolRegistration(pool_registration.clone()).serialized_size()
+            == pool_registration.serialize().as_slice().len()
+    }
+
+    #[test]
+    pub fn fee_depends_on_certificate_kind() {
+        let stake_pool = StakePoolBuilder::new().build();
+        let account = AddressData::account(Discrimination::Test);
+        let stake_delegation = build_stake_delegation_cert(&stake_pool.info(), &account);
+        let pool_registration = build_stake_pool_registration_cert(&stake_pool.info());
+
+        let mut fee = LinearFee::new(1, 1, 1);
+        assert_eq!(stake_delegation.fee(&fee), Value(1));
+        assert_eq!(pool_registration.fee(&fee), Value(1));
+
+        fee.per_certificate_fees(PerCertificateFee::new(
+            NonZeroU64::new(500),
+            NonZeroU64::new(2),
+            None,
+        ));
+        assert_eq!(stake_delegation.fee(&fee), Value(2));
+        assert_eq!(pool_registration.fee(&fee), Value(500));
+    }
 }
@@ -1,5 +1,7 @@
 use super::CertificateSlice;
-use crate::key::{deserialize_public_key, deserialize_signature, GenesisPraosLeader};
+use crate::key::{
+    deserialize_public_key, deserialize_signature, EitherEd25519SecretKey, GenesisPraosLeader,
+};
 use crate::rewards::TaxType;
 use crate::transaction::{
     AccountIdentifier, Payload, PayloadAuthData, PayloadData, PayloadSlice,
@@ -12,6 +14,7 @@ use chain_core::{
 use chain_crypto::{digest::DigestOf, Blake2b256, Ed25519, PublicKey, Verification};
 use chain_time::{DurationSeconds, TimeOffsetSeconds};
 use std::marker::PhantomData;
+use thiserror::Error;
 use typed_bytes::{ByteArray, ByteBuilder};
 
 /// Pool ID
@@ -108,6 +111,32 @@ pub struct PoolOwnersSignature {
 
 pub type PoolOwnersSigned = PoolOwnersSignature;
 
+/// Reasons for a pool's owners signature to be rejected
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PoolOwnersSignatureError {
+    #[error("Not enough owners signatures, {actual} while {expected} are needed")]
+    NotEnoughSignatures { actual: usize, expected: usize },
+    #[error("Signature index {index} does not match any of the {owners} pool owners")]
+    IndexOutOfBound { index: u8, owners: usize },
+    #[error("Owner {index} signed more than once")]
+    DuplicateOwner { index: u8 },
+    #[error("Invalid signature from owner {index}")]
+    InvalidSignature { index: u8 },
+    #[error("Signing key is not one of the pool owners")]
+    NotAnOwner,
+}
+
+/// Collect the signatures of a pool's owners over the transaction
+/// binding data of a pool management transaction (update, retirement)
+///
+/// signatures are kept ordered by owner index, whatever the order in
+/// which they are added
+pub struct PoolOwnersSignedBuilder<'a> {
+    pool_info: &'a PoolRegistration,
+    auth_data: TransactionBindingAuthData<'a>,
+    signatures: IndexSignatures,
+}
+
 impl PoolRegistration {
     pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
         let bb = bb
@@ -372,29 +401,62 @@ impl PoolOwnersSignature {
         })
     }
 
+    /// start collecting the owners signatures of the given pool over
+    /// the given transaction binding data
+    pub fn builder<'a>(
+        pool_info: &'a PoolRegistration,
+        auth_data: TransactionBindingAuthData<'a>,
+    ) -> PoolOwnersSignedBuilder<'a> {
+        PoolOwnersSignedBuilder {
+            pool_info,
+            auth_data,
+            signatures: Vec::new(),
+        }
+    }
+
     pub fn verify<'a>(
         &self,
         pool_info: &PoolRegistration,
         verify_data: &TransactionBindingAuthData<'a>,
     ) -> Verification {
+        match self.check(pool_info, verify_data) {
+            Ok(()) => Verification::Success,
+            Err(_) => Verification::Failed,
+        }
+    }
+
+    /// same as `verify` but reports why the signatures are rejected
+    pub fn check<'a>(
+        &self,
+        pool_info: &PoolRegistration,
+        verify_data: &TransactionBindingAuthData<'a>,
+    ) -> Result<(), PoolOwnersSignatureError> {
+        let expected = pool_info.management_threshold() as usize;
+
         // fast track if we don't meet the management threshold already
-        if self.signatures.len() < pool_info.management_threshold() as usize {
-            return Verification::Failed;
+        if self.signatures.len() < expected {
+            return Err(PoolOwnersSignatureError::NotEnoughSignatures {
+                actual: self.signatures.len(),
+                expected,
+            });
         }
 
         let mut present = vec![false; pool_info.owners.len()];
         let mut signatories = 0;
 
-        for (i, sig) in self.signatures.iter() {
-            let i = *i as usize;
+        for (index, sig) in self.signatures.iter() {
+            let i = *index as usize;
             // Check for out of bounds indices
             if i >= pool_info.owners.len() {
-                return Verification::Failed;
+                return Err(PoolOwnersSignatureError::IndexOutOfBound {
+                    index: *index,
+                    owners: pool_info.owners.len(),
+                });
             }
 
             // If already present, then we have a duplicate hence fail
             if present[i] {
-                return Verification::Failed;
+                return Err(PoolOwnersSignatureError::DuplicateOwner { index: *index });
             } else {
                 present[i] = true;
             }
@@ -402,18 +464,73 @@ impl PoolOwnersSignature {
             // Verify the cryptographic signature of a signatory
             let pk = &pool_info.owners[i];
             if sig.verify_slice(pk, verify_data) == Verification::Failed {
-                return Verification::Failed;
+                return Err(PoolOwnersSignatureError::InvalidSignature { index: *index });
             }
             signatories += 1
         }
 
         // check if we seen enough unique signatures; it is a redundant check
         // from the duplicated check + the threshold check
-        if signatories < pool_info.management_threshold() as usize {
-            return Verification::Failed;
+        if signatories < expected {
+            return Err(PoolOwnersSignatureError::NotEnoughSignatures {
+                actual: signatories,
+                expected,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> PoolOwnersSignedBuilder<'a> {
+    /// sign with the secret key of one of the pool owners
+    pub fn sign(
+        &mut self,
+        key: &EitherEd25519SecretKey,
+    ) -> Result<&mut Self, PoolOwnersSignatureError> {
+        let pk = key.to_public();
+        let index = self
+            .pool_info
+            .owners
+            .iter()
+            .position(|owner| owner == &pk)
+            .ok_or(PoolOwnersSignatureError::NotAnOwner)?;
+        let sig = SingleAccountBindingSignature::new(&self.auth_data, |d| key.sign_slice(d.0));
+        self.add_signature(index as u8, sig)
+    }
+
+    /// add a signature made by the owner at the given index, for example
+    /// when the owners sign on different machines
+    pub fn add_signature(
+        &mut self,
+        index: u8,
+        sig: SingleAccountBindingSignature,
+    ) -> Result<&mut Self, PoolOwnersSignatureError> {
+        let pk = self.pool_info.owners.get(index as usize).ok_or(
+            PoolOwnersSignatureError::IndexOutOfBound {
+                index,
+                owners: self.pool_info.owners.len(),
+            },
+        )?;
+        if sig.verify_slice(pk, &self.auth_data) == Verification::Failed {
+            return Err(PoolOwnersSignatureError::InvalidSignature { index });
+        }
+        match self.signatures.binary_search_by_key(&index, |(i, _)| *i) {
+            Ok(_) => Err(PoolOwnersSignatureError::DuplicateOwner { index }),
+            Err(position) => {
+                self.signatures.insert(position, (index, sig));
+                Ok(self)
+            }
         }
+    }
 
-        Verification::Success
+    /// the collected signatures, if they meet the pool's management threshold
+    pub fn build(&self) -> Result<PoolOwnersSigned, PoolOwnersSignatureError> {
+        let signed = PoolOwnersSigned {
+            signatures: self.signatures.clone(),
+        };
+        signed.check(self.pool_info, &self.auth_data)?;
+        Ok(signed)
     }
 }
 
@@ -451,7 +568,7 @@ impl Readable for PoolSignature {
 #[cfg(test)]
 mod tests {
 
-    use super::{PoolOwnersSigned, PoolPermissions};
+    use super::{PoolOwnersSignatureError, PoolOwnersSigned, PoolPermissions};
     use crate::{
         chaintypes::HeaderId,
         key::EitherEd25519SecretKey,
@@ -722,6 +839,120 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn pool_owners_builder_enough_signatures() {
+        let owners: Vec<AddressData> =
+            iter::from_fn(|| Some(AddressData::account(Discrimination::Test)))
+                .take(4)
+                .collect();
+        let pool_owners = PoolOwnersWithSignatures::new(owners.clone(), vec![]);
+        let stake_pool = StakePoolBuilder::new()
+            .with_owners(pool_owners.owners_pks())
+            .with_pool_permissions(PoolPermissions::new(2))
+            .build();
+        let pool_info = stake_pool.info();
+        let builder = TxBuilder::new()
+            .set_payload(&NoExtra)
+            .set_ios(&[], &[])
+            .set_witnesses(&[]);
+        let auth_data = builder.get_auth_data();
+
+        let mut owners_signed = PoolOwnersSigned::builder(&pool_info, auth_data.clone());
+        owners_signed.sign(&owners[3].private_key()).unwrap();
+        assert_eq!(
+            owners_signed.build().unwrap_err(),
+            PoolOwnersSignatureError::NotEnoughSignatures {
+                actual: 1,
+                expected: 2
+            }
+        );
+
+        let signed = owners_signed
+            .sign(&owners[1].private_key())
+            .unwrap()
+            .build()
+            .unwrap();
+        let indices: Vec<u8> = signed.signatures.iter().map(|(i, _)| *i).collect();
+        assert_eq!(indices, vec![1, 3]);
+        assert_eq!(signed.check(&pool_info, &auth_data), Ok(()));
+        assert_eq!(signed.verify(&pool_info, &auth_data), Verification::Success);
+    }
+
+    #[test]
+    pub fn pool_owners_builder_duplicate_owner() {
+        let owners: Vec<AddressData> =
+            iter::from_fn(|| Some(AddressData::account(Discrimination::Test)))
+                .take(2)
+                .collect();
+        let pool_owners = PoolOwnersWithSignatures::new(owners.clone(), vec![]);
+        let stake_pool = StakePoolBuilder::new()
+            .with_owners(pool_owners.owners_pks())
+            .with_pool_permissions(PoolPermissions::new(2))
+            .build();
+        let pool_info = stake_pool.info();
+        let builder = TxBuilder::new()
+            .set_payload(&NoExtra)
+            .set_ios(&[], &[])
+            .set_witnesses(&[]);
+        let auth_data = builder.get_auth_data();
+
+        let mut owners_signed = PoolOwnersSigned::builder(&pool_info, auth_data.clone());
+        owners_signed.sign(&owners[0].private_key()).unwrap();
+        assert_eq!(
+            owners_signed.sign(&owners[0].private_key()).err(),
+            Some(PoolOwnersSignatureError::DuplicateOwner { index: 0 })
+        );
+
+        let key = owners[0].private_key();
+        let sig = SingleAccountBindingSignature::new(&auth_data, |d| key.sign_slice(d.0));
+        let signed = PoolOwnersSigned {
+            signatures: vec![(0, sig.clone()), (0, sig)],
+        };
+        assert_eq!(
+            signed.check(&pool_info, &auth_data),
+            Err(PoolOwnersSignatureError::DuplicateOwner { index: 0 })
+        );
+    }
+
+    #[test]
+    pub fn pool_owners_builder_signature_from_non_owner() {
+        let owners: Vec<AddressData> =
+            iter::from_fn(|| Some(AddressData::account(Discrimination::Test)))
+                .take(2)
+                .collect();
+        let pool_owners = PoolOwnersWithSignatures::new(owners.clone(), vec![]);
+        let stake_pool = StakePoolBuilder::new()
+            .with_owners(pool_owners.owners_pks())
+            .with_pool_permissions(PoolPermissions::new(1))
+            .build();
+        let pool_info = stake_pool.info();
+        let builder = TxBuilder::new()
+            .set_payload(&NoExtra)
+            .set_ios(&[], &[])
+            .set_witnesses(&[]);
+        let auth_data = builder.get_auth_data();
+
+        let outsider = AddressData::account(Discrimination::Test).private_key();
+        let mut owners_signed = PoolOwnersSigned::builder(&pool_info, auth_data.clone());
+        assert_eq!(
+            owners_signed.sign(&outsider).err(),
+            Some(PoolOwnersSignatureError::NotAnOwner)
+        );
+
+        let sig = SingleAccountBindingSignature::new(&auth_data, |d| outsider.sign_slice(d.0));
+        assert_eq!(
+            owners_signed.add_signature(1, sig.clone()).err(),
+            Some(PoolOwnersSignatureError::InvalidSignature { index: 1 })
+        );
+        let signed = PoolOwnersSigned {
+            signatures: vec![(1, sig)],
+        };
+        assert_eq!(
+            signed.check(&pool_info, &auth_data),
+            Err(PoolOwnersSignatureError::InvalidSignature { index: 1 })
+        );
+    }
+
     /// For given pool_owner_with_sign (which contains pool registration owners and subset of signatures derived from them)
     /// and given pool_permissions limit it tests verify method for PoolOwnersSigned struct
     fn test_verify(
@@ -2,7 +2,7 @@ use super::*;
 use crate::accounting::account::DelegationType;
 use crate::block::BlockDate;
 use crate::ledger::governance::TreasuryGovernanceAction;
-use crate::rewards::TaxType;
+use crate::rewards::{Ratio, TaxType};
 use crate::vote;
 #[cfg(test)]
 use chain_core::mempack::{ReadBuf, Readable};
@@ -127,8 +127,19 @@ impl Arbitrary for PoolRegistration {
 
 impl Arbitrary for TreasuryGovernanceAction {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
-        TreasuryGovernanceAction::TransferToRewards {
-            value: Arbitrary::arbitrary(g),
+        match u8::arbitrary(g) % 3 {
+            0 => TreasuryGovernanceAction::TransferToRewards {
+                value: Arbitrary::arbitrary(g),
+            },
+            1 => TreasuryGovernanceAction::TransferToAccount {
+                account: Arbitrary::arbitrary(g),
+                value: Arbitrary::arbitrary(g),
+            },
+            _ => TreasuryGovernanceAction::TransferToAccounts {
+                payees: (0..u8::arbitrary(g) % 4)
+                    .map(|_| (Arbitrary::arbitrary(g), Arbitrary::arbitrary(g)))
+                    .collect(),
+            },
         }
     }
 }
@@ -145,27 +156,57 @@ impl Arbitrary for VoteAction {
 
 impl Arbitrary for Proposal {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
-        let external_id = ExternalProposalId::arbitrary(g);
-        let funding_plan = vote::Options::arbitrary(g);
-        let action = VoteAction::arbitrary(g);
+        arbitrary_proposal(g, VotePlanVersion::LATEST)
+    }
+}
 
-        Self::new(external_id, funding_plan, action)
+/// a proposal only using what a vote plan of the given version can encode
+fn arbitrary_proposal<G: Gen>(g: &mut G, version: VotePlanVersion) -> Proposal {
+    let external_id = ExternalProposalId::arbitrary(g);
+    let funding_plan = vote::Options::arbitrary(g);
+    let action = VoteAction::arbitrary(g);
+
+    let mut proposal = Proposal::new(external_id, funding_plan, action);
+    if version != VotePlanVersion::V1 {
+        if bool::arbitrary(g) {
+            proposal = proposal.with_payload_type(vote::PayloadType::arbitrary(g));
+        }
+        if bool::arbitrary(g) {
+            proposal = proposal.with_quorum(Ratio::arbitrary(g));
+        }
+        if bool::arbitrary(g) {
+            proposal = proposal.with_approval_threshold(Ratio::arbitrary(g));
+        }
+        if bool::arbitrary(g) {
+            proposal = proposal.with_tie_break(vote::TieBreak::LowestOption);
+        }
     }
+    proposal
 }
 
 impl Arbitrary for Proposals {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
-        let len = usize::arbitrary(g) % Proposals::MAX_LEN;
-        let mut proposals = Proposals::new();
-        for _ in 0..len {
-            if let PushProposal::Success = proposals.push(Proposal::arbitrary(g)) {
-                // pushed successfully
-            } else {
-                unreachable!("only generates what is needed")
-            }
+        arbitrary_proposals(g, VotePlanVersion::LATEST)
+    }
+}
+
+fn arbitrary_proposals<G: Gen>(g: &mut G, version: VotePlanVersion) -> Proposals {
+    let len = usize::arbitrary(g) % Proposals::MAX_LEN;
+    let mut proposals = Proposals::new();
+    for _ in 0..len {
+        if let PushProposal::Success = proposals.push(arbitrary_proposal(g, version)) {
+            // pushed successfully
+        } else {
+            unreachable!("only generates what is needed")
         }
+    }
 
-        proposals
+    proposals
+}
+
+impl Arbitrary for VotePlanVersion {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        VotePlanVersion::from_u8(u8::arbitrary(g) % 2 + 1).unwrap()
     }
 }
 
@@ -176,7 +217,8 @@ impl Arbitrary for VotePlan {
         let vote_start = BlockDate::arbitrary(g);
         let vote_end = BlockDate::arbitrary(g);
         let committee_end = BlockDate::arbitrary(g);
-        let proposals = Proposals::arbitrary(g);
+        let version = VotePlanVersion::arbitrary(g);
+        let proposals = arbitrary_proposals(g, version);
         let payload_type = vote::PayloadType::arbitrary(g);
 
         let mut keys = Vec::new();
@@ -201,6 +243,7 @@ impl Arbitrary for VotePlan {
             payload_type,
             keys,
         )
+        .with_version(version)
     }
 }
 
@@ -223,6 +266,18 @@ impl Arbitrary for VoteCast {
     }
 }
 
+impl Arbitrary for VoteCastBatch {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        let vote_plan = VotePlanId::arbitrary(g);
+        let ballots_nb = usize::arbitrary(g) % 8;
+        let ballots = (0..ballots_nb)
+            .map(|_| (u8::arbitrary(g), vote::Payload::arbitrary(g)))
+            .collect();
+
+        VoteCastBatch::new(vote_plan, ballots)
+    }
+}
+
 impl Arbitrary for VoteTally {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
         let vote_plan_id = VotePlanId::arbitrary(g);
@@ -255,9 +310,25 @@ impl Arbitrary for EncryptedVoteTallyProof {
     }
 }
 
+impl Arbitrary for CommitteeHandover {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        let plan = VotePlan::arbitrary(g);
+        Self::new(plan.to_id(), plan.committee_public_keys().to_vec())
+    }
+}
+
+impl Arbitrary for CommitteeHandoverProof {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        Self {
+            id: Arbitrary::arbitrary(g),
+            signature: Arbitrary::arbitrary(g),
+        }
+    }
+}
+
 impl Arbitrary for Certificate {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
-        let option = u8::arbitrary(g) % 9;
+        let option = u8::arbitrary(g) % 11;
         match option {
             0 => Certificate::StakeDelegation(Arbitrary::arbitrary(g)),
             1 => Certificate::OwnerStakeDelegation(Arbitrary::arbitrary(g)),
@@ -268,6 +339,8 @@ impl Arbitrary for Certificate {
             6 => Certificate::VoteCast(Arbitrary::arbitrary(g)),
             7 => Certificate::VoteTally(Arbitrary::arbitrary(g)),
             8 => Certificate::EncryptedVoteTally(Arbitrary::arbitrary(g)),
+            9 => Certificate::VoteCastBatch(Arbitrary::arbitrary(g)),
+            10 => Certificate::CommitteeHandover(Arbitrary::arbitrary(g)),
             _ => panic!("unimplemented"),
         }
     }
@@ -283,3 +356,168 @@ fn pool_reg_serialization_bijection(b: PoolRegistration) -> TestResult {
     assert_eq!(buf.get_slice_end(), &[]);
     TestResult::from_bool(left == result)
 }
+
+#[quickcheck]
+fn vote_cast_batch_serialization_bijection(b: VoteCastBatch) -> TestResult {
+    let b_got = b.serialize();
+    let mut buf = ReadBuf::from(b_got.as_ref());
+    let result = VoteCastBatch::read(&mut buf);
+    let left = Ok(b);
+    assert_eq!(left, result);
+    assert_eq!(buf.get_slice_end(), &[]);
+    TestResult::from_bool(left == result)
+}
+
+#[quickcheck]
+fn committee_handover_serialization_bijection(b: CommitteeHandover) -> TestResult {
+    let b_got = b.serialize();
+    let mut buf = ReadBuf::from(b_got.as_ref());
+    let result = CommitteeHandover::read(&mut buf);
+    let left = Ok(b);
+    assert_eq!(left, result);
+    assert_eq!(buf.get_slice_end(), &[]);
+    TestResult::from_bool(left == result)
+}
+
+#[test]
+fn private_vote_tally_serialization_bijection() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_chacha::ChaCha20Rng::from_seed([0u8; 32]);
+    let h = chain_vote::CRS::random(&mut rng);
+    let mc = chain_vote::MemberCommunicationKey::new(&mut rng);
+    let member = chain_vote::MemberState::new(&mut rng, 1, &h, &[mc.to_public()], 0);
+    let ek = chain_vote::EncryptingVoteKey::from_participants(&[member.public_key()]);
+
+    let mut encrypted_tally = chain_vote::EncryptedTally::new(3);
+    let (vote, _) = chain_vote::encrypt_vote(&mut rng, &ek, chain_vote::Vote::new(3, 1));
+    encrypted_tally.add(&vote, 1);
+    let (_, share) = encrypted_tally.finish(&mut rng, member.secret_key());
+
+    // the shares of a subset of the committee keep their member index
+    let shares = TallyDecryptShares::from_members(vec![
+        vec![(0, share.clone()), (2, share.clone())],
+        vec![],
+    ]);
+    let vote_tally = VoteTally::new_private(VotePlanId::from([1u8; 32]), shares);
+
+    let bytes = vote_tally.serialize();
+    let mut buf = ReadBuf::from(bytes.as_ref());
+    let result = VoteTally::read(&mut buf);
+    assert_eq!(Ok(vote_tally), result);
+    assert_eq!(buf.get_slice_end(), &[]);
+}
+
+#[test]
+fn private_vote_tally_without_member_index() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_chacha::ChaCha20Rng::from_seed([0u8; 32]);
+    let h = chain_vote::CRS::random(&mut rng);
+    let mc = chain_vote::MemberCommunicationKey::new(&mut rng);
+    let member = chain_vote::MemberState::new(&mut rng, 1, &h, &[mc.to_public()], 0);
+    let ek = chain_vote::EncryptingVoteKey::from_participants(&[member.public_key()]);
+
+    let mut encrypted_tally = chain_vote::EncryptedTally::new(3);
+    let (vote, _) = chain_vote::encrypt_vote(&mut rng, &ek, chain_vote::Vote::new(3, 1));
+    encrypted_tally.add(&vote, 1);
+    let (_, share) = encrypted_tally.finish(&mut rng, member.secret_key());
+
+    // a private tally tagged with its payload type has a share for every
+    // member, in the committee order, without proofs
+    let share_bytes = share.to_bytes_without_proofs();
+    let mut bytes = vec![1u8; 32];
+    bytes.extend_from_slice(&[vote::PayloadType::Private as u8, 1, 2, 3]);
+    bytes.extend_from_slice(&share_bytes);
+    bytes.extend_from_slice(&share_bytes);
+
+    let mut buf = ReadBuf::from(&bytes);
+    let result = VoteTally::read(&mut buf).unwrap();
+    let share = chain_vote::TallyDecryptShare::from_bytes_without_proofs(&share_bytes).unwrap();
+    let shares = TallyDecryptShares::new(vec![vec![share.clone(), share]]);
+    assert_eq!(
+        VoteTally::new_private(VotePlanId::from([1u8; 32]), shares.clone()),
+        result
+    );
+    assert_eq!(buf.get_slice_end(), &[]);
+    assert_eq!(result.serialize().as_ref(), bytes.as_slice());
+
+    // the shares without proofs are only accepted for the vote plans of the
+    // first version
+    let committee = [member.public_key(), member.public_key()];
+    let encrypted_tallies = [(&committee[..], &encrypted_tally)];
+    assert_eq!(
+        shares.verify(VotePlanVersion::V1, &encrypted_tallies),
+        Ok(())
+    );
+    assert_eq!(
+        shares.verify(VotePlanVersion::V2, &encrypted_tallies),
+        Err(vote::TallyError::InvalidShareProof { member_index: 0 })
+    );
+}
+
+#[test]
+fn tally_decrypt_shares_member_serialization() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_chacha::ChaCha20Rng::from_seed([0u8; 32]);
+    let h = chain_vote::CRS::random(&mut rng);
+    let mc = [
+        chain_vote::MemberCommunicationKey::new(&mut rng),
+        chain_vote::MemberCommunicationKey::new(&mut rng),
+    ];
+    let mc = [mc[0].to_public(), mc[1].to_public()];
+    let members = [
+        chain_vote::MemberState::new(&mut rng, 2, &h, &mc, 0),
+        chain_vote::MemberState::new(&mut rng, 2, &h, &mc, 1),
+    ];
+    let ek = chain_vote::EncryptingVoteKey::from_participants(&[
+        members[0].public_key(),
+        members[1].public_key(),
+    ]);
+
+    let encrypted_tallies = (0..2)
+        .map(|_| {
+            let mut encrypted_tally = chain_vote::EncryptedTally::new(3);
+            let (vote, _) = chain_vote::encrypt_vote(&mut rng, &ek, chain_vote::Vote::new(3, 1));
+            encrypted_tally.add(&vote, 1);
+            encrypted_tally
+        })
+        .collect::<Vec<_>>();
+    let encrypted_tallies = encrypted_tallies.iter().collect::<Vec<_>>();
+    let members_shares = members
+        .iter()
+        .map(|member| chain_vote::finish_all(&mut rng, member.secret_key(), &encrypted_tallies))
+        .collect::<Vec<_>>();
+
+    let serialized = members_shares
+        .iter()
+        .enumerate()
+        .map(|(member_index, shares)| {
+            TallyDecryptShares::serialize_member(member_index as u8, shares).unwrap()
+        })
+        .collect::<Vec<_>>();
+    let in_memory = TallyDecryptShares::new(
+        (0..encrypted_tallies.len())
+            .map(|proposal| {
+                members_shares
+                    .iter()
+                    .map(|shares| shares[proposal].clone())
+                    .collect()
+            })
+            .collect(),
+    );
+    assert_eq!(
+        TallyDecryptShares::from_serialized(serialized.clone()),
+        Ok(in_memory)
+    );
+
+    // the members must provide shares for the same proposals
+    let partial = TallyDecryptShares::serialize_member(1, &members_shares[1][..1]).unwrap();
+    assert!(TallyDecryptShares::from_serialized(vec![serialized[0].clone(), partial]).is_err());
+
+    // trailing bytes are rejected
+    let mut trailing = serialized[0].clone();
+    trailing.push(0);
+    assert!(TallyDecryptShares::from_serialized(vec![trailing]).is_err());
+}
@@ -0,0 +1,111 @@
+use crate::{
+    certificate::{CertificateSlice, VoteCast, VotePlanId},
+    transaction::{Payload, PayloadAuthData, PayloadData, PayloadSlice},
+    vote,
+};
+use chain_core::{
+    mempack::{ReadBuf, ReadError, Readable},
+    property,
+};
+use typed_bytes::{ByteArray, ByteBuilder};
+
+/// ballots cast by the same voter on several proposals of the same
+/// vote plan, carried by a single transaction
+///
+/// the ballots are applied all together or not at all
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VoteCastBatch {
+    vote_plan: VotePlanId,
+    ballots: Vec<(u8, vote::Payload)>,
+}
+
+impl VoteCastBatch {
+    pub fn new(vote_plan: VotePlanId, ballots: Vec<(u8, vote::Payload)>) -> Self {
+        Self { vote_plan, ballots }
+    }
+
+    pub fn vote_plan(&self) -> &VotePlanId {
+        &self.vote_plan
+    }
+
+    pub fn ballots(&self) -> &[(u8, vote::Payload)] {
+        &self.ballots
+    }
+
+    /// a batch is well formed if it holds at least one ballot and
+    /// no more than one ballot per proposal
+    pub fn check_ballots(&self) -> bool {
+        let mut indices: Vec<u8> = self.ballots.iter().map(|(index, _)| *index).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        !self.ballots.is_empty() && indices.len() == self.ballots.len()
+    }
+
+    /// split the batch into the individual vote casts, in order
+    pub fn vote_casts(&self) -> impl Iterator<Item = VoteCast> + '_ {
+        self.ballots.iter().map(move |(index, payload)| {
+            VoteCast::new(self.vote_plan.clone(), *index, payload.clone())
+        })
+    }
+
+    pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
+        bb.bytes(self.vote_plan.as_ref())
+            .iter8(&mut self.ballots.iter(), |bb, (index, payload)| {
+                payload.serialize_in(bb.u8(*index))
+            })
+    }
+
+    pub fn serialize(&self) -> ByteArray<Self> {
+        self.serialize_in(ByteBuilder::new()).finalize()
+    }
+}
+
+/* Auth/Payload ************************************************************* */
+
+impl Payload for VoteCastBatch {
+    const HAS_DATA: bool = true;
+    const HAS_AUTH: bool = false;
+    type Auth = ();
+
+    fn payload_data(&self) -> PayloadData<Self> {
+        PayloadData(
+            self.serialize_in(ByteBuilder::new())
+                .finalize_as_vec()
+                .into(),
+            std::marker::PhantomData,
+        )
+    }
+
+    fn payload_auth_data(_: &Self::Auth) -> PayloadAuthData<Self> {
+        PayloadAuthData(Vec::with_capacity(0).into(), std::marker::PhantomData)
+    }
+
+    fn to_certificate_slice(p: PayloadSlice<'_, Self>) -> Option<CertificateSlice<'_>> {
+        Some(CertificateSlice::from(p))
+    }
+}
+
+/* Ser/De ******************************************************************* */
+
+impl property::Serialize for VoteCastBatch {
+    type Error = std::io::Error;
+    fn serialize<W: std::io::Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        writer.write_all(self.serialize().as_slice())?;
+        Ok(())
+    }
+}
+
+impl Readable for VoteCastBatch {
+    fn read<'a>(buf: &mut ReadBuf<'a>) -> Result<Self, ReadError> {
+        let vote_plan = <[u8; 32]>::read(buf)?.into();
+        let ballots_nb = buf.get_u8()? as usize;
+        let mut ballots = Vec::with_capacity(ballots_nb);
+        for _ in 0..ballots_nb {
+            let proposal_index = buf.get_u8()?;
+            let payload = vote::Payload::read(buf)?;
+            ballots.push((proposal_index, payload));
+        }
+
+        Ok(Self::new(vote_plan, ballots))
+    }
+}
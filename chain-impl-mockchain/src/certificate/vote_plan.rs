@@ -2,6 +2,7 @@ use crate::{
     block::BlockDate,
     certificate::CertificateSlice,
     ledger::governance::{Governance, ParametersGovernanceAction, TreasuryGovernanceAction},
+    rewards::Ratio,
     transaction::{
         Payload, PayloadAuthData, PayloadData, PayloadSlice, SingleAccountBindingSignature,
         TransactionBindingAuthData,
@@ -13,7 +14,7 @@ use chain_core::{
     property,
 };
 use chain_crypto::{digest::DigestOf, Blake2b256, Verification};
-use chain_vote::MemberPublicKey;
+use chain_vote::{EncryptingVoteKey, MemberPublicKey};
 use std::ops::Deref;
 use typed_bytes::{ByteArray, ByteBuilder};
 
@@ -28,6 +29,31 @@ pub type ExternalProposalId = DigestOf<Blake2b256, ExternalProposalDocument>;
 /// the vote plan identifier on the blockchain
 pub type VotePlanId = DigestOf<Blake2b256, VotePlan>;
 
+/// the layout of a vote plan on chain
+///
+/// All the proposals of a vote plan of the first version are voted with the
+/// payload type of the plan and encrypted for its committee. From the second
+/// version on, a proposal may have its own payload type and committee,
+/// require a quorum and an approval threshold, and choose how a tie is
+/// decided.
+///
+/// The private votes of a vote plan of the first version are encrypted for
+/// the sum of the public keys of the committee members, the decryption shares
+/// of every member being needed to decrypt the tally. From the second version
+/// on, the members hold threshold secret keys and the shares of a threshold
+/// of them are enough.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum VotePlanVersion {
+    V1 = 1,
+    V2 = 2,
+}
+
+/// written in place of the payload type of a vote plan of the first version,
+/// which is never 0, for the vote plans of the later versions. It is
+/// followed by the version of the plan.
+const VERSIONED_LAYOUT_MARKER: u8 = 0;
+
 /// a vote plan for the voting system
 ///
 /// A vote plan defines what is being voted, for how long and how long
@@ -35,6 +61,8 @@ pub type VotePlanId = DigestOf<Blake2b256, VotePlan>;
 ///
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VotePlan {
+    /// the layout of the vote plan
+    version: VotePlanVersion,
     /// the vote start validity
     vote_start: BlockDate,
     /// the duration within which it is possible to vote for one of the proposals
@@ -45,9 +73,9 @@ pub struct VotePlan {
     committee_end: BlockDate,
     /// the proposals to vote for
     proposals: Proposals,
-    /// vote payload type
+    /// vote payload type, unless the proposal has its own
     payload_type: vote::PayloadType,
-    /// encrypting votes public keys
+    /// encrypting votes public keys, unless the proposal has its own
     committee_public_keys: Vec<chain_vote::MemberPublicKey>,
 }
 
@@ -83,11 +111,23 @@ pub struct Proposals {
 /// with its unique identifier and the funding plan required
 /// for the proposal to be operated.
 ///
+/// In a vote plan of the second version, a proposal may be voted with a
+/// different payload type than the other proposals of the vote plan, and
+/// its private votes may be encrypted for a different committee. It may
+/// also require a quorum and an approval threshold on top of the governance
+/// acceptance criteria of its action, and choose how a tie between the
+/// favorable and the rejecting stake is decided.
+///
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Proposal {
     external_id: ExternalProposalId,
     options: vote::Options,
     action: VoteAction,
+    payload_type: Option<vote::PayloadType>,
+    committee_public_keys: Vec<chain_vote::MemberPublicKey>,
+    quorum: Option<Ratio>,
+    approval_threshold: Option<Ratio>,
+    tie_break: vote::TieBreak,
 }
 
 #[must_use = "Adding a proposal may fail"]
@@ -107,9 +147,52 @@ impl Proposal {
             external_id,
             options,
             action,
+            payload_type: None,
+            committee_public_keys: Vec::new(),
+            quorum: None,
+            approval_threshold: None,
+            tie_break: vote::TieBreak::default(),
         }
     }
 
+    /// vote for this proposal with the given payload type instead of the
+    /// vote plan's
+    pub fn with_payload_type(mut self, payload_type: vote::PayloadType) -> Self {
+        self.payload_type = Some(payload_type);
+        self
+    }
+
+    /// encrypt the private votes of this proposal for the given committee
+    /// instead of the vote plan's
+    pub fn with_committee_public_keys(
+        mut self,
+        committee_public_keys: Vec<chain_vote::MemberPublicKey>,
+    ) -> Self {
+        self.committee_public_keys = committee_public_keys;
+        self
+    }
+
+    /// require the given share of the eligible stake to take part in the
+    /// vote for the proposal to be decided
+    pub fn with_quorum(mut self, quorum: Ratio) -> Self {
+        self.quorum = Some(quorum);
+        self
+    }
+
+    /// require the given share of the favorable stake among the favorable
+    /// and rejecting stake for the proposal to pass
+    pub fn with_approval_threshold(mut self, approval_threshold: Ratio) -> Self {
+        self.approval_threshold = Some(approval_threshold);
+        self
+    }
+
+    /// decide ties between the favorable and the rejecting stake with the
+    /// given rule
+    pub fn with_tie_break(mut self, tie_break: vote::TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
     pub fn check_governance(&self, governance: &Governance) -> bool {
         let criteria = match self.action() {
             VoteAction::OffChain => {
@@ -139,10 +222,74 @@ impl Proposal {
         &self.action
     }
 
-    fn serialize_in(&self, bb: ByteBuilder<VotePlan>) -> ByteBuilder<VotePlan> {
-        bb.bytes(self.external_id.as_ref())
+    /// the payload type of this proposal, if different from the vote plan's
+    pub fn payload_type(&self) -> Option<vote::PayloadType> {
+        self.payload_type
+    }
+
+    /// the committee of this proposal, if different from the vote plan's
+    pub fn committee_public_keys(&self) -> &[chain_vote::MemberPublicKey] {
+        &self.committee_public_keys
+    }
+
+    /// the quorum required by this proposal, if any
+    pub fn quorum(&self) -> Option<Ratio> {
+        self.quorum
+    }
+
+    /// the approval threshold required by this proposal, if any
+    pub fn approval_threshold(&self) -> Option<Ratio> {
+        self.approval_threshold
+    }
+
+    pub fn tie_break(&self) -> vote::TieBreak {
+        self.tie_break
+    }
+
+    /// tells if the proposal only uses what the given vote plan version
+    /// can encode
+    fn fits_version(&self, version: VotePlanVersion) -> bool {
+        match version {
+            VotePlanVersion::V1 => {
+                self.payload_type.is_none()
+                    && self.committee_public_keys.is_empty()
+                    && self.quorum.is_none()
+                    && self.approval_threshold.is_none()
+                    && self.tie_break == vote::TieBreak::default()
+            }
+            VotePlanVersion::V2 => true,
+        }
+    }
+
+    fn serialize_in(
+        &self,
+        version: VotePlanVersion,
+        bb: ByteBuilder<VotePlan>,
+    ) -> ByteBuilder<VotePlan> {
+        let bb = bb
+            .bytes(self.external_id.as_ref())
             .u8(self.options.as_byte())
-            .sub(|bb| self.action.serialize_in(bb))
+            .sub(|bb| self.action.serialize_in(bb));
+        match version {
+            VotePlanVersion::V1 => bb,
+            VotePlanVersion::V2 => {
+                let bb = bb
+                    // 0 is not a valid payload type: the vote plan's one is used
+                    .u8(self.payload_type.map_or(0, |t| t as u8))
+                    .iter8(self.committee_public_keys.iter(), |bb, key| {
+                        bb.bytes(key.to_bytes().as_ref())
+                    });
+                let bb = serialize_ratio_in(self.quorum.as_ref(), bb);
+                serialize_ratio_in(self.approval_threshold.as_ref(), bb).u8(self.tie_break as u8)
+            }
+        }
+    }
+}
+
+fn serialize_ratio_in(ratio: Option<&Ratio>, bb: ByteBuilder<VotePlan>) -> ByteBuilder<VotePlan> {
+    match ratio {
+        None => bb.u8(0),
+        Some(ratio) => bb.u8(1).u64(ratio.numerator).u64(ratio.denominator.get()),
     }
 }
 
@@ -183,7 +330,48 @@ impl Proposals {
     }
 }
 
+impl VotePlanVersion {
+    /// the version of the vote plans built with `VotePlan::new`
+    pub const LATEST: Self = VotePlanVersion::V2;
+
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            1 => Some(VotePlanVersion::V1),
+            2 => Some(VotePlanVersion::V2),
+            _ => None,
+        }
+    }
+
+    /// the key encrypting the private votes for the committee of the given
+    /// members in a vote plan of this version
+    pub fn election_key(self, committee_public_keys: &[MemberPublicKey]) -> EncryptingVoteKey {
+        match self {
+            VotePlanVersion::V1 => EncryptingVoteKey::from_participants(committee_public_keys),
+            VotePlanVersion::V2 => {
+                EncryptingVoteKey::from_threshold_participants(committee_public_keys)
+            }
+        }
+    }
+
+    /// tells if the given members of the committee are enough to decrypt
+    /// a tally in a vote plan of this version
+    ///
+    /// `members` are indices in `committee_public_keys`. Duplicate or out of
+    /// range indices are never enough.
+    pub fn reached_by(self, committee_public_keys: &[MemberPublicKey], members: &[usize]) -> bool {
+        match self {
+            VotePlanVersion::V1 => {
+                let mut sorted = members.to_vec();
+                sorted.sort_unstable();
+                sorted.into_iter().eq(0..committee_public_keys.len())
+            }
+            VotePlanVersion::V2 => EncryptingVoteKey::reached_by(committee_public_keys, members),
+        }
+    }
+}
+
 impl VotePlan {
+    /// build a vote plan of the latest version
     pub fn new(
         vote_start: BlockDate,
         vote_end: BlockDate,
@@ -193,6 +381,7 @@ impl VotePlan {
         committee_public_keys: Vec<chain_vote::MemberPublicKey>,
     ) -> Self {
         Self {
+            version: VotePlanVersion::LATEST,
             vote_start,
             vote_end,
             committee_end,
@@ -202,12 +391,36 @@ impl VotePlan {
         }
     }
 
+    /// encode the vote plan with the layout of the given version
+    pub fn with_version(mut self, version: VotePlanVersion) -> Self {
+        self.version = version;
+        self
+    }
+
     pub fn check_governance(&self, governance: &Governance) -> bool {
         self.proposals()
             .iter()
             .all(|proposal| proposal.check_governance(governance))
     }
 
+    /// check the proposals only use what the version of the plan can encode
+    pub fn check_version(&self) -> bool {
+        self.proposals()
+            .iter()
+            .all(|proposal| proposal.fits_version(self.version))
+    }
+
+    /// check the treasury transfers to several accounts of the proposals
+    /// have at least one payee and only transfer positive amounts
+    pub fn check_payees(&self) -> bool {
+        self.proposals()
+            .iter()
+            .all(|proposal| match proposal.action() {
+                VoteAction::Treasury { action } => action.check_payees(),
+                _ => true,
+            })
+    }
+
     pub fn is_governance(&self) -> bool {
         self.proposals().iter().any(|proposal| {
             matches!(proposal.action(), VoteAction::Parameters { .. })
@@ -215,6 +428,10 @@ impl VotePlan {
         })
     }
 
+    pub fn version(&self) -> VotePlanVersion {
+        self.version
+    }
+
     pub fn vote_start(&self) -> BlockDate {
         self.vote_start
     }
@@ -248,6 +465,38 @@ impl VotePlan {
         &self.committee_public_keys
     }
 
+    /// the payload type of the votes for the given proposal of this plan
+    pub fn proposal_payload_type(&self, proposal: &Proposal) -> vote::PayloadType {
+        proposal.payload_type().unwrap_or(self.payload_type)
+    }
+
+    /// the committee keys encrypting the private votes for the given proposal
+    /// of this plan
+    pub fn proposal_committee_public_keys<'a>(
+        &'a self,
+        proposal: &'a Proposal,
+    ) -> &'a [chain_vote::MemberPublicKey] {
+        if proposal.committee_public_keys().is_empty() {
+            self.committee_public_keys()
+        } else {
+            proposal.committee_public_keys()
+        }
+    }
+
+    /// the key encrypting the private votes for the given proposal of this
+    /// plan
+    pub fn proposal_election_key(&self, proposal: &Proposal) -> EncryptingVoteKey {
+        self.version
+            .election_key(self.proposal_committee_public_keys(proposal))
+    }
+
+    /// tells if the votes for any of the proposals of the plan are private
+    pub fn has_private_proposals(&self) -> bool {
+        self.proposals()
+            .iter()
+            .any(|proposal| self.proposal_payload_type(proposal) == vote::PayloadType::Private)
+    }
+
     #[inline]
     pub fn vote_started(&self, date: BlockDate) -> bool {
         self.vote_start <= date
@@ -288,15 +537,20 @@ impl VotePlan {
     }
 
     pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
-        bb.u32(self.vote_start.epoch)
+        let bb = bb
+            .u32(self.vote_start.epoch)
             .u32(self.vote_start.slot_id)
             .u32(self.vote_end.epoch)
             .u32(self.vote_end.slot_id)
             .u32(self.committee_end.epoch)
-            .u32(self.committee_end.slot_id)
-            .u8(self.payload_type as u8)
+            .u32(self.committee_end.slot_id);
+        let bb = match self.version {
+            VotePlanVersion::V1 => bb,
+            version => bb.u8(VERSIONED_LAYOUT_MARKER).u8(version as u8),
+        };
+        bb.u8(self.payload_type as u8)
             .iter8(&mut self.proposals.iter(), |bb, proposal| {
-                proposal.serialize_in(bb)
+                proposal.serialize_in(self.version, bb)
             })
             .iter8(self.committee_public_keys.iter(), |bb, key| {
                 bb.bytes(key.to_bytes().as_ref())
@@ -413,8 +667,17 @@ impl Readable for VotePlan {
             slot_id: buf.get_u32()?,
         };
 
-        let payload_type = buf
-            .get_u8()?
+        let (version, payload_type) = match buf.get_u8()? {
+            VERSIONED_LAYOUT_MARKER => {
+                let version = buf.get_u8()?;
+                let version = VotePlanVersion::from_u8(version)
+                    .filter(|version| *version != VotePlanVersion::V1)
+                    .ok_or(ReadError::UnknownTag(version as u32))?;
+                (version, buf.get_u8()?)
+            }
+            payload_type => (VotePlanVersion::V1, payload_type),
+        };
+        let payload_type = payload_type
             .try_into()
             .map_err(|e: vote::TryFromIntError| ReadError::StructureInvalid(e.to_string()))?;
 
@@ -429,26 +692,34 @@ impl Readable for VotePlan {
                     .map_err(|e| ReadError::StructureInvalid(e.to_string()))
             })?;
             let action = VoteAction::read(buf)?;
-
-            let proposal = Proposal {
-                external_id,
-                options,
-                action,
-            };
+            let mut proposal = Proposal::new(external_id, options, action);
+            match version {
+                VotePlanVersion::V1 => (),
+                VotePlanVersion::V2 => {
+                    proposal.payload_type = match buf.get_u8()? {
+                        0 => None,
+                        t => Some(t.try_into().map_err(|e: vote::TryFromIntError| {
+                            ReadError::StructureInvalid(e.to_string())
+                        })?),
+                    };
+                    proposal.committee_public_keys = read_committee_public_keys(buf)?;
+                    proposal.quorum = read_ratio(buf)?;
+                    proposal.approval_threshold = read_ratio(buf)?;
+                    proposal.tie_break = match buf.get_u8()? {
+                        0 => vote::TieBreak::NoWinner,
+                        1 => vote::TieBreak::LowestOption,
+                        t => return Err(ReadError::UnknownTag(t as u32)),
+                    };
+                }
+            }
 
             proposals.proposals.push(proposal);
         }
 
-        let member_keys_len = buf.get_u8()?;
-        let mut committee_public_keys = Vec::new();
-        for _ in 0..member_keys_len {
-            let key_buf = buf.get_slice(MemberPublicKey::BYTES_LEN)?;
-            committee_public_keys.push(MemberPublicKey::from_bytes(key_buf).ok_or_else(|| {
-                ReadError::StructureInvalid("invalid public key format".to_string())
-            })?);
-        }
+        let committee_public_keys = read_committee_public_keys(buf)?;
 
         Ok(Self {
+            version,
             vote_start,
             vote_end,
             committee_end,
@@ -459,6 +730,33 @@ impl Readable for VotePlan {
     }
 }
 
+pub(super) fn read_committee_public_keys(
+    buf: &mut ReadBuf,
+) -> Result<Vec<MemberPublicKey>, ReadError> {
+    let member_keys_len = buf.get_u8()?;
+    let mut committee_public_keys = Vec::new();
+    for _ in 0..member_keys_len {
+        let key_buf = buf.get_slice(MemberPublicKey::BYTES_LEN)?;
+        committee_public_keys.push(
+            MemberPublicKey::from_bytes(key_buf).ok_or_else(|| {
+                ReadError::StructureInvalid("invalid public key format".to_string())
+            })?,
+        );
+    }
+    Ok(committee_public_keys)
+}
+
+fn read_ratio(buf: &mut ReadBuf) -> Result<Option<Ratio>, ReadError> {
+    match buf.get_u8()? {
+        0 => Ok(None),
+        1 => Ok(Some(Ratio {
+            numerator: buf.get_u64()?,
+            denominator: buf.get_nz_u64()?,
+        })),
+        t => Err(ReadError::UnknownTag(t as u32)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -479,6 +777,149 @@ mod tests {
         decoded == vote_plan
     }
 
+    #[test]
+    pub fn serialize_deserialize_proposal_quorum() {
+        use std::num::NonZeroU64;
+
+        let ratio = |numerator| Ratio {
+            numerator,
+            denominator: NonZeroU64::new(100).unwrap(),
+        };
+
+        let mut proposals = Proposals::new();
+        let proposal = VoteTestGen::proposal()
+            .with_quorum(ratio(30))
+            .with_approval_threshold(ratio(60))
+            .with_tie_break(vote::TieBreak::LowestOption);
+        assert_eq!(proposals.push(proposal), PushProposal::Success);
+        let vote_plan = VotePlan::new(
+            BlockDate::from_epoch_slot_id(1, 0),
+            BlockDate::from_epoch_slot_id(2, 0),
+            BlockDate::from_epoch_slot_id(3, 0),
+            proposals,
+            vote::PayloadType::Public,
+            Vec::new(),
+        );
+
+        let serialized = vote_plan.serialize();
+        let mut buf = ReadBuf::from(serialized.as_ref());
+        let deserialized = VotePlan::read(&mut buf).unwrap();
+        assert_eq!(deserialized, vote_plan);
+        assert_eq!(deserialized.proposals()[0].quorum(), Some(ratio(30)));
+        assert_eq!(
+            deserialized.proposals()[0].approval_threshold(),
+            Some(ratio(60))
+        );
+        assert_eq!(
+            deserialized.proposals()[0].tie_break(),
+            vote::TieBreak::LowestOption
+        );
+    }
+
+    #[test]
+    pub fn serialize_deserialize_proposal_committee() {
+        use chain_vote::{MemberCommunicationKey, MemberState, CRS};
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let crs = CRS::random(&mut rng);
+        let communication_keys = [MemberCommunicationKey::new(&mut rng).to_public()];
+        let member = MemberState::new(&mut rng, 1, &crs, &communication_keys, 0);
+
+        let mut proposals = Proposals::new();
+        let proposal = VoteTestGen::proposal()
+            .with_payload_type(vote::PayloadType::Private)
+            .with_committee_public_keys(vec![member.public_key()]);
+        assert_eq!(proposals.push(proposal), PushProposal::Success);
+        let vote_plan = VotePlan::new(
+            BlockDate::from_epoch_slot_id(1, 0),
+            BlockDate::from_epoch_slot_id(2, 0),
+            BlockDate::from_epoch_slot_id(3, 0),
+            proposals,
+            vote::PayloadType::Public,
+            Vec::new(),
+        );
+
+        let serialized = vote_plan.serialize();
+        let mut buf = ReadBuf::from(serialized.as_ref());
+        assert_eq!(VotePlan::read(&mut buf).unwrap(), vote_plan);
+        assert!(vote_plan.has_private_proposals());
+    }
+
+    #[test]
+    pub fn first_version_proposals_share_the_plan_payload_type() {
+        let mut proposals = Proposals::new();
+        let proposal = VoteTestGen::proposal().with_payload_type(vote::PayloadType::Private);
+        assert_eq!(proposals.push(proposal), PushProposal::Success);
+        let vote_plan = VotePlan::new(
+            BlockDate::from_epoch_slot_id(1, 0),
+            BlockDate::from_epoch_slot_id(2, 0),
+            BlockDate::from_epoch_slot_id(3, 0),
+            proposals,
+            vote::PayloadType::Public,
+            Vec::new(),
+        );
+
+        assert_eq!(vote_plan.version(), VotePlanVersion::V2);
+        assert!(vote_plan.check_version());
+        assert!(!vote_plan.with_version(VotePlanVersion::V1).check_version());
+    }
+
+    #[test]
+    pub fn first_version_proposals_have_no_quorum() {
+        use std::num::NonZeroU64;
+
+        let mut proposals = Proposals::new();
+        let proposal = VoteTestGen::proposal().with_quorum(Ratio {
+            numerator: 30,
+            denominator: NonZeroU64::new(100).unwrap(),
+        });
+        assert_eq!(proposals.push(proposal), PushProposal::Success);
+        let vote_plan = VotePlan::new(
+            BlockDate::from_epoch_slot_id(1, 0),
+            BlockDate::from_epoch_slot_id(2, 0),
+            BlockDate::from_epoch_slot_id(3, 0),
+            proposals,
+            vote::PayloadType::Public,
+            Vec::new(),
+        );
+
+        assert!(vote_plan.check_version());
+        assert!(!vote_plan.with_version(VotePlanVersion::V1).check_version());
+    }
+
+    #[test]
+    pub fn deserialize_first_version_layout() {
+        // the layout of the vote plans before they were versioned
+        let bytes = ByteBuilder::<VotePlan>::new()
+            .u32(1)
+            .u32(0)
+            .u32(2)
+            .u32(0)
+            .u32(3)
+            .u32(0)
+            .u8(vote::PayloadType::Public as u8)
+            // one proposal with 3 options and an off chain action
+            .u8(1)
+            .bytes(&[1u8; 32])
+            .u8(3)
+            .u8(0)
+            // no committee member key
+            .u8(0)
+            .finalize_as_vec();
+
+        let vote_plan = VotePlan::read(&mut ReadBuf::from(&bytes)).unwrap();
+        assert_eq!(vote_plan.version(), VotePlanVersion::V1);
+        assert_eq!(vote_plan.proposals().len(), 1);
+        assert_eq!(
+            vote_plan.proposals()[0].tie_break(),
+            vote::TieBreak::NoWinner
+        );
+        // the identifier of the vote plan is left unchanged
+        assert_eq!(vote_plan.serialize().as_slice(), bytes.as_slice());
+    }
+
     #[test]
     pub fn proposals_are_full() {
         let mut proposals = VoteTestGen::proposals(Proposals::MAX_LEN);
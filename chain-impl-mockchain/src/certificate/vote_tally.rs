@@ -1,17 +1,17 @@
 use crate::{
-    certificate::{CertificateSlice, VotePlanId},
+    certificate::{CertificateSlice, VotePlanId, VotePlanVersion},
     transaction::{
         Payload, PayloadAuthData, PayloadData, PayloadSlice, SingleAccountBindingSignature,
         TransactionBindingAuthData,
     },
-    vote::{CommitteeId, PayloadType, TryFromIntError},
+    vote::{CommitteeId, PayloadType, TallyError, TryFromIntError},
 };
 use chain_core::{
     mempack::{ReadBuf, ReadError, Readable},
     property,
 };
 use chain_crypto::Verification;
-use chain_vote::TallyDecryptShare;
+use chain_vote::{EncryptedTally, MemberPublicKey, TallyDecryptShare};
 use typed_bytes::{ByteArray, ByteBuilder};
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
@@ -39,11 +39,26 @@ pub enum TallyProof {
     },
 }
 
+/// decrypt shares of a private tally, for every private proposal of the vote
+/// plan
+///
+/// every share comes with the index of the committee member that computed
+/// it, so that the tally can be decrypted with the shares of only some of
+/// the committee members.
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub struct TallyDecryptShares {
-    inner: Box<[Box<[TallyDecryptShare]>]>,
+    inner: Box<[MembersShares]>,
 }
 
+/// decrypt shares of a proposal along with the index of their member
+type MembersShares = Box<[(u8, TallyDecryptShare)]>;
+
+/// tag of a private tally whose decrypt shares carry the index of their
+/// committee member, the tags `1` and `2` being the `PayloadType` of a
+/// public tally and of a private tally with a share from every member, in
+/// the committee order
+const PRIVATE_MEMBERS_SHARES_TAG: u8 = 3;
+
 impl VoteTallyPayload {
     pub fn payload_type(&self) -> PayloadType {
         match self {
@@ -86,22 +101,41 @@ impl VoteTally {
     pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
         use std::convert::TryInto;
 
-        let bb = bb.bytes(self.id().as_ref()).u8(self.tally_type() as u8);
+        let bb = bb.bytes(self.id().as_ref());
 
         match &self.payload {
-            VoteTallyPayload::Public => bb,
-            VoteTallyPayload::Private { shares } => {
-                bb.u8(shares.inner.len().try_into().unwrap())
+            VoteTallyPayload::Public => bb.u8(PayloadType::Public as u8),
+            VoteTallyPayload::Private { shares } if !shares.has_proofs() => {
+                bb.u8(PayloadType::Private as u8)
+                    .u8(shares.inner.len().try_into().unwrap())
                     .fold(shares.inner.iter(), |bb, s| {
                         // Shares per proposal, n_members x n_options
                         let n_members = s.len().try_into().unwrap();
                         if n_members == 0 {
                             bb.u8(0).u8(0)
                         } else {
-                            let n_options = s[0].options().try_into().unwrap();
+                            let n_options = s[0].1.options().try_into().unwrap();
+                            bb.u8(n_members).u8(n_options).fold(s.iter(), |bb, (_, s)| {
+                                bb.bytes(&s.to_bytes_without_proofs())
+                            })
+                        }
+                    })
+            }
+            VoteTallyPayload::Private { shares } => {
+                bb.u8(PRIVATE_MEMBERS_SHARES_TAG)
+                    .u8(shares.inner.len().try_into().unwrap())
+                    .fold(shares.inner.iter(), |bb, s| {
+                        // Shares per proposal, n_members x (member index, n_options)
+                        let n_members = s.len().try_into().unwrap();
+                        if n_members == 0 {
+                            bb.u8(0).u8(0)
+                        } else {
+                            let n_options = s[0].1.options().try_into().unwrap();
                             bb.u8(n_members)
                                 .u8(n_options)
-                                .fold(s.iter(), |bb, s| bb.bytes(&s.to_bytes()))
+                                .fold(s.iter(), |bb, (member_index, s)| {
+                                    bb.u8(*member_index).bytes(&s.to_bytes())
+                                })
                         }
                     })
             }
@@ -150,7 +184,24 @@ impl TallyProof {
 }
 
 impl TallyDecryptShares {
+    /// create the decrypt shares from the shares of every committee member
+    ///
+    /// the shares of every proposal are expected to be ordered as the
+    /// committee members' public keys, of which a vote plan holds at most
+    /// 255.
     pub fn new(shares: Vec<Vec<TallyDecryptShare>>) -> Self {
+        Self::from_members(
+            shares
+                .into_iter()
+                .map(|s| (0..=u8::MAX).zip(s).collect())
+                .collect(),
+        )
+    }
+
+    /// create the decrypt shares from the shares of some of the committee
+    /// members, each given along with the index of its member in the
+    /// committee.
+    pub fn from_members(shares: Vec<Vec<(u8, TallyDecryptShare)>>) -> Self {
         Self {
             inner: shares
                 .into_iter()
@@ -160,13 +211,146 @@ impl TallyDecryptShares {
         }
     }
 
-    pub fn shares_for_proposal(&self, i: u8) -> Option<&[TallyDecryptShare]> {
+    /// serialize the shares computed by one committee member, one share per
+    /// private proposal, so they can be sent to whoever assembles the
+    /// `TallyDecryptShares`
+    ///
+    /// every share carries the proof it was correctly computed.
+    ///
+    /// # Errors
+    ///
+    /// * if there are more than 255 shares or a share covers more than 255
+    ///   options, these do not come from a valid vote plan.
+    pub fn serialize_member(
+        member_index: u8,
+        shares: &[TallyDecryptShare],
+    ) -> Result<Vec<u8>, std::num::TryFromIntError> {
+        use std::convert::TryFrom;
+
+        let mut bb = ByteBuilder::<Self>::new()
+            .u8(member_index)
+            .u8(u8::try_from(shares.len())?);
+        for share in shares {
+            bb = bb
+                .u8(u8::try_from(share.options())?)
+                .bytes(&share.to_bytes());
+        }
+        Ok(bb.finalize_as_vec())
+    }
+
+    /// assemble the decrypt shares from the serialized shares of some of the
+    /// committee members, as produced by `serialize_member`
+    ///
+    /// # Errors
+    ///
+    /// * if the shares of a member cannot be read;
+    /// * if the members do not provide shares for the same number of
+    ///   proposals.
+    pub fn from_serialized(members: Vec<Vec<u8>>) -> Result<Self, ReadError> {
+        let mut proposals: Option<Vec<Vec<(u8, TallyDecryptShare)>>> = None;
+        for bytes in members {
+            let mut buf = ReadBuf::from(&bytes);
+            let member_index = buf.get_u8()?;
+            let proposals_number = buf.get_u8()? as usize;
+            let proposals = proposals.get_or_insert_with(|| vec![Vec::new(); proposals_number]);
+            if proposals.len() != proposals_number {
+                return Err(ReadError::StructureInvalid(
+                    "members' decrypt shares cover different proposals".to_owned(),
+                ));
+            }
+            for shares in proposals.iter_mut() {
+                let options_number = buf.get_u8()? as usize;
+                let share = read_decrypt_share(&mut buf, options_number)?;
+                shares.push((member_index, share));
+            }
+            buf.expect_end()?;
+        }
+        Ok(Self::from_members(proposals.unwrap_or_default()))
+    }
+
+    /// tells if the shares carry the proofs they were computed with the
+    /// secret keys of their members, which the shares of a tally encoded
+    /// with a share from every member, in the committee order, do not
+    pub fn has_proofs(&self) -> bool {
+        self.iter().flatten().all(|(_, share)| share.has_proofs())
+    }
+
+    pub fn shares_for_proposal(&self, i: u8) -> Option<&[(u8, TallyDecryptShare)]> {
         self.inner.get(i as usize).map(|s| s.as_ref())
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &[TallyDecryptShare]> {
+    pub fn iter(&self) -> impl Iterator<Item = &[(u8, TallyDecryptShare)]> {
         self.inner.iter().map(|s| s.as_ref())
     }
+
+    /// verify every decrypt share against the encrypted tally of its proposal
+    ///
+    /// every encrypted tally comes with the public keys of the committee
+    /// members of its proposal, the member index of every share refers to
+    /// these keys.
+    ///
+    /// # Errors
+    ///
+    /// * if the shares do not cover exactly the given encrypted tallies;
+    /// * if a proposal has no share or more shares than committee members;
+    /// * if a share refers to a member out of the committee;
+    /// * if the proof of one of the shares does not verify, or a share
+    ///   has no proof in a vote plan of the second version;
+    /// * if the members of the shares are not enough to decrypt the tally
+    ///   in a vote plan of the given version.
+    pub fn verify(
+        &self,
+        version: VotePlanVersion,
+        encrypted_tallies: &[(&[MemberPublicKey], &EncryptedTally)],
+    ) -> Result<(), TallyError> {
+        if self.inner.len() != encrypted_tallies.len() {
+            return Err(TallyError::InvalidDecryptSharesProposals {
+                expected: encrypted_tallies.len(),
+                found: self.inner.len(),
+            });
+        }
+
+        for (shares, (public_keys, encrypted_tally)) in self.iter().zip(encrypted_tallies.iter()) {
+            if shares.is_empty() || shares.len() > public_keys.len() {
+                return Err(TallyError::InvalidDecryptSharesCount {
+                    expected: public_keys.len(),
+                    found: shares.len(),
+                });
+            }
+
+            let mut provided = vec![false; public_keys.len()];
+            for (member_index, share) in shares.iter() {
+                let member_index = usize::from(*member_index);
+                let public_key = public_keys
+                    .get(member_index)
+                    .ok_or(TallyError::InvalidShareMember { member_index })?;
+                if std::mem::replace(&mut provided[member_index], true) {
+                    return Err(TallyError::DuplicateShare { member_index });
+                }
+                let verified = match version {
+                    // the shares of the tallies recorded before the shares
+                    // carried proofs are only checked to cover every option
+                    VotePlanVersion::V1 if !share.has_proofs() => {
+                        share.options() == encrypted_tally.options()
+                    }
+                    _ => share.verify(encrypted_tally, public_key),
+                };
+                if !verified {
+                    return Err(TallyError::InvalidShareProof { member_index });
+                }
+            }
+
+            let members = shares
+                .iter()
+                .map(|(member_index, _)| usize::from(*member_index))
+                .collect::<Vec<_>>();
+            if !version.reached_by(public_keys, &members) {
+                return Err(TallyError::NotEnoughDecryptShares);
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /* Auth/Payload ************************************************************* */
@@ -234,40 +418,72 @@ impl Readable for VoteTally {
         use std::convert::TryInto as _;
 
         let id = <[u8; 32]>::read(buf)?.into();
-        let payload_type = buf
-            .get_u8()?
-            .try_into()
-            .map_err(|e: TryFromIntError| ReadError::StructureInvalid(e.to_string()))?;
-
-        let payload = match payload_type {
-            PayloadType::Public => VoteTallyPayload::Public,
-            PayloadType::Private => {
-                let proposals_number = buf.get_u8()? as usize;
-                let mut proposals = Vec::with_capacity(proposals_number);
-                for _i in 0..proposals_number {
-                    let shares_number = buf.get_u8()? as usize;
-                    let options_number = buf.get_u8()? as usize;
-                    let share_bytes = TallyDecryptShare::bytes_len(options_number);
-                    let mut shares = Vec::with_capacity(shares_number);
-                    for _j in 0..shares_number {
-                        let s_buf = buf.get_slice(share_bytes)?;
-                        let share = TallyDecryptShare::from_bytes(s_buf).ok_or_else(|| {
-                            ReadError::StructureInvalid(
-                                "invalid decrypt share structure".to_owned(),
-                            )
-                        })?;
-                        shares.push(share);
-                    }
-                    proposals.push(shares.into_boxed_slice());
-                }
-                let shares = TallyDecryptShares {
-                    inner: proposals.into_boxed_slice(),
-                };
+        let tag = buf.get_u8()?;
 
-                VoteTallyPayload::Private { shares }
+        let payload = if tag == PRIVATE_MEMBERS_SHARES_TAG {
+            VoteTallyPayload::Private {
+                shares: read_decrypt_shares(buf, true)?,
+            }
+        } else {
+            let payload_type = tag
+                .try_into()
+                .map_err(|e: TryFromIntError| ReadError::StructureInvalid(e.to_string()))?;
+            match payload_type {
+                PayloadType::Public => VoteTallyPayload::Public,
+                PayloadType::Private => VoteTallyPayload::Private {
+                    shares: read_decrypt_shares(buf, false)?,
+                },
             }
         };
 
         Ok(Self { id, payload })
     }
 }
+
+/// read the decrypt shares of every proposal of a private tally
+///
+/// without `with_member_index` the shares of a proposal are the ones of
+/// every committee member, in the committee order, without their proofs.
+fn read_decrypt_shares(
+    buf: &mut ReadBuf<'_>,
+    with_member_index: bool,
+) -> Result<TallyDecryptShares, ReadError> {
+    let proposals_number = buf.get_u8()? as usize;
+    let mut proposals = Vec::with_capacity(proposals_number);
+    for _i in 0..proposals_number {
+        let shares_number = buf.get_u8()?;
+        let options_number = buf.get_u8()? as usize;
+        let mut shares = Vec::with_capacity(shares_number as usize);
+        for j in 0..shares_number {
+            let share = if with_member_index {
+                let member_index = buf.get_u8()?;
+                (member_index, read_decrypt_share(buf, options_number)?)
+            } else {
+                (j, read_decrypt_share_without_proofs(buf, options_number)?)
+            };
+            shares.push(share);
+        }
+        proposals.push(shares.into_boxed_slice());
+    }
+    Ok(TallyDecryptShares {
+        inner: proposals.into_boxed_slice(),
+    })
+}
+
+fn read_decrypt_share(
+    buf: &mut ReadBuf<'_>,
+    options_number: usize,
+) -> Result<TallyDecryptShare, ReadError> {
+    let s_buf = buf.get_slice(TallyDecryptShare::bytes_len(options_number))?;
+    TallyDecryptShare::from_bytes(s_buf)
+        .ok_or_else(|| ReadError::StructureInvalid("invalid decrypt share structure".to_owned()))
+}
+
+fn read_decrypt_share_without_proofs(
+    buf: &mut ReadBuf<'_>,
+    options_number: usize,
+) -> Result<TallyDecryptShare, ReadError> {
+    let s_buf = buf.get_slice(TallyDecryptShare::bytes_len_without_proofs(options_number))?;
+    TallyDecryptShare::from_bytes_without_proofs(s_buf)
+        .ok_or_else(|| ReadError::StructureInvalid("invalid decrypt share structure".to_owned()))
+}
@@ -90,7 +90,9 @@ impl PerVoteCertificateFee {
     fn fees_for_certificate<'a>(&self, cert: &CertificateSlice<'a>) -> Option<Value> {
         match cert {
             CertificateSlice::VotePlan(_) => self.certificate_vote_plan.map(|v| Value(v.get())),
-            CertificateSlice::VoteCast(_) => self.certificate_vote_cast.map(|v| Value(v.get())),
+            CertificateSlice::VoteCast(_) | CertificateSlice::VoteCastBatch(_) => {
+                self.certificate_vote_cast.map(|v| Value(v.get()))
+            }
             _ => None,
         }
     }
@@ -232,7 +234,9 @@ mod test {
                 cert_fees.certificate_owner_stake_delegation.unwrap().into()
             }
             Certificate::VotePlan { .. } => vote_cert_fees.certificate_vote_plan.unwrap().into(),
-            Certificate::VoteCast { .. } => vote_cert_fees.certificate_vote_cast.unwrap().into(),
+            Certificate::VoteCast { .. } | Certificate::VoteCastBatch { .. } => {
+                vote_cert_fees.certificate_vote_cast.unwrap().into()
+            }
             _ => fee.certificate,
         }
     }
@@ -43,6 +43,14 @@ pub enum Fragment {
     VoteCast(Transaction<certificate::VoteCast>),
     VoteTally(Transaction<certificate::VoteTally>),
     EncryptedVoteTally(Transaction<certificate::EncryptedVoteTally>),
+    VoteCastBatch(Transaction<certificate::VoteCastBatch>),
+    CommitteeHandover(Transaction<certificate::CommitteeHandover>),
+    /// a fragment of a type this version does not know about, kept as it
+    /// was received. Only read by `Fragment::from_raw_keep_unknown`
+    Unknown {
+        tag: u8,
+        raw: FragmentRaw,
+    },
 }
 
 impl PartialEq for Fragment {
@@ -69,6 +77,8 @@ pub(super) enum FragmentTag {
     VoteCast = 11,
     VoteTally = 12,
     EncryptedVoteTally = 13,
+    VoteCastBatch = 14,
+    CommitteeHandover = 15,
 }
 
 impl FragmentTag {
@@ -88,15 +98,18 @@ impl FragmentTag {
             11 => Some(FragmentTag::VoteCast),
             12 => Some(FragmentTag::VoteTally),
             13 => Some(FragmentTag::EncryptedVoteTally),
+            14 => Some(FragmentTag::VoteCastBatch),
+            15 => Some(FragmentTag::CommitteeHandover),
             _ => None,
         }
     }
 }
 
 impl Fragment {
-    /// Return the tag associated with the Message
-    pub(super) fn get_tag(&self) -> MessageTag {
-        match self {
+    /// Return the tag associated with the Message, `None` if the fragment
+    /// is of an unknown type
+    pub(super) fn get_tag(&self) -> Option<MessageTag> {
+        let tag = match self {
             Fragment::Initial(_) => FragmentTag::Initial,
             Fragment::OldUtxoDeclaration(_) => FragmentTag::OldUtxoDeclaration,
             Fragment::Transaction(_) => FragmentTag::Transaction,
@@ -111,17 +124,25 @@ impl Fragment {
             Fragment::VoteCast(_) => FragmentTag::VoteCast,
             Fragment::VoteTally(_) => FragmentTag::VoteTally,
             Fragment::EncryptedVoteTally(_) => FragmentTag::EncryptedVoteTally,
-        }
+            Fragment::VoteCastBatch(_) => FragmentTag::VoteCastBatch,
+            Fragment::CommitteeHandover(_) => FragmentTag::CommitteeHandover,
+            Fragment::Unknown { .. } => return None,
+        };
+        Some(tag)
     }
 
     /// Get the serialized representation of this message
     pub fn to_raw(&self) -> FragmentRaw {
         use chain_core::packer::*;
         use chain_core::property::Serialize;
+        // fragments of an unknown type are kept as they were received
+        if let Fragment::Unknown { raw, .. } = self {
+            return raw.clone();
+        }
         let v = Vec::new();
         let mut codec = Codec::new(v);
         codec.put_u8(0).unwrap();
-        codec.put_u8(self.get_tag() as u8).unwrap();
+        codec.put_u8(self.get_tag().unwrap() as u8).unwrap();
         match self {
             Fragment::Initial(i) => i.serialize(&mut codec).unwrap(),
             Fragment::OldUtxoDeclaration(s) => s.serialize(&mut codec).unwrap(),
@@ -137,6 +158,11 @@ impl Fragment {
             Fragment::VoteCast(vote_plan) => vote_plan.serialize(&mut codec).unwrap(),
             Fragment::VoteTally(vote_tally) => vote_tally.serialize(&mut codec).unwrap(),
             Fragment::EncryptedVoteTally(vote_tally) => vote_tally.serialize(&mut codec).unwrap(),
+            Fragment::VoteCastBatch(vote_cast_batch) => {
+                vote_cast_batch.serialize(&mut codec).unwrap()
+            }
+            Fragment::CommitteeHandover(handover) => handover.serialize(&mut codec).unwrap(),
+            Fragment::Unknown { .. } => unreachable!(),
         }
         FragmentRaw(codec.into_inner())
     }
@@ -146,14 +172,22 @@ impl Fragment {
         Fragment::read(&mut buf)
     }
 
+    /// same as `from_raw`, except that a fragment of a type this version
+    /// does not know about is read as `Fragment::Unknown` instead of
+    /// failing
+    pub fn from_raw_keep_unknown(raw: &FragmentRaw) -> Result<Self, ReadError> {
+        let mut buf = ReadBuf::from(raw.as_ref());
+        Fragment::read_with(&mut buf, true)
+    }
+
     /// The ID of a message is a hash of its serialization *without* the size.
     pub fn hash(&self) -> FragmentId {
         self.to_raw().id()
     }
 }
 
-impl Readable for Fragment {
-    fn read<'a>(buf: &mut ReadBuf<'a>) -> Result<Self, ReadError> {
+impl Fragment {
+    fn read_with(buf: &mut ReadBuf, keep_unknown: bool) -> Result<Self, ReadError> {
         let padding_tag = buf.get_u8()?;
         if padding_tag != 0 {
             return Err(ReadError::StructureInvalid(format!(
@@ -192,11 +226,29 @@ impl Readable for Fragment {
             Some(FragmentTag::EncryptedVoteTally) => {
                 Transaction::read(buf).map(Fragment::EncryptedVoteTally)
             }
+            Some(FragmentTag::VoteCastBatch) => Transaction::read(buf).map(Fragment::VoteCastBatch),
+            Some(FragmentTag::CommitteeHandover) => {
+                Transaction::read(buf).map(Fragment::CommitteeHandover)
+            }
+            None if keep_unknown => {
+                let mut raw = vec![padding_tag, tag];
+                raw.extend_from_slice(buf.get_slice_end());
+                Ok(Fragment::Unknown {
+                    tag,
+                    raw: FragmentRaw(raw),
+                })
+            }
             None => Err(ReadError::UnknownTag(tag as u32)),
         }
     }
 }
 
+impl Readable for Fragment {
+    fn read<'a>(buf: &mut ReadBuf<'a>) -> Result<Self, ReadError> {
+        Fragment::read_with(buf, false)
+    }
+}
+
 impl property::Serialize for Fragment {
     type Error = std::io::Error;
     fn serialize<W: std::io::Write>(&self, writer: W) -> Result<(), Self::Error> {
@@ -7,7 +7,7 @@ use quickcheck_macros::quickcheck;
 
 impl Arbitrary for Fragment {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
-        match g.next_u32() % 14 {
+        match g.next_u32() % 16 {
             0 => Fragment::Initial(Arbitrary::arbitrary(g)),
             1 => Fragment::OldUtxoDeclaration(Arbitrary::arbitrary(g)),
             2 => Fragment::Transaction(Arbitrary::arbitrary(g)),
@@ -22,6 +22,8 @@ impl Arbitrary for Fragment {
             11 => Fragment::VoteCast(Arbitrary::arbitrary(g)),
             12 => Fragment::VoteTally(Arbitrary::arbitrary(g)),
             13 => Fragment::EncryptedVoteTally(Arbitrary::arbitrary(g)),
+            14 => Fragment::VoteCastBatch(Arbitrary::arbitrary(g)),
+            15 => Fragment::CommitteeHandover(Arbitrary::arbitrary(g)),
             _ => unreachable!(),
         }
     }
@@ -33,6 +35,27 @@ fn fragment_serialization_bijection(b: Fragment) -> TestResult {
     TestResult::from_bool(b == b_got)
 }
 
+#[test]
+fn unknown_fragment_serialization_bijection() {
+    let raw = FragmentRaw(vec![0, 200, 1, 2, 3]);
+    let fragment = Fragment::from_raw_keep_unknown(&raw).unwrap();
+    assert!(matches!(fragment, Fragment::Unknown { tag: 200, .. }));
+    assert_eq!(fragment.to_raw(), raw);
+}
+
+#[test]
+fn unknown_fragment_is_a_read_error() {
+    let raw = FragmentRaw(vec![0, 200, 1, 2, 3]);
+    assert_eq!(Fragment::from_raw(&raw), Err(ReadError::UnknownTag(200)));
+}
+
+#[test]
+fn truncated_fragment_is_a_read_error() {
+    for raw in [vec![], vec![0]].iter() {
+        assert!(Fragment::from_raw_keep_unknown(&FragmentRaw(raw.clone())).is_err());
+    }
+}
+
 quickcheck! {
     fn initial_ents_serialization_bijection(config_params: ConfigParams) -> TestResult {
         chain_test_utils::property::serialization_bijection_r(config_params)
@@ -78,7 +78,7 @@ pub(super) fn valid_stake_owner_delegation_transaction<'a>(
 /// check that the transaction input/outputs/witnesses is valid for stake_owner_delegation
 ///
 /// * Only 1 input (subsequently 1 witness), no output
-pub(super) fn valid_vote_cast<'a>(tx: &TransactionSlice<'a, certificate::VoteCast>) -> LedgerCheck {
+pub(super) fn valid_vote_cast<'a, P: Payload>(tx: &TransactionSlice<'a, P>) -> LedgerCheck {
     if_cond_fail_with!(
         tx.inputs().nb_inputs() != 1
             || tx.witnesses().nb_witnesses() != 1
@@ -91,6 +91,7 @@ pub(super) fn valid_vote_cast<'a>(tx: &TransactionSlice<'a, certificate::VoteCas
 ///
 /// * management threshold T is valid: 0 < T <= #owners
 /// * there is no more than MAXIMUM_OWNERS
+/// * no owner is listed twice
 pub(super) fn valid_pool_registration_certificate(
     auth_cert: &certificate::PoolRegistration,
 ) -> LedgerCheck {
@@ -110,10 +111,22 @@ pub(super) fn valid_pool_registration_certificate(
         auth_cert.owners.len() > CHECK_POOL_REG_MAXIMUM_OWNERS,
         Error::PoolRegistrationHasTooManyOwners
     )?;
+    if_cond_fail_with!(
+        auth_cert
+            .owners
+            .iter()
+            .enumerate()
+            .any(|(i, owner)| auth_cert.owners[..i].contains(owner)),
+        Error::PoolRegistrationHasDuplicateOwner
+    )?;
     if_cond_fail_with!(
         auth_cert.operators.len() > CHECK_POOL_REG_MAXIMUM_OPERATORS,
         Error::PoolRegistrationHasTooManyOperators
     )?;
+    if_cond_fail_with!(
+        auth_cert.rewards.ratio.numerator > auth_cert.rewards.ratio.denominator.get(),
+        Error::PoolRegistrationTaxRatioAboveOne
+    )?;
     Ok(())
 }
 
@@ -216,7 +229,15 @@ mod tests {
             && (pool_registration.management_threshold() as usize)
                 <= pool_registration.owners.len()
             && pool_registration.owners.len() <= CHECK_POOL_REG_MAXIMUM_OWNERS
-            && pool_registration.operators.len() <= CHECK_POOL_REG_MAXIMUM_OPERATORS;
+            && pool_registration
+                .owners
+                .iter()
+                .collect::<std::collections::HashSet<_>>()
+                .len()
+                == pool_registration.owners.len()
+            && pool_registration.operators.len() <= CHECK_POOL_REG_MAXIMUM_OPERATORS
+            && pool_registration.rewards.ratio.numerator
+                <= pool_registration.rewards.ratio.denominator.get();
         let result = valid_pool_registration_certificate(&pool_registration);
         to_quickchek_result(result, is_valid)
     }
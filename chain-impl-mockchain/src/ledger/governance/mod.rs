@@ -26,6 +26,8 @@ pub struct Governance {
 pub struct GovernanceAcceptanceCriteria {
     pub minimum_stake_participation: Option<Ratio>,
     pub minimum_approval: Option<Ratio>,
+    /// the blank option: like `Choice::ABSTAIN`, it counts toward the
+    /// stake participation but neither for nor against the proposal
     pub blank: Choice,
     pub favorable: Choice,
     pub rejection: Choice,
@@ -1,5 +1,11 @@
-use crate::{ledger::governance::GovernanceAcceptanceCriteria, value::Value};
-use chain_core::mempack::{ReadBuf, ReadError, Readable};
+use crate::{
+    config::ConfigParam, fee::LinearFee, ledger::governance::GovernanceAcceptanceCriteria,
+    value::Value,
+};
+use chain_core::{
+    mempack::{ReadBuf, ReadError, Readable},
+    property::Serialize as _,
+};
 use imhamt::Hamt;
 use std::collections::{
     hash_map::{DefaultHasher, Entry},
@@ -11,12 +17,14 @@ use typed_bytes::ByteBuilder;
 pub enum ParametersGovernanceAction {
     NoOp,
     RewardAdd { value: Value },
+    FeeUpdate { fee: LinearFee },
 }
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum ParametersGovernanceActionType {
     NoOp,
     RewardAdd,
+    FeeUpdate,
 }
 
 #[derive(Default, Clone, Eq, PartialEq)]
@@ -34,6 +42,7 @@ impl ParametersGovernanceAction {
         match self {
             Self::NoOp => ParametersGovernanceActionType::NoOp,
             Self::RewardAdd { .. } => ParametersGovernanceActionType::RewardAdd,
+            Self::FeeUpdate { .. } => ParametersGovernanceActionType::FeeUpdate,
         }
     }
 
@@ -41,6 +50,13 @@ impl ParametersGovernanceAction {
         match self {
             Self::NoOp => bb.u8(0),
             Self::RewardAdd { value } => bb.u8(1).u64(value.0),
+            Self::FeeUpdate { fee } => bb.u8(2).fold(fee_config_params(fee).iter(), |bb, param| {
+                bb.bytes(
+                    &param
+                        .serialize_as_vec()
+                        .expect("fee config parameters have a fixed size"),
+                )
+            }),
         }
     }
 }
@@ -98,6 +114,9 @@ impl ParametersGovernance {
     }
 
     /// register a new action
+    ///
+    /// fails if an action of the same type is already registered, the
+    /// registered action is kept.
     pub fn logs_register(&mut self, action: ParametersGovernanceAction) -> Result<(), ()> {
         let entry = self.logs.entry(action.to_type());
 
@@ -113,6 +132,15 @@ impl ParametersGovernance {
 
 /* Ser/De ******************************************************************* */
 
+/// the fee is serialized as the config parameters setting it
+fn fee_config_params(fee: &LinearFee) -> [ConfigParam; 3] {
+    [
+        ConfigParam::LinearFee(*fee),
+        ConfigParam::PerCertificateFees(fee.per_certificate_fees),
+        ConfigParam::PerVoteCertificateFees(fee.per_vote_certificate_fees),
+    ]
+}
+
 impl Readable for ParametersGovernanceAction {
     fn read<'a>(buf: &mut ReadBuf<'a>) -> Result<Self, ReadError> {
         match buf.get_u8()? {
@@ -121,6 +149,27 @@ impl Readable for ParametersGovernanceAction {
                 let value = Value::read(buf)?;
                 Ok(Self::RewardAdd { value })
             }
+            2 => {
+                let params = (
+                    ConfigParam::read(buf)?,
+                    ConfigParam::read(buf)?,
+                    ConfigParam::read(buf)?,
+                );
+                match params {
+                    (
+                        ConfigParam::LinearFee(mut fee),
+                        ConfigParam::PerCertificateFees(per_certificate_fees),
+                        ConfigParam::PerVoteCertificateFees(per_vote_certificate_fees),
+                    ) => {
+                        fee.per_certificate_fees(per_certificate_fees);
+                        fee.per_vote_certificate_fees(per_vote_certificate_fees);
+                        Ok(Self::FeeUpdate { fee })
+                    }
+                    _ => Err(ReadError::StructureInvalid(
+                        "expected the fee config parameters".to_owned(),
+                    )),
+                }
+            }
             t => Err(ReadError::UnknownTag(t as u32)),
         }
     }
@@ -130,16 +179,22 @@ impl Readable for ParametersGovernanceAction {
 mod tests {
 
     use super::{ParametersGovernance, ParametersGovernanceAction, ParametersGovernanceActionType};
-    use crate::{ledger::governance::GovernanceAcceptanceCriteria, value::Value, vote::Choice};
+    use crate::{
+        fee::LinearFee, ledger::governance::GovernanceAcceptanceCriteria, value::Value,
+        vote::Choice,
+    };
+    use chain_core::mempack::{ReadBuf, Readable};
     use quickcheck::{Arbitrary, Gen};
     use quickcheck_macros::quickcheck;
+    use typed_bytes::ByteBuilder;
 
     impl Arbitrary for ParametersGovernanceActionType {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
-            let option = u8::arbitrary(g) % 2;
+            let option = u8::arbitrary(g) % 3;
             match option {
                 0 => ParametersGovernanceActionType::NoOp,
                 1 => ParametersGovernanceActionType::RewardAdd,
+                2 => ParametersGovernanceActionType::FeeUpdate,
                 _ => unreachable!(),
             }
         }
@@ -147,12 +202,15 @@ mod tests {
 
     impl Arbitrary for ParametersGovernanceAction {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
-            let option = u8::arbitrary(g) % 2;
+            let option = u8::arbitrary(g) % 3;
             match option {
                 0 => ParametersGovernanceAction::NoOp,
                 1 => ParametersGovernanceAction::RewardAdd {
                     value: Arbitrary::arbitrary(g),
                 },
+                2 => ParametersGovernanceAction::FeeUpdate {
+                    fee: Arbitrary::arbitrary(g),
+                },
                 _ => unreachable!(),
             }
         }
@@ -165,6 +223,20 @@ mod tests {
 
         let action = ParametersGovernanceAction::RewardAdd { value: Value(10) };
         assert_eq!(action.to_type(), ParametersGovernanceActionType::RewardAdd);
+
+        let action = ParametersGovernanceAction::FeeUpdate {
+            fee: LinearFee::new(1, 2, 3),
+        };
+        assert_eq!(action.to_type(), ParametersGovernanceActionType::FeeUpdate);
+    }
+
+    #[quickcheck]
+    pub fn parameters_action_serialization_bijection(action: ParametersGovernanceAction) -> bool {
+        let bytes = action.serialize_in(ByteBuilder::new()).finalize_as_vec();
+        let mut buf = ReadBuf::from(&bytes);
+        let decoded = ParametersGovernanceAction::read(&mut buf).unwrap();
+        buf.expect_end().unwrap();
+        decoded == action
     }
 
     #[test]
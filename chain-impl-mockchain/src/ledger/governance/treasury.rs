@@ -1,4 +1,4 @@
-use crate::{ledger::governance::GovernanceAcceptanceCriteria, value::Value};
+use crate::{account::Identifier, ledger::governance::GovernanceAcceptanceCriteria, value::Value};
 use chain_core::mempack::{ReadBuf, ReadError, Readable};
 use imhamt::Hamt;
 use std::collections::hash_map::DefaultHasher;
@@ -8,12 +8,16 @@ use typed_bytes::ByteBuilder;
 pub enum TreasuryGovernanceAction {
     NoOp,
     TransferToRewards { value: Value },
+    TransferToAccount { account: Identifier, value: Value },
+    TransferToAccounts { payees: Vec<(Identifier, Value)> },
 }
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum TreasuryGovernanceActionType {
     NoOp,
     TransferToRewards,
+    TransferToAccount,
+    TransferToAccounts,
 }
 
 #[derive(Default, Clone, Eq, PartialEq)]
@@ -29,6 +33,8 @@ impl TreasuryGovernanceAction {
         match self {
             Self::NoOp => TreasuryGovernanceActionType::NoOp,
             Self::TransferToRewards { .. } => TreasuryGovernanceActionType::TransferToRewards,
+            Self::TransferToAccount { .. } => TreasuryGovernanceActionType::TransferToAccount,
+            Self::TransferToAccounts { .. } => TreasuryGovernanceActionType::TransferToAccounts,
         }
     }
 
@@ -36,6 +42,23 @@ impl TreasuryGovernanceAction {
         match self {
             Self::NoOp => bb.u8(0),
             Self::TransferToRewards { value } => bb.u8(1).u64(value.0),
+            Self::TransferToAccount { account, value } => {
+                bb.u8(2).bytes(account.as_ref().as_ref()).u64(value.0)
+            }
+            Self::TransferToAccounts { payees } => bb.u8(3).iter8(payees.iter(), |bb, payee| {
+                bb.bytes(payee.0.as_ref().as_ref()).u64((payee.1).0)
+            }),
+        }
+    }
+
+    /// check a transfer to several accounts has at least one payee and
+    /// only transfers positive amounts
+    pub fn check_payees(&self) -> bool {
+        match self {
+            Self::TransferToAccounts { payees } => {
+                !payees.is_empty() && payees.iter().all(|(_, value)| *value > Value::zero())
+            }
+            _ => true,
         }
     }
 }
@@ -95,6 +118,22 @@ impl Readable for TreasuryGovernanceAction {
                 let value = Value::read(buf)?;
                 Ok(Self::TransferToRewards { value })
             }
+            2 => {
+                let account = Identifier::read(buf)?;
+                let value = Value::read(buf)?;
+                Ok(Self::TransferToAccount { account, value })
+            }
+            3 => {
+                let len = buf.get_u8()?;
+                let payees = (0..len)
+                    .map(|_| {
+                        let account = Identifier::read(buf)?;
+                        let value = Value::read(buf)?;
+                        Ok((account, value))
+                    })
+                    .collect::<Result<_, ReadError>>()?;
+                Ok(Self::TransferToAccounts { payees })
+            }
             t => Err(ReadError::UnknownTag(t as u32)),
         }
     }
@@ -104,16 +143,21 @@ impl Readable for TreasuryGovernanceAction {
 mod tests {
 
     use super::{TreasuryGovernance, TreasuryGovernanceAction, TreasuryGovernanceActionType};
-    use crate::{ledger::governance::GovernanceAcceptanceCriteria, value::Value, vote::Choice};
+    use crate::{
+        ledger::governance::GovernanceAcceptanceCriteria, testing::TestGen, value::Value,
+        vote::Choice,
+    };
     use quickcheck::{Arbitrary, Gen};
     use quickcheck_macros::quickcheck;
 
     impl Arbitrary for TreasuryGovernanceActionType {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
-            let option = u8::arbitrary(g) % 2;
+            let option = u8::arbitrary(g) % 4;
             match option {
                 0 => TreasuryGovernanceActionType::NoOp,
                 1 => TreasuryGovernanceActionType::TransferToRewards,
+                2 => TreasuryGovernanceActionType::TransferToAccount,
+                3 => TreasuryGovernanceActionType::TransferToAccounts,
                 _ => unreachable!(),
             }
         }
@@ -128,6 +172,45 @@ mod tests {
             action.to_type(),
             TreasuryGovernanceActionType::TransferToRewards
         );
+
+        let action = TreasuryGovernanceAction::TransferToAccount {
+            account: TestGen::identifier(),
+            value: Value(10),
+        };
+        assert_eq!(
+            action.to_type(),
+            TreasuryGovernanceActionType::TransferToAccount
+        );
+
+        let action = TreasuryGovernanceAction::TransferToAccounts {
+            payees: vec![(TestGen::identifier(), Value(10))],
+        };
+        assert_eq!(
+            action.to_type(),
+            TreasuryGovernanceActionType::TransferToAccounts
+        );
+    }
+
+    #[test]
+    pub fn treasury_governance_check_payees() {
+        let action = TreasuryGovernanceAction::TransferToAccounts {
+            payees: vec![
+                (TestGen::identifier(), Value(10)),
+                (TestGen::identifier(), Value(20)),
+            ],
+        };
+        assert!(action.check_payees());
+
+        let action = TreasuryGovernanceAction::TransferToAccounts { payees: Vec::new() };
+        assert!(!action.check_payees());
+
+        let action = TreasuryGovernanceAction::TransferToAccounts {
+            payees: vec![
+                (TestGen::identifier(), Value(10)),
+                (TestGen::identifier(), Value::zero()),
+            ],
+        };
+        assert!(!action.check_payees());
     }
 
     #[test]
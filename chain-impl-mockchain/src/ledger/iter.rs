@@ -2,7 +2,7 @@ use super::governance::Governance;
 use super::ledger::{Error, Ledger, LedgerStaticParameters};
 use super::pots::{self, Pots};
 use super::LeadersParticipationRecord;
-use crate::certificate::{VotePlan, VotePlanId};
+use crate::certificate::VotePlanId;
 use crate::chaintypes::ChainLength;
 use crate::config::ConfigParam;
 use crate::date::BlockDate;
@@ -46,7 +46,7 @@ pub enum Entry<'a> {
     ),
     StakePool((&'a crate::certificate::PoolId, &'a crate::stake::PoolState)),
     LeaderParticipation((&'a crate::certificate::PoolId, &'a u32)),
-    VotePlan(&'a VotePlan),
+    VotePlan(&'a VotePlanManager),
 }
 
 #[derive(Clone)]
@@ -77,7 +77,7 @@ pub enum EntryOwned {
     MultisigDeclaration((crate::multisig::Identifier, crate::multisig::Declaration)),
     StakePool((crate::certificate::PoolId, crate::stake::PoolState)),
     LeaderParticipation((crate::certificate::PoolId, u32)),
-    VotePlan(VotePlan),
+    VotePlan(VotePlanManager),
     StopEntry,
 }
 
@@ -121,7 +121,7 @@ impl EntryOwned {
             EntryOwned::LeaderParticipation((pool_id, participation)) => {
                 Some(Entry::LeaderParticipation((pool_id, participation)))
             }
-            EntryOwned::VotePlan(vote_plan) => Some(Entry::VotePlan(vote_plan)),
+            EntryOwned::VotePlan(vote_plan_manager) => Some(Entry::VotePlan(vote_plan_manager)),
             EntryOwned::StopEntry => None,
         }
     }
@@ -255,7 +255,7 @@ impl<'a> Iterator for LedgerIterator<'a> {
                     self.state = IterState::Done;
                     self.next()
                 }
-                Some((_, plan_manager)) => Some(Entry::VotePlan(plan_manager.plan())),
+                Some((_, plan_manager)) => Some(Entry::VotePlan(plan_manager)),
             },
             IterState::Done => None,
         }
@@ -334,14 +334,10 @@ impl<'a> std::iter::FromIterator<Entry<'a>> for Result<Ledger, Error> {
                 Entry::LeaderParticipation((pool_id, pool_participation)) => leaders_log
                     .set_for(pool_id.clone(), *pool_participation)
                     .unwrap(),
-                Entry::VotePlan(vote_plan) => {
-                    // TODO: don't use default
+                Entry::VotePlan(vote_plan_manager) => {
                     votes.plans = votes
                         .plans
-                        .insert(
-                            vote_plan.to_id(),
-                            VotePlanManager::new(vote_plan.clone(), Default::default()),
-                        )
+                        .insert(vote_plan_manager.id().clone(), vote_plan_manager.clone())
                         .unwrap();
                 }
             }
@@ -469,8 +465,8 @@ mod tests {
                 Entry::LeaderParticipation((pool_id, pool_record)) => {
                     println!("LeaderParticipation {} {}", pool_id, pool_record);
                 }
-                Entry::VotePlan(plan) => {
-                    println!("VotePlan {}", plan.to_id());
+                Entry::VotePlan(plan_manager) => {
+                    println!("VotePlan {}", plan_manager.id());
                 }
             }
         }
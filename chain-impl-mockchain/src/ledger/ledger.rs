@@ -6,7 +6,8 @@ use super::governance::{Governance, ParametersGovernanceAction, TreasuryGovernan
 use super::leaderlog::LeadersParticipationRecord;
 use super::pots::Pots;
 use super::reward_info::{EpochRewardsInfo, RewardsInfoParameters};
-use crate::certificate::{PoolId, VoteAction, VotePlan};
+use crate::accounting::account::DelegationType;
+use crate::certificate::{PoolId, SignedCertificate, VoteAction, VotePlan};
 use crate::chaineval::HeaderContentEvalContext;
 use crate::chaintypes::{ChainLength, ConsensusType, HeaderId};
 use crate::config::{self, ConfigParam};
@@ -16,7 +17,8 @@ use crate::fragment::{BlockContentHash, BlockContentSize, Contents, Fragment, Fr
 use crate::rewards;
 use crate::setting::ActiveSlotsCoeffError;
 use crate::stake::{
-    PercentStake, PoolError, PoolStakeInformation, PoolsState, StakeControl, StakeDistribution,
+    PercentStake, PoolError, PoolStakeInformation, PoolState, PoolsState, StakeControl,
+    StakeDistribution,
 };
 use crate::transaction::*;
 use crate::treasury::Treasury;
@@ -25,7 +27,11 @@ use crate::vote::{CommitteeId, VotePlanLedger, VotePlanLedgerError, VotePlanStat
 use crate::{account, certificate, legacy, multisig, setting, stake, update, utxo};
 use chain_addr::{Address, Discrimination, Kind};
 use chain_crypto::Verification;
-use chain_time::{Epoch as TimeEpoch, SlotDuration, TimeEra, TimeFrame, Timeline};
+use chain_time::era::{EpochPosition, EpochSlotOffset};
+use chain_time::{
+    DurationSeconds, Epoch as TimeEpoch, SlotDuration, TimeEra, TimeFrame, TimeOffsetSeconds,
+    Timeline,
+};
 use std::mem::swap;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -151,6 +157,8 @@ pub enum Block0Error {
     HasVoteCast,
     #[error("Vote tallying are not valid in the block0")]
     HasVoteTally,
+    #[error("Committee handovers are not valid in the block0")]
+    HasCommitteeHandover,
 }
 
 pub type OutputOldAddress = Output<legacy::OldAddress>;
@@ -260,12 +268,16 @@ pub enum Error {
     PoolRegistrationHasNoOwner,
     #[error("Pool registration with too many owners")]
     PoolRegistrationHasTooManyOwners,
+    #[error("Pool registration with the same owner listed twice")]
+    PoolRegistrationHasDuplicateOwner,
     #[error("Pool registration with too many operators")]
     PoolRegistrationHasTooManyOperators,
     #[error("Pool registration management threshold is zero")]
     PoolRegistrationManagementThresholdZero,
     #[error("Pool registration management threshold above owners")]
     PoolRegistrationManagementThresholdAbove,
+    #[error("Pool registration tax ratio above one")]
+    PoolRegistrationTaxRatioAboveOne,
     #[error("Pool Update not allowed yet")]
     PoolUpdateNotAllowedYet,
     #[error("Stake Delegation payload signature failed")]
@@ -278,12 +290,24 @@ pub enum Error {
     VotePlanProofInvalidCommittee,
     #[error("Vote plan contains proposal(s) that does not pass governance criteria")]
     VotePlanInvalidGovernanceParameters,
+    #[error("Vote plan contains a treasury transfer with no payee or with a zero amount")]
+    VotePlanInvalidPayees,
+    #[error("Vote plan contains proposal(s) that its version cannot encode")]
+    VotePlanInvalidVersion,
+    #[error("Vote cast batch is empty or contains more than one ballot for the same proposal")]
+    VoteCastBatchInvalidBallots,
+    #[error("Certificate of unknown type {tag}")]
+    UnknownCertificate { tag: u8 },
     #[error("Vote Tally Proof failed")]
     VoteTallyProofFailed,
+    #[error("Committee Handover Proof failed")]
+    CommitteeHandoverProofFailed,
     #[error("Vote tally decryption failed")]
     VoteTallyDecryptionFailed,
     #[error("Pool update payload signature failed")]
     PoolUpdateSignatureFailed,
+    #[error("Pool update signed by {actual} owner(s) but the management threshold is {expected}")]
+    PoolUpdateNotEnoughSignatures { actual: usize, expected: u8 },
     #[error("Pool update last known registration hash doesn't match")]
     PoolUpdateLastHashDoesntMatch,
     #[error("Pool update doesnt currently allow fees update")]
@@ -468,7 +492,7 @@ impl Ledger {
                         tx.payload_auth().into_payload_auth(),
                     )?;
                 }
-                Fragment::VoteCast(_) => {
+                Fragment::VoteCast(_) | Fragment::VoteCastBatch(_) => {
                     return Err(Error::Block0(Block0Error::HasVoteCast));
                 }
                 Fragment::VoteTally(_) => {
@@ -477,6 +501,12 @@ impl Ledger {
                 Fragment::EncryptedVoteTally(_) => {
                     return Err(Error::Block0(Block0Error::HasVoteTally));
                 }
+                Fragment::CommitteeHandover(_) => {
+                    return Err(Error::Block0(Block0Error::HasCommitteeHandover));
+                }
+                Fragment::Unknown { tag, .. } => {
+                    return Err(Error::UnknownCertificate { tag: *tag });
+                }
             }
         }
 
@@ -497,13 +527,39 @@ impl Ledger {
                 ParametersGovernanceAction::RewardAdd { value } => {
                     new.pots.rewards_add(*value)?;
                 }
+                ParametersGovernanceAction::FeeUpdate { fee } => {
+                    new.settings.linear_fees = *fee;
+                }
             }
         }
 
         new.governance.parameters.logs_clear();
+
+        // retire the stake pools whose retirement time is reached by the
+        // beginning of the next epoch
+        let next_epoch_time = new.date_time_offset(BlockDate {
+            epoch: new.date.epoch + 1,
+            slot_id: 0,
+        });
+        for pool_id in new.delegation.stake_pools_retiring_by(next_epoch_time) {
+            new.delegation = new.delegation.deregister_stake_pool(&pool_id)?;
+        }
+
         Ok(new)
     }
 
+    /// Time elapsed since the beginning of the blockchain at the given date
+    fn date_time_offset(&self, date: BlockDate) -> TimeOffsetSeconds {
+        let slot: u64 = self
+            .era
+            .from_era_to_slot(EpochPosition {
+                epoch: TimeEpoch(date.epoch),
+                slot: EpochSlotOffset(date.slot_id),
+            })
+            .into();
+        DurationSeconds::from(slot * self.settings.slot_duration as u64).into()
+    }
+
     /// This need to be called before the *first* block of a new epoch
     ///
     /// * Reset the leaders log
@@ -517,6 +573,16 @@ impl Ledger {
         let mut new_ledger = self.clone();
         let mut rewards_info = EpochRewardsInfo::new(rewards_info_params);
 
+        // the rewards are distributed at the start of the next epoch, after
+        // the voting windows ending by then
+        let next_epoch = BlockDate {
+            epoch: self.date.epoch + 1,
+            slot_id: 0,
+        };
+        new_ledger.votes = self.votes.snapshot_voting_stake(next_epoch, || {
+            StakeControl::new_with(&self.accounts, &self.utxos)
+        });
+
         if self.leaders_log.total() == 0 {
             return Ok((new_ledger, rewards_info));
         }
@@ -813,6 +879,12 @@ impl Ledger {
     ) -> Result<Self, Error> {
         let mut new_ledger = self.clone();
 
+        // the votes are weighted with the stake at the end of their voting
+        // window, before the fragments of later dates change it
+        new_ledger.votes = self.votes.snapshot_voting_stake(block_date, || {
+            StakeControl::new_with(&self.accounts, &self.utxos)
+        });
+
         let fragment_id = content.hash();
         match content {
             Fragment::Initial(_) => return Err(Error::Block0OnlyFragmentReceived),
@@ -875,6 +947,7 @@ impl Ledger {
                     &tx.payload().into_payload(),
                     &tx.transaction_binding_auth_data(),
                     tx.payload_auth().into_payload_auth(),
+                    block_date,
                 )?;
             }
             Fragment::PoolUpdate(tx) => {
@@ -918,6 +991,11 @@ impl Ledger {
                 let (new_ledger_, _fee) = new_ledger.apply_vote_cast(&tx, &ledger_params)?;
                 new_ledger = new_ledger_;
             }
+            Fragment::VoteCastBatch(tx) => {
+                let tx = tx.as_slice();
+                let (new_ledger_, _fee) = new_ledger.apply_vote_cast_batch(&tx, ledger_params)?;
+                new_ledger = new_ledger_;
+            }
             Fragment::VoteTally(tx) => {
                 let tx = tx.as_slice();
 
@@ -942,6 +1020,23 @@ impl Ledger {
                     tx.payload_auth().into_payload_auth(),
                 )?;
             }
+            Fragment::CommitteeHandover(tx) => {
+                let tx = tx.as_slice();
+
+                let (new_ledger_, _fee) =
+                    new_ledger.apply_transaction(&fragment_id, &tx, ledger_params)?;
+
+                new_ledger = new_ledger_.apply_committee_handover(
+                    &tx.payload().into_payload(),
+                    &tx.transaction_binding_auth_data(),
+                    tx.payload_auth().into_payload_auth(),
+                )?;
+            }
+            Fragment::Unknown { tag, .. } => {
+                // skipping it would leave nodes of different versions with
+                // different ledgers
+                return Err(Error::UnknownCertificate { tag: *tag });
+            }
         }
 
         Ok(new_ledger)
@@ -1000,9 +1095,7 @@ impl Ledger {
             return Err(Error::VotePlanProofInvalidSignature);
         }
 
-        if !vote_plan.check_governance(&self.governance) {
-            return Err(Error::VotePlanInvalidGovernanceParameters);
-        }
+        self.valid_vote_plan(&vote_plan)?;
 
         let committee: std::collections::HashSet<CommitteeId> = {
             let mut vec = Vec::with_capacity(tx.nb_inputs() as usize);
@@ -1035,11 +1128,64 @@ impl Ledger {
         Ok(self)
     }
 
+    fn valid_vote_plan(&self, vote_plan: &VotePlan) -> Result<(), Error> {
+        if !vote_plan.check_governance(&self.governance) {
+            return Err(Error::VotePlanInvalidGovernanceParameters);
+        }
+
+        if !vote_plan.check_payees() {
+            return Err(Error::VotePlanInvalidPayees);
+        }
+
+        if !vote_plan.check_version() {
+            return Err(Error::VotePlanInvalidVersion);
+        }
+
+        Ok(())
+    }
+
     pub fn apply_vote_cast<'a>(
-        mut self,
+        self,
         tx: &TransactionSlice<'a, certificate::VoteCast>,
         dyn_params: &LedgerParameters,
     ) -> Result<(Self, Value), Error> {
+        let (mut ledger, account_id, fee) = self.apply_vote_cast_transaction(tx, dyn_params)?;
+
+        let vote = tx.payload().into_payload();
+        ledger.votes = ledger.votes.apply_vote(ledger.date(), account_id, vote)?;
+
+        Ok((ledger, fee))
+    }
+
+    /// apply all the ballots of the batch, the fees are charged only once
+    /// for the whole batch. If one of the ballots is rejected the batch
+    /// is rejected as a whole.
+    pub fn apply_vote_cast_batch<'a>(
+        self,
+        tx: &TransactionSlice<'a, certificate::VoteCastBatch>,
+        dyn_params: &LedgerParameters,
+    ) -> Result<(Self, Value), Error> {
+        let batch = tx.payload().into_payload();
+        if !batch.check_ballots() {
+            return Err(Error::VoteCastBatchInvalidBallots);
+        }
+
+        let (mut ledger, account_id, fee) = self.apply_vote_cast_transaction(tx, dyn_params)?;
+
+        ledger.votes = ledger
+            .votes
+            .apply_vote_batch(ledger.date(), account_id, &batch)?;
+
+        Ok((ledger, fee))
+    }
+
+    /// verify and consume the single account input of a vote casting
+    /// transaction, returning the voter's identifier and the fee paid
+    fn apply_vote_cast_transaction<'a, P: Payload>(
+        mut self,
+        tx: &TransactionSlice<'a, P>,
+        dyn_params: &LedgerParameters,
+    ) -> Result<(Self, UnspecifiedAccountIdentifier, Value), Error> {
         let sign_data_hash = tx.transaction_sign_data_hash();
 
         let (account_id, value, witness) = {
@@ -1089,10 +1235,7 @@ impl Ledger {
         };
         self = self.apply_tx_fee(fee)?;
 
-        let vote = tx.payload().into_payload();
-        self.votes = self.votes.apply_vote(self.date(), account_id, vote)?;
-
-        Ok((self, fee))
+        Ok((self, account_id, fee))
     }
 
     pub fn active_vote_plans(&self) -> Vec<VotePlanStatus> {
@@ -1104,7 +1247,7 @@ impl Ledger {
     }
 
     pub fn apply_vote_tally<'a>(
-        mut self,
+        self,
         tally: &certificate::VoteTally,
         bad: &TransactionBindingAuthData<'a>,
         sig: certificate::TallyProof,
@@ -1113,14 +1256,34 @@ impl Ledger {
             return Err(Error::VoteTallyProofFailed);
         }
 
-        let stake = StakeControl::new_with(&self.accounts, &self.utxos);
+        let block_date = self.date();
+        self.apply_vote_tally_verified(block_date, tally, sig)
+    }
 
-        let mut actions = Vec::new();
+    fn apply_vote_tally_verified(
+        mut self,
+        block_date: BlockDate,
+        tally: &certificate::VoteTally,
+        sig: certificate::TallyProof,
+    ) -> Result<Self, Error> {
+        let stake = StakeControl::new_with(&self.accounts, &self.utxos);
 
-        let mut f = |action: &VoteAction| actions.push(action.clone());
+        // the treasury actions are applied as the proposals are tallied, so
+        // the decision of a proposal whose action fails records it
+        let mut parameters_actions = Vec::new();
+        let pots = &mut self.pots;
+        let accounts = &mut self.accounts;
+        let mut f = |action: &VoteAction| match action {
+            VoteAction::OffChain => true,
+            VoteAction::Treasury { action } => apply_treasury_action(pots, accounts, action),
+            VoteAction::Parameters { action } => {
+                parameters_actions.push(action.clone());
+                true
+            }
+        };
 
         self.votes = self.votes.apply_committee_result(
-            self.date(),
+            block_date,
             &stake,
             &self.governance,
             tally,
@@ -1128,26 +1291,10 @@ impl Ledger {
             &mut f,
         )?;
 
-        for action in actions {
-            match action {
-                VoteAction::OffChain => {}
-                VoteAction::Treasury {
-                    action: TreasuryGovernanceAction::NoOp,
-                } => {}
-                VoteAction::Treasury {
-                    action: TreasuryGovernanceAction::TransferToRewards { value },
-                } => {
-                    let value = self.pots.draw_treasury(value);
-                    self.pots.rewards_add(value)?;
-                }
-                VoteAction::Parameters { action } => {
-                    if self.governance.parameters.logs_register(action).is_err() {
-                        unimplemented!("the action was already recorded for this epoch")
-                    } else {
-                        // nothing
-                    }
-                }
-            }
+        for action in parameters_actions {
+            // only the first action of a kind passed within an epoch is
+            // applied at the next protocol change, the later ones are dropped
+            let _ = self.governance.parameters.logs_register(action);
         }
 
         Ok(self)
@@ -1172,6 +1319,23 @@ impl Ledger {
         Ok(self)
     }
 
+    pub fn apply_committee_handover<'a>(
+        mut self,
+        handover: &certificate::CommitteeHandover,
+        bad: &TransactionBindingAuthData<'a>,
+        sig: certificate::CommitteeHandoverProof,
+    ) -> Result<Self, Error> {
+        if sig.verify(bad) == Verification::Failed {
+            return Err(Error::CommitteeHandoverProofFailed);
+        }
+
+        self.votes = self
+            .votes
+            .apply_committee_handover(self.date(), handover, sig)?;
+
+        Ok(self)
+    }
+
     pub fn apply_pool_registration_signcheck<'a>(
         self,
         cert: &certificate::PoolRegistration,
@@ -1203,6 +1367,7 @@ impl Ledger {
         auth_cert: &certificate::PoolRetirement,
         bad: &TransactionBindingAuthData<'a>,
         sig: certificate::PoolSignature,
+        block_date: BlockDate,
     ) -> Result<Self, Error> {
         check::valid_pool_retirement_certificate(auth_cert)?;
         check::valid_pool_signature(&sig)?;
@@ -1212,7 +1377,14 @@ impl Ledger {
             return Err(Error::PoolRetirementSignatureFailed);
         }
 
-        self.delegation = self.delegation.deregister_stake_pool(&auth_cert.pool_id)?;
+        // a retirement in the future is only scheduled, the pool keeps
+        // producing blocks until `apply_protocol_changes` retires it
+        if auth_cert.retirement_time <= self.date_time_offset(block_date) {
+            self.delegation = self.delegation.deregister_stake_pool(&auth_cert.pool_id)?;
+        } else {
+            self.delegation
+                .stake_pool_set_retirement(&auth_cert.pool_id, auth_cert.retirement_time)?;
+        }
         Ok(self)
     }
 
@@ -1222,8 +1394,32 @@ impl Ledger {
         bad: &TransactionBindingAuthData<'a>,
         sig: certificate::PoolSignature,
     ) -> Result<Self, Error> {
+        let state = self.valid_pool_update(auth_cert, &sig)?;
+
+        if sig.verify(&state.registration, bad) == Verification::Failed {
+            return Err(Error::PoolUpdateSignatureFailed);
+        }
+
+        let new = auth_cert.new_pool_reg.clone();
+
+        let mut updated_state = state.clone();
+        updated_state.registration = Arc::new(new);
+
+        self.delegation
+            .stake_pool_set_state(&auth_cert.pool_id, updated_state)?;
+
+        Ok(self)
+    }
+
+    /// check the pool update against the current state of the pool,
+    /// returning the state to update. The signature itself is not verified.
+    fn valid_pool_update(
+        &self,
+        auth_cert: &certificate::PoolUpdate,
+        sig: &certificate::PoolSignature,
+    ) -> Result<&PoolState, Error> {
         check::valid_pool_update_certificate(auth_cert)?;
-        check::valid_pool_signature(&sig)?;
+        check::valid_pool_signature(sig)?;
 
         let state = self.delegation.stake_pool_get_state(&auth_cert.pool_id)?;
 
@@ -1231,26 +1427,22 @@ impl Ledger {
             return Err(Error::PoolUpdateLastHashDoesntMatch);
         }
 
-        let new = &auth_cert.new_pool_reg;
-
         // don't allow any fees update for now
-        if new.rewards != state.registration.rewards {
+        if auth_cert.new_pool_reg.rewards != state.registration.rewards {
             return Err(Error::PoolUpdateFeesNotAllowedYet);
         }
 
-        if sig.verify(&state.registration, bad) == Verification::Failed {
-            return Err(Error::PoolUpdateSignatureFailed);
+        if let certificate::PoolSignature::Owners(owners) = sig {
+            let expected = state.registration.management_threshold();
+            if owners.signatures.len() < expected as usize {
+                return Err(Error::PoolUpdateNotEnoughSignatures {
+                    actual: owners.signatures.len(),
+                    expected,
+                });
+            }
         }
 
-        let new = new.clone();
-
-        let mut updated_state = state.clone();
-        updated_state.registration = Arc::new(new);
-
-        self.delegation
-            .stake_pool_set_state(&auth_cert.pool_id, updated_state)?;
-
-        Ok(self)
+        Ok(state)
     }
 
     pub fn apply_stake_delegation(
@@ -1331,6 +1523,93 @@ impl Ledger {
         Ok((self, fee))
     }
 
+    /// check the certificate would be accepted if applied to this ledger at
+    /// the given date, the ledger is not modified
+    ///
+    /// All the checks done when applying the certificate are run except the
+    /// ones depending on the transaction carrying it: its inputs and the
+    /// signatures, which are bound to the transaction. Delegating to a pool
+    /// that is not registered is reported too, even though the ledger
+    /// accepts it and leaves the stake unassigned.
+    pub fn validate_certificate(
+        &self,
+        cert: &SignedCertificate,
+        block_date: BlockDate,
+    ) -> Result<(), Error> {
+        match cert {
+            SignedCertificate::StakeDelegation(cert, _) => {
+                self.clone().apply_stake_delegation(cert)?;
+                self.valid_delegation_type(&cert.delegation)
+            }
+            SignedCertificate::OwnerStakeDelegation(cert, _) => {
+                self.valid_delegation_type(&cert.delegation)
+            }
+            SignedCertificate::PoolRegistration(cert, sig) => {
+                check::valid_pool_signature(sig)?;
+                self.clone().apply_pool_registration(cert)?;
+                Ok(())
+            }
+            SignedCertificate::PoolRetirement(cert, sig) => {
+                check::valid_pool_retirement_certificate(cert)?;
+                check::valid_pool_signature(sig)?;
+                self.delegation.stake_pool_get(&cert.pool_id)?;
+                Ok(())
+            }
+            SignedCertificate::PoolUpdate(cert, sig) => {
+                self.valid_pool_update(cert, sig)?;
+                Ok(())
+            }
+            SignedCertificate::VotePlan(vote_plan, proof) => {
+                self.valid_vote_plan(vote_plan)?;
+                let committee = std::iter::once(proof.id).collect();
+                self.votes
+                    .add_vote_plan(block_date, vote_plan.clone(), committee)?;
+                Ok(())
+            }
+            SignedCertificate::VoteCast(vote, ()) => {
+                self.votes.validate_vote(block_date, vote)?;
+                Ok(())
+            }
+            SignedCertificate::VoteCastBatch(batch, ()) => {
+                if !batch.check_ballots() {
+                    return Err(Error::VoteCastBatchInvalidBallots);
+                }
+                for vote in batch.vote_casts() {
+                    self.votes.validate_vote(block_date, &vote)?;
+                }
+                Ok(())
+            }
+            SignedCertificate::VoteTally(tally, proof) => {
+                self.clone()
+                    .apply_vote_tally_verified(block_date, tally, proof.clone())?;
+                Ok(())
+            }
+            SignedCertificate::EncryptedVoteTally(tally, proof) => {
+                let stake = StakeControl::new_with(&self.accounts, &self.utxos);
+                self.votes
+                    .apply_encrypted_vote_tally(block_date, &stake, tally, proof.clone())?;
+                Ok(())
+            }
+            SignedCertificate::CommitteeHandover(handover, proof) => {
+                self.votes
+                    .apply_committee_handover(block_date, handover, proof.clone())?;
+                Ok(())
+            }
+        }
+    }
+
+    fn valid_delegation_type(&self, delegation: &DelegationType) -> Result<(), Error> {
+        let pools = match delegation {
+            DelegationType::NonDelegated => return Ok(()),
+            DelegationType::Full(pool_id) => vec![pool_id],
+            DelegationType::Ratio(ratio) => ratio.pools().iter().map(|(id, _)| id).collect(),
+        };
+        for pool_id in pools {
+            self.delegation.stake_pool_get(pool_id)?;
+        }
+        Ok(())
+    }
+
     pub fn get_stake_distribution(&self) -> StakeDistribution {
         stake::get_distribution(&self.accounts, &self.delegation, &self.utxos)
     }
@@ -1637,6 +1916,64 @@ fn apply_old_declaration(
     Ok(utxos)
 }
 
+/// apply the treasury action of a proposal that passed, telling if it could
+/// be applied
+///
+/// an action that cannot be applied as a whole, because the treasury does
+/// not cover it or a balance would overflow, leaves the pots and the
+/// accounts untouched.
+fn apply_treasury_action(
+    pots: &mut Pots,
+    accounts: &mut account::Ledger,
+    action: &TreasuryGovernanceAction,
+) -> bool {
+    match action {
+        TreasuryGovernanceAction::NoOp => true,
+        TreasuryGovernanceAction::TransferToRewards { value } => {
+            let mut updated = pots.clone();
+            let value = updated.draw_treasury(*value);
+            if updated.rewards_add(value).is_err() {
+                return false;
+            }
+            *pots = updated;
+            true
+        }
+        TreasuryGovernanceAction::TransferToAccount { account, value } => {
+            if pots.treasury_value() < *value {
+                return false;
+            }
+            match accounts.add_value_or_account(account, *value, ()) {
+                Ok(updated) => {
+                    let _ = pots.draw_treasury(*value);
+                    *accounts = updated;
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+        TreasuryGovernanceAction::TransferToAccounts { payees } => {
+            // either every payee is paid or none of them
+            let requested = match Value::sum(payees.iter().map(|(_, value)| *value)) {
+                Ok(requested) if requested <= pots.treasury_value() => requested,
+                _ => return false,
+            };
+            let updated = payees
+                .iter()
+                .try_fold(accounts.clone(), |updated, (account, value)| {
+                    updated.add_value_or_account(account, *value, ())
+                });
+            match updated {
+                Ok(updated) => {
+                    let _ = pots.draw_treasury(requested);
+                    *accounts = updated;
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+    }
+}
+
 fn calculate_fee<'a, Extra: Payload>(
     tx: &TransactionSlice<'a, Extra>,
     dyn_params: &LedgerParameters,
@@ -2818,4 +3155,47 @@ mod tests {
             .apply_transaction(test_tx.get_fragment())
             .is_err());
     }
+
+    #[test]
+    pub fn treasury_transfer_overflowing_the_account_is_not_applied() {
+        let account = TestGen::identifier();
+        let mut accounts = account::Ledger::new()
+            .add_account(&account, Value(u64::MAX), ())
+            .unwrap();
+        let mut pots = Pots::zero();
+        pots.treasury_add(Value(100)).unwrap();
+
+        let action = TreasuryGovernanceAction::TransferToAccount {
+            account: account.clone(),
+            value: Value(10),
+        };
+        assert!(!apply_treasury_action(&mut pots, &mut accounts, &action));
+        assert_eq!(pots.treasury_value(), Value(100));
+        assert_eq!(
+            accounts.get_state(&account).unwrap().value(),
+            Value(u64::MAX)
+        );
+    }
+
+    #[test]
+    pub fn treasury_transfer_overflowing_a_payee_pays_no_payee() {
+        let payee = TestGen::identifier();
+        let rich_payee = TestGen::identifier();
+        let mut accounts = account::Ledger::new()
+            .add_account(&rich_payee, Value(u64::MAX), ())
+            .unwrap();
+        let mut pots = Pots::zero();
+        pots.treasury_add(Value(100)).unwrap();
+
+        let action = TreasuryGovernanceAction::TransferToAccounts {
+            payees: vec![(payee.clone(), Value(10)), (rich_payee.clone(), Value(10))],
+        };
+        assert!(!apply_treasury_action(&mut pots, &mut accounts, &action));
+        assert_eq!(pots.treasury_value(), Value(100));
+        assert!(accounts.get_state(&payee).is_err());
+        assert_eq!(
+            accounts.get_state(&rich_payee).unwrap().value(),
+            Value(u64::MAX)
+        );
+    }
 }
@@ -41,7 +41,9 @@ use crate::account::AccountAlg;
 use crate::accounting::account::{
     AccountState, DelegationRatio, DelegationType, LastRewards, SpendingCounter,
 };
-use crate::certificate::{PoolId, PoolRegistration, Proposal, Proposals, VoteAction, VotePlan};
+use crate::certificate::{
+    PoolId, PoolRegistration, Proposal, Proposals, VoteAction, VotePlan, VotePlanVersion,
+};
 use crate::config::ConfigParam;
 use crate::date::BlockDate;
 use crate::fragment::FragmentId;
@@ -50,11 +52,11 @@ use crate::key::serialize_public_key;
 use crate::ledger::{Globals, Ledger, LedgerStaticParameters};
 use crate::legacy;
 use crate::multisig::{DeclElement, Declaration};
-use crate::stake::{PoolLastRewards, PoolState};
+use crate::stake::{PoolLastRewards, PoolState, Stake, StakeControl};
 use crate::transaction::Output;
 use crate::update::{UpdateProposal, UpdateProposalId, UpdateProposalState, UpdateVoterId};
 use crate::value::Value;
-use crate::vote;
+use crate::vote::{self, VotePlanManager};
 use crate::{config, key, multisig, utxo};
 use chain_addr::{Address, Discrimination};
 use chain_core::mempack::{ReadBuf, Readable};
@@ -63,6 +65,7 @@ use chain_crypto::AsymmetricPublicKey;
 use chain_ser::deser::{Deserialize, Serialize};
 use chain_ser::packer::Codec;
 use chain_time::era::{pack_time_era, unpack_time_era};
+use chain_time::DurationSeconds;
 use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::io::{self, BufRead, Read, Write};
@@ -697,18 +700,30 @@ fn pack_pool_state<W: std::io::Write>(
 ) -> Result<(), std::io::Error> {
     pack_pool_last_rewards(&pool_state.last_rewards, codec)?;
     pack_pool_registration(&pool_state.registration, codec)?;
+    if let Some(retirement_time) = pool_state.retirement_time {
+        codec.put_u64(retirement_time.into())?;
+    }
     Ok(())
 }
 
+/// the retirement time is only there for a pool packed as a
+/// `RetiringStakePool` entry
 fn unpack_pool_state<R: std::io::BufRead>(
     codec: &mut Codec<R>,
+    retiring: bool,
 ) -> Result<PoolState, std::io::Error> {
     let last_rewards = unpack_pool_last_rewards(codec)?;
     let registration = Arc::new(unpack_pool_registration(codec)?);
+    let retirement_time = if retiring {
+        Some(DurationSeconds::from(codec.get_u64()?).into())
+    } else {
+        None
+    };
 
     Ok(PoolState {
         last_rewards,
         registration,
+        retirement_time,
     })
 }
 
@@ -874,15 +889,6 @@ fn unpack_address<R: std::io::BufRead>(codec: &mut Codec<R>) -> Result<Address,
     }
 }
 
-fn pack_vote_proposal<W: std::io::Write>(
-    proposal: &Proposal,
-    codec: &mut Codec<W>,
-) -> Result<(), std::io::Error> {
-    pack_digestof(proposal.external_id(), codec)?;
-    codec.put_u8(proposal.options().as_byte())?;
-    Ok(())
-}
-
 fn unpack_proposal<R: std::io::BufRead>(codec: &mut Codec<R>) -> Result<Proposal, std::io::Error> {
     let external_id = unpack_digestof(codec)?;
     let options = vote::Options::new_length(codec.get_u8()?)
@@ -894,18 +900,10 @@ fn unpack_proposal<R: std::io::BufRead>(codec: &mut Codec<R>) -> Result<Proposal
 fn unpack_vote_action<R: std::io::BufRead>(
     _codec: &mut Codec<R>,
 ) -> Result<VoteAction, std::io::Error> {
-    todo!()
-}
-
-fn pack_vote_proposals<W: std::io::Write>(
-    proposals: &Proposals,
-    codec: &mut Codec<W>,
-) -> Result<(), std::io::Error> {
-    codec.put_u64(proposals.len() as u64)?;
-    for proposal in proposals.iter() {
-        pack_vote_proposal(proposal, codec)?;
-    }
-    Ok(())
+    Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "the vote plan entries of this layout do not record the proposal actions",
+    ))
 }
 
 fn unpack_proposals<R: std::io::BufRead>(
@@ -919,13 +917,6 @@ fn unpack_proposals<R: std::io::BufRead>(
     Ok(proposals)
 }
 
-fn pack_payload_type<W: std::io::Write>(
-    t: vote::PayloadType,
-    codec: &mut Codec<W>,
-) -> Result<(), std::io::Error> {
-    codec.put_u8(t as u8)
-}
-
 fn unpack_payload_type<R: std::io::BufRead>(
     codec: &mut Codec<R>,
 ) -> Result<vote::PayloadType, std::io::Error> {
@@ -966,19 +957,8 @@ fn unpack_committee_public_keys<R: BufRead>(
     Ok(result)
 }
 
-fn pack_vote_plan<W: std::io::Write>(
-    vote_plan: &VotePlan,
-    codec: &mut Codec<W>,
-) -> Result<(), std::io::Error> {
-    pack_block_date(vote_plan.vote_start(), codec)?;
-    pack_block_date(vote_plan.vote_end(), codec)?;
-    pack_block_date(vote_plan.committee_end(), codec)?;
-    pack_payload_type(vote_plan.payload_type(), codec)?;
-    pack_vote_proposals(vote_plan.proposals(), codec)?;
-    pack_committee_public_keys(vote_plan.committee_public_keys(), codec)?;
-    Ok(())
-}
-
+/// read a vote plan entry of the first layout, which only records the
+/// vote plans of the first version
 fn unpack_vote_plan<R: std::io::BufRead>(codec: &mut Codec<R>) -> Result<VotePlan, std::io::Error> {
     let vote_start = unpack_block_date(codec)?;
     let vote_end = unpack_block_date(codec)?;
@@ -993,9 +973,83 @@ fn unpack_vote_plan<R: std::io::BufRead>(codec: &mut Codec<R>) -> Result<VotePla
         proposals,
         payload_type,
         keys,
+    )
+    .with_version(VotePlanVersion::V1))
+}
+
+fn pack_vote_plan_manager<W: std::io::Write>(
+    vote_plan_manager: &VotePlanManager,
+    codec: &mut Codec<W>,
+) -> Result<(), std::io::Error> {
+    let byte_array = vote_plan_manager.plan().serialize();
+    let bytes = byte_array.as_slice();
+    codec.put_u64(bytes.len() as u64)?;
+    codec.put_bytes(bytes)?;
+    pack_committee_public_keys(vote_plan_manager.committee_public_keys(), codec)?;
+    match vote_plan_manager.recorded_voting_stake() {
+        None => codec.put_u8(0)?,
+        Some(stake) => {
+            codec.put_u8(1)?;
+            pack_stake_control(stake, codec)?;
+        }
+    }
+    Ok(())
+}
+
+fn unpack_vote_plan_manager<R: std::io::BufRead>(
+    codec: &mut Codec<R>,
+) -> Result<VotePlanManager, std::io::Error> {
+    let size = codec.get_u64()? as usize;
+    let bytes_buff = codec.get_bytes(size)?;
+    let vote_plan = VotePlan::read(&mut ReadBuf::from(&bytes_buff)).map_err(|err| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Error reading VotePlan data: {}", err),
+        )
+    })?;
+    let committee_public_keys = unpack_committee_public_keys(codec)?;
+    let voting_stake = match codec.get_u8()? {
+        0 => None,
+        1 => Some(unpack_stake_control(codec)?),
+        tag => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid voting stake tag {}", tag),
+            ))
+        }
+    };
+    Ok(VotePlanManager::restore(
+        vote_plan,
+        committee_public_keys,
+        voting_stake,
     ))
 }
 
+fn pack_stake_control<W: std::io::Write>(
+    stake_control: &StakeControl,
+    codec: &mut Codec<W>,
+) -> Result<(), std::io::Error> {
+    codec.put_u64(stake_control.unassigned().0)?;
+    codec.put_u64(stake_control.iter().count() as u64)?;
+    for (identifier, stake) in stake_control.iter() {
+        pack_account_identifier(identifier, codec)?;
+        codec.put_u64(stake.0)?;
+    }
+    Ok(())
+}
+
+fn unpack_stake_control<R: std::io::BufRead>(
+    codec: &mut Codec<R>,
+) -> Result<StakeControl, std::io::Error> {
+    let mut stake_control = StakeControl::new().add_unassigned(Stake(codec.get_u64()?));
+    let size = codec.get_u64()?;
+    for _ in 0..size {
+        let identifier = unpack_account_identifier(codec)?;
+        stake_control = stake_control.add_to(identifier, Stake(codec.get_u64()?));
+    }
+    Ok(stake_control)
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum EntrySerializeCode {
     Globals = 0,
@@ -1010,6 +1064,8 @@ enum EntrySerializeCode {
     StakePool = 9,
     LeaderParticipation = 10,
     VotePlan = 11,
+    RetiringStakePool = 12,
+    VersionedVotePlan = 13,
     SerializationEnd = 99,
 }
 
@@ -1028,6 +1084,8 @@ impl EntrySerializeCode {
             9 => Some(EntrySerializeCode::StakePool),
             10 => Some(EntrySerializeCode::LeaderParticipation),
             11 => Some(EntrySerializeCode::VotePlan),
+            12 => Some(EntrySerializeCode::RetiringStakePool),
+            13 => Some(EntrySerializeCode::VersionedVotePlan),
             99 => Some(EntrySerializeCode::SerializationEnd),
             _ => None,
         }
@@ -1080,7 +1138,13 @@ fn pack_entry<W: std::io::Write>(
             pack_declaration(declaration, codec)?;
         }
         Entry::StakePool((pool_id, pool_state)) => {
-            codec.put_u8(EntrySerializeCode::StakePool as u8)?;
+            // a pool with no retirement time keeps the layout it had before
+            // pools could be scheduled to retire
+            let code = match pool_state.retirement_time {
+                None => EntrySerializeCode::StakePool,
+                Some(_) => EntrySerializeCode::RetiringStakePool,
+            };
+            codec.put_u8(code as u8)?;
             pack_digestof(pool_id, codec)?;
             pack_pool_state(pool_state, codec)?;
         }
@@ -1089,9 +1153,9 @@ fn pack_entry<W: std::io::Write>(
             pack_digestof(pool_id, codec)?;
             codec.put_u32(**participation)?;
         }
-        Entry::VotePlan(vote_plan) => {
-            codec.put_u8(EntrySerializeCode::VotePlan as u8)?;
-            pack_vote_plan(vote_plan, codec)?;
+        Entry::VotePlan(vote_plan_manager) => {
+            codec.put_u8(EntrySerializeCode::VersionedVotePlan as u8)?;
+            pack_vote_plan_manager(vote_plan_manager, codec)?;
         }
     }
     Ok(())
@@ -1141,7 +1205,12 @@ fn unpack_entry_owned<R: std::io::BufRead>(
         }
         EntrySerializeCode::StakePool => {
             let pool_id = unpack_digestof(codec)?;
-            let pool_state = unpack_pool_state(codec)?;
+            let pool_state = unpack_pool_state(codec, false)?;
+            Ok(EntryOwned::StakePool((pool_id, pool_state)))
+        }
+        EntrySerializeCode::RetiringStakePool => {
+            let pool_id = unpack_digestof(codec)?;
+            let pool_state = unpack_pool_state(codec, true)?;
             Ok(EntryOwned::StakePool((pool_id, pool_state)))
         }
         EntrySerializeCode::LeaderParticipation => {
@@ -1151,7 +1220,14 @@ fn unpack_entry_owned<R: std::io::BufRead>(
         }
         EntrySerializeCode::VotePlan => {
             let vote_plan = unpack_vote_plan(codec)?;
-            Ok(EntryOwned::VotePlan(vote_plan))
+            Ok(EntryOwned::VotePlan(VotePlanManager::new(
+                vote_plan,
+                HashSet::new(),
+            )))
+        }
+        EntrySerializeCode::VersionedVotePlan => {
+            let vote_plan_manager = unpack_vote_plan_manager(codec)?;
+            Ok(EntryOwned::VotePlan(vote_plan_manager))
         }
         EntrySerializeCode::SerializationEnd => Ok(EntryOwned::StopEntry),
     }
@@ -1209,8 +1285,10 @@ impl Deserialize for Ledger {
 #[cfg(test)]
 pub mod test {
     use super::*;
-    use crate::testing::{ConfigBuilder, LedgerBuilder, StakePoolBuilder};
+    use crate::certificate::ExternalProposalId;
+    use crate::testing::{ConfigBuilder, LedgerBuilder, StakePoolBuilder, VoteTestGen};
     use cardano_legacy_address::Addr;
+    use chain_core::property::BlockDate as _;
     use chain_crypto::Blake2b256;
     use quickcheck::{quickcheck, TestResult};
     use std::io::Cursor;
@@ -1272,12 +1350,11 @@ pub mod test {
 
     #[test]
     pub fn delegation_ratio_pack_unpack_bijection() -> Result<(), std::io::Error> {
-        let fake_pool_id = StakePoolBuilder::new().build().id();
         let parts = 8u8;
         let pools: Vec<(PoolId, u8)> = vec![
-            (fake_pool_id.clone(), 2u8),
-            (fake_pool_id.clone(), 3u8),
-            (fake_pool_id, 3u8),
+            (StakePoolBuilder::new().build().id(), 2u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
         ];
 
         let mut c: Cursor<Vec<u8>> = Cursor::new(Vec::new());
@@ -1301,9 +1378,9 @@ pub mod test {
 
         let parts = 8u8;
         let pools: Vec<(PoolId, u8)> = vec![
-            (fake_pool_id.clone(), 2u8),
-            (fake_pool_id.clone(), 3u8),
-            (fake_pool_id, 3u8),
+            (fake_pool_id, 2u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
+            (StakePoolBuilder::new().build().id(), 3u8),
         ];
         let ratio = DelegationType::Ratio(DelegationRatio::new(parts, pools).unwrap());
 
@@ -1473,6 +1550,54 @@ pub mod test {
         Ok(())
     }
 
+    fn pack_first_layout_vote_plan_entry(
+        proposals: &[ExternalProposalId],
+    ) -> Result<Cursor<Vec<u8>>, std::io::Error> {
+        let mut codec = Codec::new(Cursor::new(Vec::new()));
+        codec.put_u8(EntrySerializeCode::VotePlan as u8)?;
+        pack_block_date(BlockDate::from_epoch_slot_id(1, 0), &mut codec)?;
+        pack_block_date(BlockDate::from_epoch_slot_id(2, 0), &mut codec)?;
+        pack_block_date(BlockDate::from_epoch_slot_id(3, 0), &mut codec)?;
+        codec.put_u8(vote::PayloadType::Public as u8)?;
+        codec.put_u64(proposals.len() as u64)?;
+        for external_id in proposals {
+            pack_digestof(external_id, &mut codec)?;
+            codec.put_u8(3)?;
+        }
+        codec.put_u8(0)?;
+        let mut c = codec.into_inner();
+        c.set_position(0);
+        Ok(c)
+    }
+
+    #[test]
+    pub fn first_layout_vote_plan_entry_unpack() -> Result<(), std::io::Error> {
+        let c = pack_first_layout_vote_plan_entry(&[])?;
+        match unpack_entry_owned(&mut Codec::new(c))? {
+            EntryOwned::VotePlan(vote_plan_manager) => {
+                let vote_plan = vote_plan_manager.plan();
+                assert_eq!(vote_plan.version(), VotePlanVersion::V1);
+                assert_eq!(vote_plan.vote_start(), BlockDate::from_epoch_slot_id(1, 0));
+                assert_eq!(
+                    vote_plan.committee_end(),
+                    BlockDate::from_epoch_slot_id(3, 0)
+                );
+                assert_eq!(vote_plan.payload_type(), vote::PayloadType::Public);
+                assert!(vote_plan_manager.recorded_voting_stake().is_none());
+            }
+            _ => panic!("expected a vote plan entry"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    pub fn first_layout_vote_plan_entry_without_actions() -> Result<(), std::io::Error> {
+        // the entries of the first layout do not record the proposal actions
+        let c = pack_first_layout_vote_plan_entry(&[VoteTestGen::external_proposal_id()])?;
+        assert!(unpack_entry_owned(&mut Codec::new(c)).is_err());
+        Ok(())
+    }
+
     #[cfg(test)]
     fn pack_unpack_bijection<T, Pack, Unpack>(
         pack_method: &Pack,
@@ -1590,13 +1715,72 @@ pub mod test {
         }
 
         fn pool_state_pack_unpack_bijection(pool_state: PoolState) -> TestResult {
+            let retiring = pool_state.retirement_time.is_some();
             pack_unpack_bijection(
                 &pack_pool_state,
-                &unpack_pool_state,
+                &|codec| unpack_pool_state(codec, retiring),
                 pool_state
             )
         }
 
+        fn stake_pool_entry_pack_unpack_bijection(pool_state: PoolState) -> TestResult {
+            let pool_id = pool_state.registration.to_id();
+            let mut codec = Codec::new(Cursor::new(Vec::new()));
+            if let Err(e) = pack_entry(&Entry::StakePool((&pool_id, &pool_state)), &mut codec) {
+                return TestResult::error(format!("{}", e));
+            }
+            let mut c = codec.into_inner();
+
+            // only a pool with a retirement time needs the new entry
+            let code = match pool_state.retirement_time {
+                None => EntrySerializeCode::StakePool,
+                Some(_) => EntrySerializeCode::RetiringStakePool,
+            };
+            if c.get_ref()[0] != code as u8 {
+                return TestResult::failed();
+            }
+
+            c.set_position(0);
+            match unpack_entry_owned(&mut Codec::new(c)) {
+                Ok(EntryOwned::StakePool((id, state))) => {
+                    TestResult::from_bool(id == pool_id && state == pool_state)
+                }
+                Ok(_) => TestResult::failed(),
+                Err(e) => TestResult::error(format!("{}", e)),
+            }
+        }
+
+        fn vote_plan_entry_pack_unpack_bijection(
+            vote_plan: VotePlan,
+            identifier: crate::account::Identifier,
+            stake: u64
+        ) -> TestResult {
+            // a plan handed over to another committee, with its voting stake recorded
+            let mut committee_public_keys = vote_plan.committee_public_keys().to_vec();
+            committee_public_keys.reverse();
+            let voting_stake = StakeControl::new()
+                .add_unassigned(Stake(1))
+                .add_to(identifier, Stake(stake));
+            let vote_plan_manager =
+                VotePlanManager::restore(vote_plan, committee_public_keys, Some(voting_stake));
+
+            let mut codec = Codec::new(Cursor::new(Vec::new()));
+            if let Err(e) = pack_entry(&Entry::VotePlan(&vote_plan_manager), &mut codec) {
+                return TestResult::error(format!("{}", e));
+            }
+            let mut c = codec.into_inner();
+            if c.get_ref()[0] != EntrySerializeCode::VersionedVotePlan as u8 {
+                return TestResult::failed();
+            }
+
+            c.set_position(0);
+            match unpack_entry_owned(&mut Codec::new(c)) {
+                Ok(EntryOwned::VotePlan(other)) => TestResult::from_bool(other == vote_plan_manager),
+                Ok(_) => TestResult::failed(),
+                Err(e) => TestResult::error(format!("{}", e)),
+            }
+        }
+
         fn pool_last_rewards_pack_unpack_bijection(pool_last_rewards: PoolLastRewards) -> TestResult {
             pack_unpack_bijection(
                 &pack_pool_last_rewards,
@@ -3,6 +3,7 @@ use crate::{
     accounting::account::LedgerError::ValueError,
     chaintypes::ChainLength,
     date::BlockDate,
+    fragment::{Fragment, FragmentRaw},
     ledger::{ledger::Error::Account, Error as LedgerError},
     testing::{
         builders::{GenesisPraosBlockBuilder, TestTxBuilder},
@@ -11,6 +12,7 @@ use crate::{
     },
     value::{Value, ValueError::NegativeAmount},
 };
+use chain_core::property::Deserialize as _;
 
 #[test]
 pub fn apply_block_increases_leaders_log() {
@@ -208,3 +210,33 @@ pub fn apply_block_above_max_content_size() {
         ledger.apply_block(block)
     );
 }
+
+fn unknown_certificate() -> Fragment {
+    // size, padding, unknown tag and some content
+    let raw = FragmentRaw::deserialize(&[0, 5, 0, 200, 1, 2, 3][..]).unwrap();
+    Fragment::from_raw_keep_unknown(&raw).unwrap()
+}
+
+#[test]
+pub fn apply_block_with_unknown_certificate() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_initials(vec![wallet("Bob").with(1_000).owns("stake_pool")])
+        .build()
+        .unwrap();
+
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+    let block = GenesisPraosBlockBuilder::new()
+        .with_date(BlockDate {
+            epoch: 0,
+            slot_id: 1,
+        })
+        .with_fragments(vec![unknown_certificate()])
+        .with_chain_length(ledger.chain_length())
+        .with_parent_id(ledger.block0_hash)
+        .build(&stake_pool, ledger.era());
+
+    assert_err!(
+        LedgerError::UnknownCertificate { tag: 200 },
+        ledger.apply_block(block)
+    );
+}
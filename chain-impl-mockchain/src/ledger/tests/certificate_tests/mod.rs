@@ -1,3 +1,4 @@
 pub mod pool_registration;
 pub mod pool_update;
+pub mod validate_certificate;
 pub mod voting;
@@ -103,6 +103,32 @@ pub fn pool_registration_management_threshold_above() {
     );
 }
 
+#[test]
+pub fn pool_registration_tax_ratio_above_one() {
+    let alice = Wallet::from_value(Value(100));
+
+    let test_ledger = LedgerBuilder::from_config(ConfigBuilder::new(0))
+        .faucets_wallets(vec![&alice])
+        .build()
+        .expect("cannot build test ledger");
+
+    let stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key()])
+        .with_pool_permissions(PoolPermissions::new(1))
+        .with_ratio_tax_type(3, 2, None)
+        .build();
+
+    // such a tax cannot be deserialized from a fragment, the registration
+    // is applied directly
+    assert_err!(
+        Error::PoolRegistrationTaxRatioAboveOne,
+        test_ledger
+            .ledger
+            .clone()
+            .apply_pool_registration(&stake_pool.info())
+    );
+}
+
 #[test]
 pub fn pool_registration_too_many_owners() {
     let alice = Wallet::from_value(Value(100));
@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use crate::{
-    certificate::{PoolRegistration, PoolUpdate},
+    certificate::{PoolPermissions, PoolRegistration, PoolUpdate},
     date::BlockDate,
     fee::LinearFee,
     ledger::ledger::{Block0Error, Error},
@@ -92,6 +92,48 @@ pub fn pool_update_wrong_last_hash() {
     );
 }
 
+#[test]
+pub fn pool_update_not_enough_signatures() {
+    let alice = Wallet::from_value(Value(100));
+    let bob = Wallet::from_value(Value(100));
+
+    let stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key(), bob.public_key()])
+        .with_pool_permissions(PoolPermissions::new(2))
+        .build();
+
+    let registration_certificate =
+        create_initial_stake_pool_registration(&stake_pool, &[alice.clone(), bob.clone()]);
+    let mut test_ledger = LedgerBuilder::from_config(ConfigBuilder::new(0))
+        .faucets_wallets(vec![&alice, &bob])
+        .certs(&[registration_certificate])
+        .build()
+        .unwrap();
+
+    let mut new_pool_registration = stake_pool.clone();
+    new_pool_registration.info_mut().serial = 1231u128;
+
+    let pool_update = PoolUpdate {
+        pool_id: stake_pool.id(),
+        last_pool_reg_hash: stake_pool.info().to_id(),
+        new_pool_reg: new_pool_registration.info(),
+    };
+    let certificate = build_stake_pool_update_cert(&pool_update);
+    let fragment = TestTxCertBuilder::new(test_ledger.block0_hash, test_ledger.fee())
+        .make_transaction(&[alice], &certificate);
+
+    assert_eq!(
+        test_ledger
+            .apply_fragment(&fragment, BlockDate::first())
+            .err()
+            .unwrap(),
+        Error::PoolUpdateNotEnoughSignatures {
+            actual: 1,
+            expected: 2
+        }
+    );
+}
+
 #[test]
 pub fn pool_update_not_enough_fee() {
     let alice = Wallet::from_value(Value(100));
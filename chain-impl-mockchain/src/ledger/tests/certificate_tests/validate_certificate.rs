@@ -0,0 +1,377 @@
+#![cfg(test)]
+
+use crate::{
+    certificate::{
+        EncryptedVoteTally, PoolPermissions, PoolUpdate, SignedCertificate, VoteCast, VotePlan,
+        VoteTally,
+    },
+    date::BlockDate,
+    fragment::Fragment,
+    ledger::ledger::Error,
+    stake::PoolError,
+    testing::{
+        builders::{
+            build_stake_delegation_cert, build_stake_pool_registration_cert,
+            build_stake_pool_retirement_cert, build_stake_pool_update_cert,
+            create_initial_stake_pool_registration, StakePoolBuilder, TestTxCertBuilder,
+        },
+        data::Wallet,
+        scenario::{committee, prepare_scenario, proposal, vote_plan, wallet, FragmentFactory},
+        ConfigBuilder, LedgerBuilder, TestLedger, VoteTestGen,
+    },
+    value::*,
+    vote::{Choice, Payload, PayloadType, VoteError, VotePlanLedgerError},
+};
+use imhamt::UpdateError;
+
+/// the certificate carried by the fragment, along with its signature
+fn signed_certificate(fragment: &Fragment) -> SignedCertificate {
+    match fragment {
+        Fragment::OwnerStakeDelegation(tx) => {
+            SignedCertificate::OwnerStakeDelegation(tx.as_slice().payload().into_payload(), ())
+        }
+        Fragment::StakeDelegation(tx) => {
+            let tx = tx.as_slice();
+            SignedCertificate::StakeDelegation(
+                tx.payload().into_payload(),
+                tx.payload_auth().into_payload_auth(),
+            )
+        }
+        Fragment::PoolRegistration(tx) => {
+            let tx = tx.as_slice();
+            SignedCertificate::PoolRegistration(
+                tx.payload().into_payload(),
+                tx.payload_auth().into_payload_auth(),
+            )
+        }
+        Fragment::PoolRetirement(tx) => {
+            let tx = tx.as_slice();
+            SignedCertificate::PoolRetirement(
+                tx.payload().into_payload(),
+                tx.payload_auth().into_payload_auth(),
+            )
+        }
+        Fragment::PoolUpdate(tx) => {
+            let tx = tx.as_slice();
+            SignedCertificate::PoolUpdate(
+                tx.payload().into_payload(),
+                tx.payload_auth().into_payload_auth(),
+            )
+        }
+        Fragment::VotePlan(tx) => {
+            let tx = tx.as_slice();
+            SignedCertificate::VotePlan(
+                tx.payload().into_payload(),
+                tx.payload_auth().into_payload_auth(),
+            )
+        }
+        Fragment::VoteCast(tx) => {
+            SignedCertificate::VoteCast(tx.as_slice().payload().into_payload(), ())
+        }
+        Fragment::VoteCastBatch(tx) => {
+            SignedCertificate::VoteCastBatch(tx.as_slice().payload().into_payload(), ())
+        }
+        Fragment::VoteTally(tx) => {
+            let tx = tx.as_slice();
+            SignedCertificate::VoteTally(
+                tx.payload().into_payload(),
+                tx.payload_auth().into_payload_auth(),
+            )
+        }
+        Fragment::EncryptedVoteTally(tx) => {
+            let tx = tx.as_slice();
+            SignedCertificate::EncryptedVoteTally(
+                tx.payload().into_payload(),
+                tx.payload_auth().into_payload_auth(),
+            )
+        }
+        Fragment::CommitteeHandover(tx) => {
+            let tx = tx.as_slice();
+            SignedCertificate::CommitteeHandover(
+                tx.payload().into_payload(),
+                tx.payload_auth().into_payload_auth(),
+            )
+        }
+        Fragment::Initial(_)
+        | Fragment::OldUtxoDeclaration(_)
+        | Fragment::Transaction(_)
+        | Fragment::UpdateProposal(_)
+        | Fragment::UpdateVote(_)
+        | Fragment::Unknown { .. } => panic!("the fragment does not carry a certificate"),
+    }
+}
+
+fn validate(test_ledger: &TestLedger, fragment: &Fragment) -> Result<(), Error> {
+    test_ledger
+        .ledger
+        .validate_certificate(&signed_certificate(fragment), test_ledger.date())
+}
+
+#[test]
+pub fn validate_stake_delegation_to_unknown_pool() {
+    let alice = Wallet::from_value(Value(100));
+    let stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key()])
+        .build();
+
+    let test_ledger = LedgerBuilder::from_config(ConfigBuilder::new(0))
+        .faucets_wallets(vec![&alice])
+        .build()
+        .unwrap();
+
+    let certificate = build_stake_delegation_cert(&stake_pool.info(), &alice.as_account_data());
+    let fragment = TestTxCertBuilder::new(test_ledger.block0_hash, test_ledger.fee())
+        .make_transaction(&[alice], &certificate);
+
+    assert_eq!(
+        validate(&test_ledger, &fragment),
+        Err(Error::Delegation(PoolError::NotFound(stake_pool.id())))
+    );
+}
+
+#[test]
+pub fn validate_stake_delegation_to_retired_pool() {
+    let mut alice = Wallet::from_value(Value(100));
+    let stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key()])
+        .build();
+
+    let registration_certificate =
+        create_initial_stake_pool_registration(&stake_pool, &[alice.clone()]);
+    let mut test_ledger = LedgerBuilder::from_config(ConfigBuilder::new(0))
+        .faucets_wallets(vec![&alice])
+        .certs(&[registration_certificate])
+        .build()
+        .unwrap();
+
+    let certificate = build_stake_delegation_cert(&stake_pool.info(), &alice.as_account_data());
+    let delegation = TestTxCertBuilder::new(test_ledger.block0_hash, test_ledger.fee())
+        .make_transaction(&[alice.clone()], &certificate);
+    assert_eq!(validate(&test_ledger, &delegation), Ok(()));
+
+    let retirement = TestTxCertBuilder::new(test_ledger.block0_hash, test_ledger.fee())
+        .make_transaction(
+            &[alice.clone()],
+            &build_stake_pool_retirement_cert(stake_pool.id(), 0),
+        );
+    test_ledger
+        .apply_fragment(&retirement, BlockDate::first())
+        .unwrap();
+    alice.confirm_transaction();
+
+    assert_eq!(
+        validate(&test_ledger, &delegation),
+        Err(Error::Delegation(PoolError::NotFound(stake_pool.id())))
+    );
+}
+
+#[test]
+pub fn validate_pool_registration_with_duplicate_owner() {
+    let alice = Wallet::from_value(Value(100));
+    let stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key(), alice.public_key()])
+        .build();
+
+    let mut test_ledger = LedgerBuilder::from_config(ConfigBuilder::new(0))
+        .faucets_wallets(vec![&alice])
+        .build()
+        .unwrap();
+
+    let certificate = build_stake_pool_registration_cert(&stake_pool.info());
+    let fragment = TestTxCertBuilder::new(test_ledger.block0_hash, test_ledger.fee())
+        .make_transaction(&[alice], &certificate);
+
+    let expected = Err(Error::PoolRegistrationHasDuplicateOwner);
+    assert_eq!(validate(&test_ledger, &fragment), expected);
+    assert_eq!(
+        test_ledger.apply_fragment(&fragment, BlockDate::first()),
+        expected
+    );
+}
+
+#[test]
+pub fn validate_pool_update_not_enough_signatures() {
+    let alice = Wallet::from_value(Value(100));
+    let bob = Wallet::from_value(Value(100));
+
+    let stake_pool = StakePoolBuilder::new()
+        .with_owners(vec![alice.public_key(), bob.public_key()])
+        .with_pool_permissions(PoolPermissions::new(2))
+        .build();
+
+    let registration_certificate =
+        create_initial_stake_pool_registration(&stake_pool, &[alice.clone(), bob.clone()]);
+    let test_ledger = LedgerBuilder::from_config(ConfigBuilder::new(0))
+        .faucets_wallets(vec![&alice, &bob])
+        .certs(&[registration_certificate])
+        .build()
+        .unwrap();
+
+    let mut new_pool_registration = stake_pool.clone();
+    new_pool_registration.info_mut().serial = 1231u128;
+
+    let pool_update = PoolUpdate {
+        pool_id: stake_pool.id(),
+        last_pool_reg_hash: stake_pool.info().to_id(),
+        new_pool_reg: new_pool_registration.info(),
+    };
+    let certificate = build_stake_pool_update_cert(&pool_update);
+    let fragment = TestTxCertBuilder::new(test_ledger.block0_hash, test_ledger.fee())
+        .make_transaction(&[alice], &certificate);
+
+    assert_eq!(
+        validate(&test_ledger, &fragment),
+        Err(Error::PoolUpdateNotEnoughSignatures {
+            actual: 1,
+            expected: 2
+        })
+    );
+}
+
+#[test]
+pub fn validate_vote_cast_outside_vote_window() {
+    const ALICE: &str = "Alice";
+    const VOTE_PLAN: &str = "fund1";
+
+    let (mut ledger, controller) = prepare_scenario()
+        .with_initials(vec![wallet(ALICE).with(1_000).committee_member()])
+        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
+            .owner(ALICE)
+            .consecutive_epoch_dates()
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_off_chain(),
+            )])
+        .build()
+        .unwrap();
+
+    let vote_plan: VotePlan = controller.vote_plan(VOTE_PLAN).unwrap().into();
+    let vote = VoteCast::new(vote_plan.to_id(), 0, Payload::public(Choice::new(1)));
+    let certificate = SignedCertificate::VoteCast(vote.clone(), ());
+
+    assert_eq!(
+        ledger
+            .ledger
+            .validate_certificate(&certificate, ledger.date()),
+        Ok(())
+    );
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    assert_eq!(
+        ledger
+            .ledger
+            .validate_certificate(&certificate, ledger.date()),
+        Err(Error::VotePlan(VotePlanLedgerError::VoteError {
+            id: vote_plan.to_id(),
+            reason: UpdateError::ValueCallbackError(VoteError::NotVoteTime {
+                start: vote_plan.vote_start(),
+                end: vote_plan.vote_end(),
+                vote,
+            }),
+        }))
+    );
+}
+
+#[test]
+pub fn validate_vote_tally_at_block_date() {
+    const ALICE: &str = "Alice";
+    const VOTE_PLAN: &str = "fund1";
+
+    let (ledger, controller) = prepare_scenario()
+        .with_initials(vec![wallet(ALICE).with(1_000).committee_member()])
+        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
+            .owner(ALICE)
+            .consecutive_epoch_dates()
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_off_chain(),
+            )])
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet(ALICE).unwrap();
+    let vote_plan: VotePlan = controller.vote_plan(VOTE_PLAN).unwrap().into();
+    let fragment = FragmentFactory::from_ledger(&ledger)
+        .vote_tally(&alice, VoteTally::new_public(vote_plan.to_id()));
+    let certificate = signed_certificate(&fragment);
+
+    // the ledger itself is still in the voting window
+    assert_eq!(
+        ledger
+            .ledger
+            .validate_certificate(&certificate, committee_date()),
+        Ok(())
+    );
+    assert_eq!(
+        ledger
+            .ledger
+            .validate_certificate(&certificate, ledger.date()),
+        Err(not_committee_time(&vote_plan))
+    );
+}
+
+#[test]
+pub fn validate_encrypted_vote_tally_at_block_date() {
+    const ALICE: &str = "Alice";
+    const VOTE_PLAN: &str = "fund1";
+    const COMMITTEE: &str = "committee";
+
+    let (ledger, controller) = prepare_scenario()
+        .with_initials(vec![wallet(ALICE).with(1_000).committee_member()])
+        .with_committees(vec![committee(COMMITTEE).members_no(3).threshold(2)])
+        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
+            .owner(ALICE)
+            .consecutive_epoch_dates()
+            .payload_type(PayloadType::Private)
+            .committee(COMMITTEE)
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_off_chain(),
+            )])
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet(ALICE).unwrap();
+    let vote_plan: VotePlan = controller.vote_plan(VOTE_PLAN).unwrap().into();
+    let fragment = FragmentFactory::from_ledger(&ledger)
+        .vote_encrypted_tally(&alice, EncryptedVoteTally::new(vote_plan.to_id()));
+    let certificate = signed_certificate(&fragment);
+
+    // the ledger itself is still in the voting window
+    assert_eq!(
+        ledger
+            .ledger
+            .validate_certificate(&certificate, committee_date()),
+        Ok(())
+    );
+    assert_eq!(
+        ledger
+            .ledger
+            .validate_certificate(&certificate, ledger.date()),
+        Err(not_committee_time(&vote_plan))
+    );
+}
+
+fn committee_date() -> BlockDate {
+    BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    }
+}
+
+fn not_committee_time(vote_plan: &VotePlan) -> Error {
+    Error::VotePlan(VotePlanLedgerError::VoteError {
+        id: vote_plan.to_id(),
+        reason: UpdateError::ValueCallbackError(VoteError::NotCommitteeTime {
+            start: vote_plan.committee_start(),
+            end: vote_plan.committee_end(),
+        }),
+    })
+}
@@ -5,7 +5,7 @@ use crate::{
     utxo,
 };
 use chain_addr::{Address, Kind};
-use imhamt::Hamt;
+use imhamt::{Hamt, HamtIter};
 use std::{collections::hash_map::DefaultHasher, fmt, num::NonZeroU64};
 
 #[derive(Default, Clone, Eq, PartialEq)]
@@ -69,6 +69,11 @@ impl StakeControl {
         self.unassigned
     }
 
+    /// iterate over the stake controlled by each account
+    pub fn iter(&self) -> HamtIter<'_, Identifier, Stake> {
+        self.control.iter()
+    }
+
     /// get the total stake controlled by the given account
     pub fn by(&self, identifier: &Identifier) -> Option<Stake> {
         self.control.lookup(identifier).copied()
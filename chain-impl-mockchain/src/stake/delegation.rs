@@ -1,6 +1,7 @@
 use crate::certificate::{PoolId, PoolRegistration, PoolRegistrationHash};
 use crate::date::Epoch;
 use crate::value::Value;
+use chain_time::TimeOffsetSeconds;
 use imhamt::Hamt;
 use std::collections::hash_map::DefaultHasher;
 use std::fmt::{self, Debug};
@@ -39,6 +40,9 @@ impl PoolLastRewards {
 pub struct PoolState {
     pub last_rewards: PoolLastRewards,
     pub registration: Arc<PoolRegistration>,
+    /// time at which the pool is scheduled to retire, if a retirement
+    /// certificate was applied before it was due
+    pub retirement_time: Option<TimeOffsetSeconds>,
 }
 
 impl PoolState {
@@ -46,6 +50,7 @@ impl PoolState {
         PoolState {
             last_rewards: PoolLastRewards::default(),
             registration: Arc::new(reg),
+            retirement_time: None,
         }
     }
 
@@ -160,6 +165,31 @@ impl PoolsState {
         Ok(())
     }
 
+    pub fn stake_pool_set_retirement(
+        &mut self,
+        pool_id: &PoolId,
+        retirement_time: TimeOffsetSeconds,
+    ) -> Result<(), PoolError> {
+        self.stake_pools = self
+            .stake_pools
+            .replace_with(pool_id, |st| {
+                let mut st = st.clone();
+                st.retirement_time = Some(retirement_time);
+                st
+            })
+            .map_err(|_| PoolError::NotFound(pool_id.clone()))?;
+        Ok(())
+    }
+
+    /// ids of the stake pools scheduled to retire at or before the given time
+    pub fn stake_pools_retiring_by(&self, time: TimeOffsetSeconds) -> Vec<PoolId> {
+        self.stake_pools
+            .iter()
+            .filter(|(_, st)| matches!(st.retirement_time, Some(t) if t <= time))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
     pub fn register_stake_pool(&self, owner: PoolRegistration) -> Result<Self, PoolError> {
         let id = owner.to_id();
         let new_pools = self
@@ -208,9 +238,12 @@ mod tests {
     impl Arbitrary for PoolState {
         fn arbitrary<G: Gen>(gen: &mut G) -> Self {
             let registration = Arc::new(PoolRegistration::arbitrary(gen));
+            let retirement_time = Option::<u64>::arbitrary(gen)
+                .map(|time| chain_time::DurationSeconds::from(time).into());
             PoolState {
                 last_rewards: PoolLastRewards::arbitrary(gen),
                 registration,
+                retirement_time,
             }
         }
     }
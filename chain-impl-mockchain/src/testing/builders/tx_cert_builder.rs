@@ -1,8 +1,9 @@
 use crate::certificate::EncryptedVoteTally;
 use crate::{
     certificate::{
-        Certificate, CertificatePayload, EncryptedVoteTallyProof, PoolOwnersSigned, PoolSignature,
-        TallyProof, VotePlan, VotePlanProof, VoteTally,
+        Certificate, CertificatePayload, CommitteeHandover, CommitteeHandoverProof,
+        EncryptedVoteTallyProof, PoolOwnersSigned, PoolSignature, TallyProof, VotePlan,
+        VotePlanProof, VoteTally,
     },
     chaintypes::HeaderId,
     fee::FeeAlgorithm,
@@ -161,6 +162,17 @@ impl TestTxCertBuilder {
                 let tx = builder.set_payload_auth(&());
                 Fragment::VoteCast(tx)
             }
+            Certificate::VoteCastBatch(vote_cast_batch) => {
+                let builder = self.set_initial_ios(
+                    TxBuilder::new().set_payload(vote_cast_batch),
+                    funder,
+                    inputs,
+                    outputs,
+                    make_witness,
+                );
+                let tx = builder.set_payload_auth(&());
+                Fragment::VoteCastBatch(tx)
+            }
             Certificate::VoteTally(vt) => {
                 let builder = self.set_initial_ios(
                     TxBuilder::new().set_payload(vt),
@@ -185,6 +197,18 @@ impl TestTxCertBuilder {
                 let tx = builder.set_payload_auth(&committee_signature);
                 Fragment::EncryptedVoteTally(tx)
             }
+            Certificate::CommitteeHandover(handover) => {
+                let builder = self.set_initial_ios(
+                    TxBuilder::new().set_payload(handover),
+                    funder,
+                    inputs,
+                    outputs,
+                    make_witness,
+                );
+                let committee_signature = handover_sign(&keys, &builder);
+                let tx = builder.set_payload_auth(&committee_signature);
+                Fragment::CommitteeHandover(tx)
+            }
         }
     }
 
@@ -247,6 +271,18 @@ pub fn encrypted_tally_sign(
     EncryptedVoteTallyProof { id, signature }
 }
 
+pub fn handover_sign(
+    keys: &[EitherEd25519SecretKey],
+    builder: &TxBuilderState<SetAuthData<CommitteeHandover>>,
+) -> CommitteeHandoverProof {
+    let key: EitherEd25519SecretKey = keys[0].clone();
+    let id = key.to_public().into();
+
+    let auth_data = builder.get_auth_data();
+    let signature = SingleAccountBindingSignature::new(&auth_data, |d| key.sign_slice(d.0));
+    CommitteeHandoverProof { id, signature }
+}
+
 pub fn plan_sign(
     keys: &[EitherEd25519SecretKey],
     builder: &TxBuilderState<SetAuthData<VotePlan>>,
@@ -0,0 +1,254 @@
+use crate::{
+    certificate::TallyDecryptShares,
+    vote::{PayloadType, VotePlanStatus},
+};
+use chain_vote::{
+    committee::MemberSecretKey, MemberCommunicationKey, MemberPublicKey, MemberState, CRS,
+};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+
+/// Keys of a committee and of each of its members, as generated by the
+/// committee members before a private vote plan is submitted
+pub struct CommitteeSetup {
+    crs: CRS,
+    communication_keys: Vec<MemberCommunicationKey>,
+    states: Vec<MemberState>,
+    secret_keys: Vec<MemberSecretKey>,
+}
+
+pub struct CommitteeMembersManager {
+    alias: String,
+    members: Vec<CommitteeMember>,
+}
+
+pub struct CommitteeMember {
+    secret_key: MemberSecretKey,
+}
+
+impl CommitteeMembersManager {
+    pub fn new(
+        alias: &str,
+        rng: &mut (impl RngCore + CryptoRng),
+        threshold: usize,
+        members_no: usize,
+    ) -> Self {
+        let members = CommitteeSetup::new(rng, threshold, members_no)
+            .secret_keys
+            .into_iter()
+            .map(|secret_key| CommitteeMember { secret_key })
+            .collect();
+
+        Self {
+            alias: alias.to_owned(),
+            members,
+        }
+    }
+
+    pub fn alias(&self) -> String {
+        self.alias.clone()
+    }
+
+    pub fn members(&self) -> &[CommitteeMember] {
+        &self.members
+    }
+
+    pub fn public_keys(&self) -> Vec<MemberPublicKey> {
+        self.members
+            .iter()
+            .map(|committee_member| committee_member.public_key())
+            .collect()
+    }
+
+    pub fn decrypt_shares(
+        &self,
+        rng: &mut (impl RngCore + CryptoRng),
+        vote_plan_status: &VotePlanStatus,
+    ) -> TallyDecryptShares {
+        let member_indices = (0..=u8::MAX).take(self.members.len()).collect::<Vec<_>>();
+        self.decrypt_shares_from(rng, vote_plan_status, &member_indices)
+    }
+
+    /// decrypt shares of the committee members at the given indices only
+    pub fn decrypt_shares_from(
+        &self,
+        rng: &mut (impl RngCore + CryptoRng),
+        vote_plan_status: &VotePlanStatus,
+        member_indices: &[u8],
+    ) -> TallyDecryptShares {
+        let members_shares = member_indices
+            .iter()
+            .map(|member_index| {
+                vote_plan_status
+                    .member_decrypt_shares(
+                        rng,
+                        self.members[usize::from(*member_index)].secret_key(),
+                    )
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // shares are only computed for the private proposals
+        let private_proposals = vote_plan_status
+            .proposals
+            .iter()
+            .filter(|proposal| proposal.payload == PayloadType::Private)
+            .count();
+        let shares = (0..private_proposals)
+            .map(|private_index| {
+                member_indices
+                    .iter()
+                    .zip(members_shares.iter())
+                    .map(|(member_index, shares)| (*member_index, shares[private_index].clone()))
+                    .collect()
+            })
+            .collect();
+        TallyDecryptShares::from_members(shares)
+    }
+
+    /// hand the committee over to `members_no` new members, any `threshold`
+    /// of which decrypt the tally, the keys of the current members at the
+    /// given indices being reshared to them
+    pub fn handover(
+        &self,
+        rng: &mut (impl RngCore + CryptoRng),
+        member_indices: &[usize],
+        threshold: usize,
+        members_no: usize,
+    ) -> Self {
+        let communication_keys = (0..members_no)
+            .map(|_| MemberCommunicationKey::new(rng))
+            .collect::<Vec<_>>();
+        let communication_public_keys = communication_keys
+            .iter()
+            .map(MemberCommunicationKey::to_public)
+            .collect::<Vec<_>>();
+
+        let dealt = member_indices
+            .iter()
+            .map(|member_index| {
+                self.members[*member_index].secret_key().reshare(
+                    rng,
+                    threshold,
+                    &communication_public_keys,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let previous = self.public_keys();
+        let members = communication_keys
+            .iter()
+            .enumerate()
+            .map(|(i, communication_key)| {
+                let shares = member_indices
+                    .iter()
+                    .zip(dealt.iter())
+                    .map(|(member_index, shares)| (*member_index, shares[i].clone()))
+                    .collect::<Vec<_>>();
+                let secret_key =
+                    MemberSecretKey::from_handover(communication_key, i, &previous, &shares)
+                        .unwrap();
+                CommitteeMember { secret_key }
+            })
+            .collect();
+
+        Self {
+            alias: self.alias.clone(),
+            members,
+        }
+    }
+}
+
+impl CommitteeSetup {
+    pub fn new(rng: &mut (impl RngCore + CryptoRng), threshold: usize, members_no: usize) -> Self {
+        let communication_keys = (0..members_no)
+            .map(|_| MemberCommunicationKey::new(rng))
+            .collect::<Vec<_>>();
+        let communication_public_keys = communication_keys
+            .iter()
+            .map(MemberCommunicationKey::to_public)
+            .collect::<Vec<_>>();
+
+        let crs = CRS::random(rng);
+
+        let states = (0..members_no)
+            .map(|i| MemberState::new(rng, threshold, &crs, &communication_public_keys, i))
+            .collect::<Vec<_>>();
+
+        // every member combines the shares dealt to it by the whole committee
+        // so that any `threshold` of them can decrypt the tally
+        let secret_keys = states
+            .iter()
+            .zip(communication_keys.iter())
+            .enumerate()
+            .map(|(i, (state, communication_key))| {
+                let shares = states
+                    .iter()
+                    .map(|dealer| dealer.share_for(i).unwrap())
+                    .collect::<Vec<_>>();
+                state
+                    .threshold_secret_key(communication_key, &shares)
+                    .unwrap()
+            })
+            .collect();
+
+        Self {
+            crs,
+            communication_keys,
+            states,
+            secret_keys,
+        }
+    }
+
+    /// build the same committee every time the same seed is given
+    pub fn deterministic(seed: [u8; 32], threshold: usize, members_no: usize) -> Self {
+        Self::new(&mut ChaCha20Rng::from_seed(seed), threshold, members_no)
+    }
+
+    pub fn crs(&self) -> &CRS {
+        &self.crs
+    }
+
+    pub fn communication_keys(&self) -> &[MemberCommunicationKey] {
+        &self.communication_keys
+    }
+
+    pub fn member_states(&self) -> &[MemberState] {
+        &self.states
+    }
+
+    pub fn secret_keys(&self) -> &[MemberSecretKey] {
+        &self.secret_keys
+    }
+
+    pub fn public_keys(&self) -> Vec<MemberPublicKey> {
+        self.secret_keys.iter().map(|key| key.to_public()).collect()
+    }
+}
+
+impl CommitteeMember {
+    pub fn public_key(&self) -> MemberPublicKey {
+        self.secret_key.to_public()
+    }
+
+    pub fn secret_key(&self) -> &MemberSecretKey {
+        &self.secret_key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn committee_setup_is_deterministic() {
+        let setup = CommitteeSetup::deterministic([1u8; 32], 2, 3);
+        let same = CommitteeSetup::deterministic([1u8; 32], 2, 3);
+        assert_eq!(setup.crs(), same.crs());
+        assert_eq!(setup.public_keys(), same.public_keys());
+
+        let other = CommitteeSetup::deterministic([2u8; 32], 2, 3);
+        assert_ne!(setup.crs(), other.crs());
+        assert_ne!(setup.public_keys(), other.public_keys());
+    }
+}
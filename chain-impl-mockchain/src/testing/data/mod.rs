@@ -1,10 +1,12 @@
 mod address;
+mod committee;
 mod keys;
 mod leader;
 mod stake_pool;
 mod wallet;
 
 pub use address::*;
+pub use committee::*;
 pub use keys::KeysDb;
 pub use leader::*;
 pub use stake_pool::*;
@@ -1,6 +1,7 @@
 pub mod fees;
 pub mod management_threshold;
 pub mod owner_delegation;
+pub mod pool_retirement;
 pub mod pool_update;
 pub mod rewards;
 pub mod stake_distribution;
@@ -79,3 +79,43 @@ pub fn delegation_for_non_existing_account() {
         .and()
         .pools_total_stake_is(Stake::from_value(Value::zero()));
 }
+
+#[test]
+pub fn owner_delegation_to_many_stake_pools() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new(0)
+                .with_discrimination(Discrimination::Test)
+                .with_fee(LinearFee::new(1, 1, 1)),
+        )
+        .with_initials(vec![
+            wallet("Alice").with(1_000).owns("alice_stake_pool"),
+            wallet("Bob").with(1_000).owns("bob_stake_pool"),
+            wallet("Clarice").with(603),
+        ])
+        .build()
+        .unwrap();
+
+    let alice_stake_pool = controller.stake_pool("alice_stake_pool").unwrap();
+    let bob_stake_pool = controller.stake_pool("bob_stake_pool").unwrap();
+    let mut clarice = controller.wallet("Clarice").unwrap();
+
+    controller
+        .delegates_to_many(
+            &clarice,
+            &[(&alice_stake_pool, 1u8), (&bob_stake_pool, 2u8)],
+            &mut ledger,
+        )
+        .unwrap();
+    clarice.confirm_transaction();
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("after owner delegation of 1/3 and 2/3 of stake")
+        .distribution()
+        .pools_distribution_is(vec![
+            (alice_stake_pool.id(), Value(200)),
+            (bob_stake_pool.id(), Value(400)),
+        ])
+        .and()
+        .dangling_is(Stake::from_value(Value::zero()));
+}
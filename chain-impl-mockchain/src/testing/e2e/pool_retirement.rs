@@ -0,0 +1,133 @@
+use crate::{
+    chaintypes::ConsensusType,
+    date::BlockDate,
+    fee::LinearFee,
+    milli::Milli,
+    stake::Stake,
+    testing::{
+        ledger::ConfigBuilder,
+        scenario::{prepare_scenario, wallet},
+        verifiers::LedgerStateVerifier,
+    },
+    value::Value,
+};
+
+const SLOT_DURATION: u8 = 10;
+const SLOTS_PER_EPOCH: u32 = 10;
+
+#[test]
+pub fn pool_retirement_is_applied_at_retirement_time() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new(0)
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_slot_duration(SLOT_DURATION)
+                .with_slots_per_epoch(SLOTS_PER_EPOCH),
+        )
+        .with_initials(vec![
+            wallet("Alice").with(1_000).owns("stake_pool"),
+            wallet("Bob").with(1_000).delegates_to("stake_pool"),
+        ])
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet("Alice").unwrap();
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+
+    // retire at the beginning of the next epoch
+    let retirement_time = SLOTS_PER_EPOCH as u64 * SLOT_DURATION as u64;
+    assert!(controller
+        .retire_at(Some(&alice), &stake_pool, retirement_time, &mut ledger)
+        .is_ok());
+
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("stake pool is still active until its retirement time")
+        .stake_pools()
+        .is_not_retired(&stake_pool);
+
+    assert!(ledger.produce_empty_block(&stake_pool).is_ok());
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 0,
+        slot_id: SLOTS_PER_EPOCH - 1,
+    });
+    ledger.apply_protocol_changes().unwrap();
+
+    let mut verifier = LedgerStateVerifier::new(ledger.into());
+    verifier.info("stake pool is retired in the next epoch");
+    verifier.stake_pools().is_retired(&stake_pool);
+    verifier
+        .distribution()
+        .dangling_is(Stake::from_value(Value(1_000)))
+        .and()
+        .pools_total_stake_is(Stake::from_value(Value::zero()));
+}
+
+#[test]
+pub fn pool_retirement_is_not_applied_before_retirement_time() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new(0)
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_slot_duration(SLOT_DURATION)
+                .with_slots_per_epoch(SLOTS_PER_EPOCH),
+        )
+        .with_initials(vec![wallet("Alice").with(1_000).owns("stake_pool")])
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet("Alice").unwrap();
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+
+    // retire at the beginning of the epoch after the next one
+    let retirement_time = 2 * SLOTS_PER_EPOCH as u64 * SLOT_DURATION as u64;
+    assert!(controller
+        .retire_at(Some(&alice), &stake_pool, retirement_time, &mut ledger)
+        .is_ok());
+
+    ledger.apply_protocol_changes().unwrap();
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("stake pool is not retired before its retirement time")
+        .stake_pools()
+        .is_not_retired(&stake_pool);
+}
+
+#[test]
+pub fn retired_pool_is_no_longer_block_leader() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new(0)
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_consensus_version(ConsensusType::GenesisPraos)
+                // every slot has a leader: the pool owning all the stake
+                .with_active_slots_coeff(Milli::ONE)
+                .with_slot_duration(SLOT_DURATION)
+                .with_slots_per_epoch(SLOTS_PER_EPOCH),
+        )
+        .with_initials(vec![
+            wallet("Alice").with(1_000).owns("stake_pool"),
+            wallet("Bob").with(1_000).delegates_to("stake_pool"),
+        ])
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet("Alice").unwrap();
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+
+    let next_slot = BlockDate {
+        epoch: 0,
+        slot_id: 1,
+    };
+    LedgerStateVerifier::new(ledger.clone().into())
+        .info("stake pool owns all the stake")
+        .is_block_leader(&stake_pool, next_slot);
+
+    assert!(controller
+        .retire(Some(&alice), &stake_pool, &mut ledger)
+        .is_ok());
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("retired stake pool has no stake left")
+        .is_not_block_leader(&stake_pool, next_slot);
+}
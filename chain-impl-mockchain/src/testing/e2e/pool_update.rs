@@ -1,13 +1,19 @@
 use crate::{
+    config::RewardParams,
     fee::LinearFee,
+    rewards::Ratio,
     stake::Stake,
     testing::{
+        data::AddressData,
         ledger::ConfigBuilder,
         scenario::{prepare_scenario, stake_pool, wallet},
         verifiers::LedgerStateVerifier,
     },
+    transaction::AccountIdentifier,
     value::Value,
 };
+use chain_addr::Discrimination;
+use std::num::{NonZeroU32, NonZeroU64};
 
 #[test]
 pub fn delegations_are_preserved_after_pool_update() {
@@ -265,3 +271,55 @@ pub fn pool_update_revert_changes() {
         .stake_pools()
         .is_retired(&new_stake_pool);
 }
+
+#[test]
+pub fn pool_update_changes_reward_account() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new(0)
+                .with_rewards(Value(1000))
+                .with_treasury(Value(0))
+                .with_rewards_params(RewardParams::Linear {
+                    constant: 100,
+                    ratio: Ratio {
+                        numerator: 1,
+                        denominator: NonZeroU64::new(1).unwrap(),
+                    },
+                    epoch_start: 0,
+                    epoch_rate: NonZeroU32::new(1).unwrap(),
+                }),
+        )
+        .with_initials(vec![wallet("Alice").with(1_000).owns("stake_pool")])
+        .with_stake_pools(vec![stake_pool("stake_pool")
+            .with_reward_account(true)
+            .tax_ratio(1, 10)])
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet("Alice").unwrap();
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+    let old_reward_account = stake_pool.reward_account().unwrap().clone();
+    let new_reward_account = AddressData::account(Discrimination::Test);
+
+    let mut new_stake_pool = stake_pool.clone();
+    new_stake_pool.info_mut().reward_account =
+        Some(AccountIdentifier::Single(new_reward_account.to_id()));
+
+    assert!(controller
+        .update(&stake_pool, new_stake_pool, vec![&alice], &mut ledger)
+        .is_ok());
+
+    assert!(ledger.produce_empty_block(&stake_pool).is_ok());
+    ledger.distribute_rewards().unwrap();
+
+    let mut ledger_verifier = LedgerStateVerifier::new(ledger.into());
+    ledger_verifier.info("after rewards distribution to the updated reward account");
+
+    ledger_verifier
+        .account(new_reward_account)
+        .has_value(&Value(9))
+        .and()
+        .has_last_reward(&Value(9));
+
+    ledger_verifier.account(old_reward_account).does_not_exist();
+}
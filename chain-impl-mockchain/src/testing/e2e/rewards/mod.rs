@@ -455,6 +455,66 @@ pub fn rewards_stake_pool_with_delegation() {
         .has_value(&Value(1050));
 }
 
+#[test]
+pub fn rewards_stake_pool_with_fixed_and_ratio_tax_and_uneven_delegation() {
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new(0)
+                .with_rewards(Value(1000))
+                .with_treasury(Value(0))
+                .with_rewards_params(RewardParams::Linear {
+                    constant: 100,
+                    ratio: Ratio {
+                        numerator: 1,
+                        denominator: NonZeroU64::new(1).unwrap(),
+                    },
+                    epoch_start: 0,
+                    epoch_rate: NonZeroU32::new(1).unwrap(),
+                }),
+        )
+        .with_initials(vec![
+            wallet("Alice").with(1_000).owns("stake_pool"),
+            wallet("Bob").with(1_000).delegates_to("stake_pool"),
+            wallet("Clarice").with(3_000).delegates_to("stake_pool"),
+        ])
+        .with_stake_pools(vec![stake_pool("stake_pool")
+            .tax_ratio(1, 10)
+            .fixed_tax(10)])
+        .build()
+        .unwrap();
+
+    let stake_pool = controller.stake_pool("stake_pool").unwrap();
+    let alice = controller.wallet("Alice").unwrap();
+    let bob = controller.wallet("Bob").unwrap();
+    let clarice = controller.wallet("Clarice").unwrap();
+
+    assert!(ledger.produce_empty_block(&stake_pool).is_ok());
+
+    ledger.distribute_rewards().unwrap();
+
+    let mut ledger_verifier = LedgerStateVerifier::new(ledger.into());
+    ledger_verifier.info("after rewards distribution with fixed and ratio tax");
+
+    // 99 of rewards: the owner gets the fixed tax of 10 plus a tenth of
+    // the 89 left (8), the delegators split the remaining 81 by stake and
+    // the rounding leftover goes to the treasury
+    ledger_verifier
+        .pots()
+        .has_treasury_equals_to(&Value(1))
+        .and()
+        .has_remaining_rewards_equals_to(&Value(901));
+
+    ledger_verifier
+        .account(alice.as_account_data())
+        .has_value(&Value(1018));
+    ledger_verifier
+        .account(bob.as_account_data())
+        .has_value(&Value(1020));
+    ledger_verifier
+        .account(clarice.as_account_data())
+        .has_value(&Value(3060));
+}
+
 #[test]
 pub fn rewards_total_amount_is_constant_after_reward_distribution() {
     let (mut ledger, controller) = prepare_scenario()
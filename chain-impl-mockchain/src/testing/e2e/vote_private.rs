@@ -3,66 +3,29 @@ use crate::{
     certificate::{TallyDecryptShares, VotePlan},
     fee::LinearFee,
     header::BlockDate,
+    ledger::Error as LedgerError,
+    stake::Stake,
     testing::{
+        data::{AddressData, CommitteeMembersManager},
         ledger::ConfigBuilder,
-        scenario::{prepare_scenario, proposal, vote_plan, wallet},
+        scenario::{
+            committee, prepare_scenario, prepare_vote_scenario, proposal, vote_plan, wallet, ALICE,
+            COMMITTEE, STAKE_POOL, VOTE_PLAN,
+        },
         verifiers::LedgerStateVerifier,
     },
     value::Value,
-    vote::{Choice, PayloadType},
-};
-use chain_vote::{
-    committee::MemberSecretKey, MemberCommunicationKey, MemberPublicKey, MemberState, CRS,
+    vote::{
+        Choice, Decision, Payload, PayloadType, PrivateTallyState, Tally, TallyError, VoteError,
+        VotePlanLedgerError,
+    },
 };
+use chain_addr::Discrimination;
+use imhamt::UpdateError;
 use rand_chacha::ChaCha20Rng;
-use rand_core::{CryptoRng, RngCore, SeedableRng};
-
-const ALICE: &str = "Alice";
-const STAKE_POOL: &str = "stake_pool";
-const VOTE_PLAN: &str = "fund1";
-
-struct CommitteeMembersManager {
-    members: Vec<CommitteeMember>,
-}
-
-struct CommitteeMember {
-    state: MemberState,
-}
-
-impl CommitteeMembersManager {
-    pub fn new(rng: &mut (impl RngCore + CryptoRng), threshold: usize, members_no: usize) -> Self {
-        let mut public_keys = Vec::new();
-        for _ in 0..members_no {
-            let private_key = MemberCommunicationKey::new(rng);
-            let public_key = private_key.to_public();
-            public_keys.push(public_key);
-        }
-
-        let crs = CRS::random(rng);
-
-        let mut members = Vec::new();
-        for i in 0..members_no {
-            let state = MemberState::new(rng, threshold, &crs, &public_keys, i);
-            members.push(CommitteeMember { state })
-        }
-
-        Self { members }
-    }
-
-    pub fn members(&self) -> &[CommitteeMember] {
-        &self.members
-    }
-}
-
-impl CommitteeMember {
-    pub fn public_key(&self) -> MemberPublicKey {
-        self.state.public_key()
-    }
+use rand_core::SeedableRng;
 
-    pub fn secret_key(&self) -> &MemberSecretKey {
-        self.state.secret_key()
-    }
-}
+const BOB: &str = "Bob";
 
 #[test]
 pub fn private_vote_cast_action_transfer_to_rewards_all_shares() {
@@ -73,39 +36,21 @@ pub fn private_vote_cast_action_transfer_to_rewards_all_shares() {
 
     let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
 
-    let members = CommitteeMembersManager::new(&mut rng, THRESHOLD, MEMBERS_NO);
-
-    let committee_keys = members
-        .members()
-        .iter()
-        .map(|committee_member| committee_member.public_key())
-        .collect::<Vec<_>>();
-
-    let (mut ledger, controller) = prepare_scenario()
-        .with_config(
-            ConfigBuilder::new(0)
-                .with_fee(LinearFee::new(1, 1, 1))
-                .with_rewards(Value(1000)),
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_rewards(100),
         )
-        .with_initials(vec![wallet(ALICE)
-            .with(1_000)
-            .owns(STAKE_POOL)
-            .committee_member()])
-        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
-            .owner(ALICE)
-            .consecutive_epoch_dates()
-            .payload_type(PayloadType::Private)
-            .committee_keys(committee_keys)
-            .with_proposal(
-                proposal(VoteTestGen::external_proposal_id())
-                    .options(3)
-                    .action_transfer_to_rewards(100),
-            )])
         .build()
         .unwrap();
 
     let mut alice = controller.wallet(ALICE).unwrap();
     let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
     let proposal = vote_plan.proposal(0);
 
     controller
@@ -130,43 +75,34 @@ pub fn private_vote_cast_action_transfer_to_rewards_all_shares() {
         .unwrap();
     alice.confirm_transaction();
 
-    let shares = ledger
-        .ledger
-        .active_vote_plans()
-        .iter()
-        .find(|c_vote_plan| {
-            let vote_plan: VotePlan = vote_plan.clone().into();
-            c_vote_plan.id == vote_plan.to_id()
-        })
+    let shares = controller
+        .active_vote_plan(&vote_plan, &ledger)
         .unwrap()
         .proposals
         .iter()
-        .map(|proposal| {
-            proposal
-                .tally
-                .as_ref()
-                .unwrap()
-                .private_encrypted()
-                .unwrap()
-                .0
-                .clone()
-        })
+        .map(|proposal| proposal.encrypted_tally().unwrap().clone())
         .map(|encrypted_tally| {
             members
                 .members()
                 .iter()
                 .map(|member| member.secret_key())
-                .map(|secret_key| encrypted_tally.finish(secret_key).1)
+                .map(|secret_key| encrypted_tally.finish(&mut rng, secret_key).1)
                 .collect::<Vec<_>>()
         })
         .collect::<Vec<_>>();
 
     let shares = TallyDecryptShares::new(shares);
 
-    controller
+    let results = controller
         .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
         .unwrap();
 
+    // Alice paid the fee of her ballot by the end of the voting window
+    let mut expected = [0.into(); 3];
+    expected[favorable.as_byte() as usize] = (1_000 - 3).into();
+    assert_eq!(results[0].results(), &expected[..]);
+    assert_eq!(results[0].winner(), Some(favorable));
+
     ledger.fast_forward_to(BlockDate {
         epoch: 1,
         slot_id: 1,
@@ -181,30 +117,1824 @@ pub fn private_vote_cast_action_transfer_to_rewards_all_shares() {
 }
 
 #[test]
-#[should_panic]
-pub fn private_vote_plan_without_keys() {
-    let committee_keys = vec![];
+pub fn private_vote_cast_action_transfer_to_account() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
 
-    let (_ledger, _controller) = prepare_scenario()
-        .with_config(
-            ConfigBuilder::new(0)
-                .with_fee(LinearFee::new(1, 1, 1))
-                .with_rewards(Value(1000)),
+    let favorable = Choice::new(1);
+    let beneficiary = AddressData::account(Discrimination::Test);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_treasury(Value(1000))
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_account(beneficiary.to_id(), 100),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            favorable,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+    let shares = members.decrypt_shares(&mut rng, &vote_plan_status);
+
+    controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("treasury funds are transferred to the account")
+        .account_has_expected_balance(beneficiary, Value(100))
+        .pots()
+        .has_treasury_equals_to(&Value(900));
+}
+
+#[test]
+pub fn private_vote_cast_action_transfer_to_account_not_enough_treasury() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+    let beneficiary = AddressData::account(Discrimination::Test);
+    let other_beneficiary = AddressData::account(Discrimination::Test);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_treasury(Value(50))
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_account(beneficiary.to_id(), 100),
+        )
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_account(other_beneficiary.to_id(), 30),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+
+    for proposal in vote_plan.proposals() {
+        controller
+            .cast_vote_private(
+                &alice,
+                &vote_plan,
+                &proposal.id(),
+                favorable,
+                &mut ledger,
+                &mut rng,
+            )
+            .unwrap();
+        alice.confirm_transaction();
+    }
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+    let shares = members.decrypt_shares(&mut rng, &vote_plan_status);
+
+    controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    let mut verifier = LedgerStateVerifier::new(ledger.into());
+    verifier
+        .info("only the transfer the treasury covers is applied")
+        .account_has_expected_balance(other_beneficiary, Value(30))
+        .pots()
+        .has_treasury_equals_to(&Value(20));
+    verifier.account(beneficiary).does_not_exist();
+    verifier
+        .info("the transfer the treasury does not cover is recorded as failed")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_decision(Decision::ActionFailed);
+    verifier
+        .info("the transfer the treasury covers is recorded as passed")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 1)
+        .has_decision(Decision::Passed);
+}
+
+#[test]
+pub fn private_vote_cast_action_transfer_to_accounts() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+    let payees = [
+        AddressData::account(Discrimination::Test),
+        AddressData::account(Discrimination::Test),
+        AddressData::account(Discrimination::Test),
+    ];
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_treasury(Value(1000))
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_accounts(vec![
+                    (payees[0].to_id(), 100),
+                    (payees[1].to_id(), 200),
+                    (payees[2].to_id(), 300),
+                ]),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            favorable,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = controller.active_vote_plan(&vote_plan, &ledger).unwrap();
+    let shares = members.decrypt_shares(&mut rng, &vote_plan_status);
+
+    controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    let [first, second, third] = payees;
+    LedgerStateVerifier::new(ledger.into())
+        .info("treasury funds are transferred to every payee")
+        .account_has_expected_balance(first, Value(100))
+        .account_has_expected_balance(second, Value(200))
+        .account_has_expected_balance(third, Value(300))
+        .pots()
+        .has_treasury_equals_to(&Value(400));
+}
+
+#[test]
+pub fn private_vote_cast_action_transfer_to_accounts_not_enough_treasury() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+    let payees = [
+        AddressData::account(Discrimination::Test),
+        AddressData::account(Discrimination::Test),
+        AddressData::account(Discrimination::Test),
+    ];
+    let other_payee = AddressData::account(Discrimination::Test);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_treasury(Value(500))
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_accounts(vec![
+                    (payees[0].to_id(), 100),
+                    (payees[1].to_id(), 200),
+                    (payees[2].to_id(), 300),
+                ]),
+        )
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_accounts(vec![(other_payee.to_id(), 50)]),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+
+    for proposal in vote_plan.proposals() {
+        controller
+            .cast_vote_private(
+                &alice,
+                &vote_plan,
+                &proposal.id(),
+                favorable,
+                &mut ledger,
+                &mut rng,
+            )
+            .unwrap();
+        alice.confirm_transaction();
+    }
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = controller.active_vote_plan(&vote_plan, &ledger).unwrap();
+    let shares = members.decrypt_shares(&mut rng, &vote_plan_status);
+
+    controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    // the treasury covers the first two payees but none of them is paid,
+    // the next proposal is still applied
+    let mut verifier = LedgerStateVerifier::new(ledger.into());
+    verifier
+        .info("only the transfer the treasury covers is applied")
+        .account_has_expected_balance(other_payee, Value(50))
+        .pots()
+        .has_treasury_equals_to(&Value(450));
+    for payee in payees.iter() {
+        verifier.account(payee.clone()).does_not_exist();
+    }
+    verifier
+        .info("the transfer the treasury does not cover is recorded as failed")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_decision(Decision::ActionFailed);
+    verifier
+        .info("the transfer the treasury covers is recorded as passed")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 1)
+        .has_decision(Decision::Passed);
+}
+
+#[test]
+pub fn private_vote_tally_with_tampered_share() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_rewards(100),
         )
-        .with_initials(vec![wallet(ALICE)
-            .with(1_000)
-            .owns(STAKE_POOL)
-            .committee_member()])
-        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
-            .owner(ALICE)
-            .consecutive_epoch_dates()
-            .payload_type(PayloadType::Private)
-            .committee_keys(committee_keys)
-            .with_proposal(
-                proposal(VoteTestGen::external_proposal_id())
-                    .options(3)
-                    .action_transfer_to_rewards(100),
-            )])
         .build()
         .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            favorable,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_id = vote_plan.to_id();
+
+    let shares = controller
+        .active_vote_plan(&vote_plan, &ledger)
+        .unwrap()
+        .proposals
+        .iter()
+        .map(|proposal| proposal.encrypted_tally().unwrap().clone())
+        .map(|encrypted_tally| {
+            // the second member submits a share computed with the key of the first one
+            let mut shares = members
+                .members()
+                .iter()
+                .map(|member| member.secret_key())
+                .map(|secret_key| encrypted_tally.finish(&mut rng, secret_key).1)
+                .collect::<Vec<_>>();
+            shares[1] = encrypted_tally
+                .finish(&mut rng, members.members()[0].secret_key())
+                .1;
+            shares
+        })
+        .collect::<Vec<_>>();
+
+    let shares = TallyDecryptShares::new(shares);
+
+    assert_eq!(
+        controller.tally_vote_private(&alice, &vote_plan, shares, &mut ledger),
+        Err(LedgerError::VotePlan(VotePlanLedgerError::VoteError {
+            id: vote_plan_id,
+            reason: UpdateError::ValueCallbackError(VoteError::CannotTallyVotes {
+                source: TallyError::InvalidShareProof { member_index: 1 }
+            }),
+        }))
+    );
+}
+
+#[test]
+pub fn private_vote_tally_with_threshold_of_shares() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            favorable,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+
+    // one share fewer than the threshold
+    let shares = members.decrypt_shares_from(&mut rng, &vote_plan_status, &[1]);
+    assert_eq!(
+        controller.tally_vote_private(&alice, &vote_plan, shares, &mut ledger),
+        Err(LedgerError::VotePlan(VotePlanLedgerError::VoteError {
+            id: vote_plan.to_id(),
+            reason: UpdateError::ValueCallbackError(VoteError::CannotTallyVotes {
+                source: TallyError::NotEnoughDecryptShares
+            }),
+        }))
+    );
+
+    // the same member cannot account for the threshold twice
+    let shares = members.decrypt_shares_from(&mut rng, &vote_plan_status, &[1, 1]);
+    assert_eq!(
+        controller.tally_vote_private(&alice, &vote_plan, shares, &mut ledger),
+        Err(LedgerError::VotePlan(VotePlanLedgerError::VoteError {
+            id: vote_plan.to_id(),
+            reason: UpdateError::ValueCallbackError(VoteError::CannotTallyVotes {
+                source: TallyError::DuplicateShare { member_index: 1 }
+            }),
+        }))
+    );
+
+    // the second member does not take part in the tally
+    let shares = members.decrypt_shares_from(&mut rng, &vote_plan_status, &[0, 2]);
+    controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    // Alice paid the fee of her ballot by the end of the voting window
+    let mut expected = [0.into(); 3];
+    expected[favorable.as_byte() as usize] = (1_000 - 3).into();
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("tally decrypted with a threshold of shares")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_result(&expected);
+}
+
+#[test]
+pub fn private_vote_tally_after_committee_handover() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            favorable,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+
+    // a committee of its own cannot take over the vote plan
+    let other = CommitteeMembersManager::new(COMMITTEE, &mut rng, THRESHOLD, MEMBERS_NO);
+    assert_eq!(
+        controller.committee_handover(&alice, &vote_plan, &other, &mut ledger),
+        Err(LedgerError::VotePlan(VotePlanLedgerError::VoteError {
+            id: vote_plan.to_id(),
+            reason: UpdateError::ValueCallbackError(VoteError::InvalidCommitteeHandover),
+        }))
+    );
+
+    // the first and last members hand over to new members with the same
+    // threshold
+    let new_members = members.handover(&mut rng, &[0, 2], THRESHOLD, MEMBERS_NO);
+    controller
+        .committee_handover(&alice, &vote_plan, &new_members, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+    assert_eq!(
+        vote_plan_status.committee_public_keys,
+        new_members.public_keys()
+    );
+
+    // the previous members no longer decrypt the tally
+    let shares = members.decrypt_shares_from(&mut rng, &vote_plan_status, &[0, 1]);
+    assert_eq!(
+        controller.tally_vote_private(&alice, &vote_plan, shares, &mut ledger),
+        Err(LedgerError::VotePlan(VotePlanLedgerError::VoteError {
+            id: vote_plan.to_id(),
+            reason: UpdateError::ValueCallbackError(VoteError::CannotTallyVotes {
+                source: TallyError::InvalidShareProof { member_index: 0 }
+            }),
+        }))
+    );
+
+    let shares = new_members.decrypt_shares_from(&mut rng, &vote_plan_status, &[1, 2]);
+    controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    // Alice paid the fees of her ballot and of the handover by the end of
+    // the voting window
+    let mut expected = [0.into(); 3];
+    expected[favorable.as_byte() as usize] = (1_000 - 2 * 3).into();
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("tally decrypted by the new committee")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_result(&expected);
+}
+
+#[test]
+#[should_panic]
+pub fn private_vote_plan_without_keys() {
+    let committee_keys = vec![];
+
+    let (_ledger, _controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new(0)
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_rewards(Value(1000)),
+        )
+        .with_initials(vec![wallet(ALICE)
+            .with(1_000)
+            .owns(STAKE_POOL)
+            .committee_member()])
+        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
+            .owner(ALICE)
+            .consecutive_epoch_dates()
+            .payload_type(PayloadType::Private)
+            .committee_keys(committee_keys)
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_transfer_to_rewards(100),
+            )])
+        .build()
+        .unwrap();
+}
+
+#[test]
+pub fn vote_cast_for_any_payload_type() {
+    let favorable = Choice::new(1);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    for payload_type in [PayloadType::Public, PayloadType::Private].iter() {
+        let (mut ledger, controller) = prepare_vote_scenario()
+            .with_rewards(Value(1000))
+            .payload_type(*payload_type)
+            .committee(1, 1)
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_transfer_to_rewards(100),
+            )
+            .build()
+            .unwrap();
+
+        let alice = controller.wallet(ALICE).unwrap();
+        let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+        let proposal = vote_plan.proposal(0);
+
+        controller
+            .cast_vote(
+                &alice,
+                &vote_plan,
+                &proposal.id(),
+                favorable,
+                &mut ledger,
+                &mut rng,
+            )
+            .unwrap();
+
+        let vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+        let (_, payload) = vote_plan_status.proposals[0].votes.iter().next().unwrap();
+        assert_eq!(payload.payload_type(), *payload_type);
+    }
+}
+
+#[test]
+pub fn private_vote_tally_weighted_by_stake() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+    let rejection = Choice::new(0);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_voters(vec![wallet(BOB).with(3_000).delegates_to(STAKE_POOL)])
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_rewards(100),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let mut bob = controller.wallet(BOB).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            favorable,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_private(
+            &bob,
+            &vote_plan,
+            &proposal.id(),
+            rejection,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    bob.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let shares = ledger
+        .ledger
+        .active_vote_plans()
+        .pop()
+        .unwrap()
+        .proposals
+        .iter()
+        .map(|proposal| {
+            let encrypted_tally = proposal.encrypted_tally().unwrap();
+            members
+                .members()
+                .iter()
+                .map(|member| encrypted_tally.finish(&mut rng, member.secret_key()).1)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    controller
+        .tally_vote_private(
+            &alice,
+            &vote_plan,
+            TallyDecryptShares::new(shares),
+            &mut ledger,
+        )
+        .unwrap();
+
+    // each ballot weights the stake of its caster at the end of the voting
+    // window (initial value minus the fees paid by then)
+    let mut expected = [0.into(); 3];
+    expected[favorable.as_byte() as usize] = 997.into();
+    expected[rejection.as_byte() as usize] = 2997.into();
+    LedgerStateVerifier::new(ledger.into())
+        .info("tally is weighted by stake")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_result(&expected);
+}
+
+#[test]
+pub fn private_vote_overridden_before_tally() {
+    let favorable = Choice::new(1);
+    let rejection = Choice::new(0);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .payload_type(PayloadType::Private)
+        .committee(1, 1)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_rewards(100),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    for choice in [favorable, rejection].iter() {
+        controller
+            .cast_vote_private(
+                &alice,
+                &vote_plan,
+                &proposal.id(),
+                *choice,
+                &mut ledger,
+                &mut rng,
+            )
+            .unwrap();
+        alice.confirm_transaction();
+    }
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let shares = ledger
+        .ledger
+        .active_vote_plans()
+        .pop()
+        .unwrap()
+        .proposals
+        .iter()
+        .map(|proposal| {
+            let encrypted_tally = proposal.encrypted_tally().unwrap();
+            members
+                .members()
+                .iter()
+                .map(|member| encrypted_tally.finish(&mut rng, member.secret_key()).1)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    controller
+        .tally_vote_private(
+            &alice,
+            &vote_plan,
+            TallyDecryptShares::new(shares),
+            &mut ledger,
+        )
+        .unwrap();
+
+    let vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+    let result = vote_plan_status.proposals[0]
+        .tally
+        .as_ref()
+        .unwrap()
+        .result()
+        .cloned()
+        .unwrap();
+
+    // only the latest ballot counts, with the stake left after the fees
+    // of both ballots
+    assert_eq!(result.results()[favorable.as_byte() as usize], 0.into());
+    assert_eq!(result.results()[rejection.as_byte() as usize], 994.into());
+    assert_eq!(vote_plan_status.proposals[0].votes.size(), 1);
+}
+
+#[test]
+pub fn private_vote_tally_all_proposals() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+    let rejection = Choice::new(0);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_voters(vec![wallet(BOB).with(3_000).delegates_to(STAKE_POOL)])
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut wallets = [
+        controller.wallet(ALICE).unwrap(),
+        controller.wallet(BOB).unwrap(),
+    ];
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+
+    // Alice votes on every proposal, Bob only on the first two
+    let votes = [
+        (0, 0, favorable),
+        (0, 1, rejection),
+        (0, 2, favorable),
+        (1, 0, rejection),
+        (1, 1, rejection),
+    ];
+    for (wallet_index, proposal_index, choice) in votes.iter() {
+        let wallet = &mut wallets[*wallet_index];
+        let proposal = vote_plan.proposal(*proposal_index);
+        controller
+            .cast_vote_private(
+                wallet,
+                &vote_plan,
+                &proposal.id(),
+                *choice,
+                &mut ledger,
+                &mut rng,
+            )
+            .unwrap();
+        wallet.confirm_transaction();
+    }
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    let alice = &mut wallets[0];
+    controller
+        .encrypted_tally(alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+    let shares = members.decrypt_shares(&mut rng, &vote_plan_status);
+
+    controller
+        .tally_vote_private(alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    // stakes at the end of the voting window: Alice paid the fees of 3
+    // ballots, Bob the fees of 2 ballots
+    let alice_stake = 1_000 - 3 * 3;
+    let bob_stake = 3_000 - 2 * 3;
+
+    let mut verifier = LedgerStateVerifier::new(ledger.into());
+    verifier.info("all the proposals are tallied independently");
+
+    let expected = |favorable_weight: u64, rejection_weight: u64| {
+        let mut expected = [0.into(); 3];
+        expected[favorable.as_byte() as usize] = favorable_weight.into();
+        expected[rejection.as_byte() as usize] = rejection_weight.into();
+        expected
+    };
+
+    let vote_plans = verifier.vote_plans();
+    vote_plans
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_result(&expected(alice_stake, bob_stake));
+    vote_plans
+        .tally_for(&vote_plan.to_id(), 1)
+        .has_result(&expected(0, alice_stake + bob_stake));
+    vote_plans
+        .tally_for(&vote_plan.to_id(), 2)
+        .has_result(&expected(alice_stake, 0));
+}
+
+#[test]
+pub fn private_vote_encrypted_tally_before_committee_start_is_rejected() {
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .payload_type(PayloadType::Private)
+        .committee(1, 1)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+
+    assert_eq!(
+        controller.encrypted_tally(&alice, &vote_plan, &mut ledger),
+        Err(LedgerError::VotePlan(VotePlanLedgerError::VoteError {
+            id: vote_plan.to_id(),
+            reason: UpdateError::ValueCallbackError(VoteError::NotCommitteeTime {
+                start: BlockDate {
+                    epoch: 1,
+                    slot_id: 0,
+                },
+                end: BlockDate {
+                    epoch: 2,
+                    slot_id: 0,
+                },
+            }),
+        }))
+    );
+}
+
+#[test]
+pub fn private_vote_plans_with_disjoint_committees() {
+    const OTHER_VOTE_PLAN: &str = "fund2";
+    const OTHER_COMMITTEE: &str = "other_committee";
+
+    let favorable = Choice::new(1);
+    let rejection = Choice::new(0);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new(0)
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_rewards(Value(1000)),
+        )
+        .with_initials(vec![wallet(ALICE)
+            .with(1_000)
+            .owns(STAKE_POOL)
+            .committee_member()])
+        .with_committees(vec![
+            committee(COMMITTEE).members_no(3).threshold(2),
+            committee(OTHER_COMMITTEE).members_no(2).threshold(1),
+        ])
+        .with_vote_plans(vec![
+            vote_plan(VOTE_PLAN)
+                .owner(ALICE)
+                .consecutive_epoch_dates()
+                .payload_type(PayloadType::Private)
+                .committee(COMMITTEE)
+                .with_proposal(
+                    proposal(VoteTestGen::external_proposal_id())
+                        .options(3)
+                        .action_off_chain(),
+                ),
+            vote_plan(OTHER_VOTE_PLAN)
+                .owner(ALICE)
+                .consecutive_epoch_dates()
+                .payload_type(PayloadType::Private)
+                .committee(OTHER_COMMITTEE)
+                .with_proposal(
+                    proposal(VoteTestGen::external_proposal_id())
+                        .options(3)
+                        .action_off_chain(),
+                ),
+        ])
+        .build()
+        .unwrap();
+
+    let first_committee = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let other_committee = controller.vote_plan_committee(OTHER_VOTE_PLAN).unwrap();
+    assert_eq!(first_committee.members().len(), 3);
+    assert_eq!(other_committee.members().len(), 2);
+    assert!(other_committee
+        .public_keys()
+        .iter()
+        .all(|key| !first_committee.public_keys().contains(key)));
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let votes = [(VOTE_PLAN, favorable), (OTHER_VOTE_PLAN, rejection)];
+    for (vote_plan_alias, choice) in votes.iter() {
+        let vote_plan = controller.vote_plan(vote_plan_alias).unwrap();
+        controller
+            .cast_vote_private(
+                &alice,
+                &vote_plan,
+                &vote_plan.proposal(0).id(),
+                *choice,
+                &mut ledger,
+                &mut rng,
+            )
+            .unwrap();
+        alice.confirm_transaction();
+    }
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    for (vote_plan_alias, _) in votes.iter() {
+        let vote_plan = controller.vote_plan(vote_plan_alias).unwrap();
+        controller
+            .encrypted_tally(&alice, &vote_plan, &mut ledger)
+            .unwrap();
+        alice.confirm_transaction();
+    }
+
+    for (vote_plan_alias, _) in votes.iter() {
+        let vote_plan = controller.vote_plan(vote_plan_alias).unwrap();
+        let vote_plan_status = controller.active_vote_plan(&vote_plan, &ledger).unwrap();
+        let shares = controller
+            .vote_plan_committee(vote_plan_alias)
+            .unwrap()
+            .decrypt_shares(&mut rng, &vote_plan_status);
+        controller
+            .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+            .unwrap();
+        alice.confirm_transaction();
+    }
+
+    // the voting windows of both plans ended together, when Alice had paid
+    // the fees of her 2 ballots
+    let expected = |choice: Choice, stake: u64| {
+        let mut expected = [0.into(); 3];
+        expected[choice.as_byte() as usize] = stake.into();
+        expected
+    };
+
+    let mut verifier = LedgerStateVerifier::new(ledger.into());
+    verifier.info("each vote plan is tallied by its own committee");
+    let vote_plans = verifier.vote_plans();
+    vote_plans
+        .tally_for(&controller.vote_plan(VOTE_PLAN).unwrap().to_id(), 0)
+        .has_result(&expected(favorable, 1_000 - 2 * 3));
+    vote_plans
+        .tally_for(&controller.vote_plan(OTHER_VOTE_PLAN).unwrap().to_id(), 0)
+        .has_result(&expected(rejection, 1_000 - 2 * 3));
+}
+
+#[test]
+pub fn private_vote_cast_random_ballots() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+    const VOTERS: [&str; 4] = ["Voter1", "Voter2", "Voter3", "Voter4"];
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_voters(vec![
+            wallet(VOTERS[0]).with(1_000).delegates_to(STAKE_POOL),
+            wallet(VOTERS[1]).with(1_000).delegates_to(STAKE_POOL),
+            wallet(VOTERS[2]).with(1_000).delegates_to(STAKE_POOL),
+            wallet(VOTERS[3]).with(1_000).delegates_to(STAKE_POOL),
+        ])
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let mut voters = VOTERS
+        .iter()
+        .map(|voter| controller.wallet(voter).unwrap())
+        .collect::<Vec<_>>();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+
+    let ballots = VoteTestGen::random_ballots(&vote_plan.clone().into(), 0, VOTERS.len(), &mut rng);
+    controller
+        .cast_ballots(&mut voters, ballots, &mut ledger)
+        .unwrap();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+    let shares = members.decrypt_shares_from(&mut rng, &vote_plan_status, &[0, 1]);
+    let results = controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    // all the ballots were counted, each voter paid the fee of their ballot
+    assert_eq!(
+        results[0].participation(),
+        Stake::from_value(Value(VOTERS.len() as u64 * (1_000 - 3)))
+    );
+}
+
+#[test]
+pub fn vote_plan_with_public_and_private_proposals() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .payload_type(PayloadType::Public)
+                .action_off_chain(),
+        )
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let public_proposal = vote_plan.proposal(0);
+    let private_proposal = vote_plan.proposal(1);
+
+    controller
+        .cast_vote_public(
+            &alice,
+            &vote_plan,
+            &public_proposal.id(),
+            favorable,
+            &mut ledger,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &private_proposal.id(),
+            favorable,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    // the public proposal is tallied along with the private one
+    assert_eq!(
+        controller.tally_vote_public(&alice, &vote_plan, &mut ledger),
+        Err(LedgerError::VotePlan(VotePlanLedgerError::VoteError {
+            id: vote_plan.to_id(),
+            reason: UpdateError::ValueCallbackError(VoteError::CannotTallyVotes {
+                source: TallyError::InvalidPrivacy
+            }),
+        }))
+    );
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+    assert!(vote_plan_status.proposals[0].encrypted_tally().is_none());
+    assert!(vote_plan_status.proposals[1].encrypted_tally().is_some());
+
+    // only the private proposal needs decrypt shares
+    let shares = members.decrypt_shares(&mut rng, &vote_plan_status);
+    assert_eq!(shares.iter().count(), 1);
+
+    let results = controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    assert_eq!(results[0].winner(), Some(favorable));
+    assert_eq!(results[1].winner(), Some(favorable));
+}
+
+#[test]
+pub fn private_vote_recorded_tally_is_verifiable() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            favorable,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+    let shares = members.decrypt_shares_from(&mut rng, &vote_plan_status, &[0, 2]);
+    controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    let mut vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+    assert_eq!(vote_plan_status.verify_recorded_tally(), Ok(()));
+
+    match &mut vote_plan_status.proposals[0].tally {
+        Some(Tally::Private {
+            state: PrivateTallyState::Decrypted { result, .. },
+        }) => result.add_vote(Choice::new(0), 1).unwrap(),
+        _ => panic!("the private tally should be decrypted"),
+    }
+    assert_eq!(
+        vote_plan_status.verify_recorded_tally(),
+        Err(TallyError::InvalidRecordedResult { proposal_index: 0 })
+    );
+}
+
+#[test]
+pub fn private_vote_participation_before_decryption() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_voters(vec![wallet(BOB).with(3_000).delegates_to(STAKE_POOL)])
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            favorable,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+
+    // bob abstained, only alice's stake (initial value minus the fees paid
+    // by the end of the voting window) is counted
+    let alice_stake = Stake::from_value(Value(997));
+    let bob_stake = Stake::from_value(Value(3000));
+
+    let vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+    let participation = vote_plan_status.proposals[0].participation().unwrap();
+    assert_eq!(participation.eligible(), alice_stake + bob_stake);
+    assert_eq!(participation.voted(), alice_stake);
+    assert_eq!(participation.voters(), 1);
+}
+
+#[test]
+pub fn private_vote_tally_tie() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+    let rejection = Choice::new(2);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_voters(vec![
+            // both pay the fee of their ballot by the end of the voting
+            // window
+            wallet(BOB).with(1_000).delegates_to(STAKE_POOL),
+        ])
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let mut bob = controller.wallet(BOB).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            favorable,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_private(
+            &bob,
+            &vote_plan,
+            &proposal.id(),
+            rejection,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    bob.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    // the tie is only known once the tally is decrypted
+    let vote_plan_status = controller.active_vote_plan(&vote_plan, &ledger).unwrap();
+    assert_eq!(vote_plan_status.proposals[0].decision(), None);
+
+    let shares = members.decrypt_shares(&mut rng, &vote_plan_status);
+    let results = controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+    assert!(results[0].is_tie());
+    assert_eq!(results[0].winner(), None);
+
+    let vote_plan_status = controller.active_vote_plan(&vote_plan, &ledger).unwrap();
+    assert_eq!(
+        vote_plan_status.proposals[0].decision(),
+        Some(Decision::Tie)
+    );
+}
+
+#[test]
+pub fn private_vote_spoiled_ballot_is_rejected() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_voters(vec![wallet(BOB).with(1_000).delegates_to(STAKE_POOL)])
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let bob = controller.wallet(BOB).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            favorable,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+
+    // the encrypted vote of one ballot with the proof of another one
+    let plan: VotePlan = vote_plan.clone().into();
+    let mut payload = || match VoteTestGen::private_vote_cast_payload(
+        &plan,
+        &plan.proposals()[0],
+        favorable,
+        &mut rng,
+    ) {
+        Payload::Private {
+            encrypted_vote,
+            proof,
+        } => (encrypted_vote, proof),
+        Payload::Public { .. } => unreachable!(),
+    };
+    let (encrypted_vote, _) = payload();
+    let (_, proof) = payload();
+
+    assert_eq!(
+        controller.cast_vote_private_raw(
+            &bob,
+            &vote_plan,
+            &proposal.id(),
+            encrypted_vote,
+            proof,
+            &mut ledger,
+        ),
+        Err(LedgerError::VotePlan(VotePlanLedgerError::VoteError {
+            id: vote_plan.to_id(),
+            reason: UpdateError::ValueCallbackError(VoteError::VoteVerificationError),
+        }))
+    );
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = controller.active_vote_plan(&vote_plan, &ledger).unwrap();
+    let shares = members.decrypt_shares(&mut rng, &vote_plan_status);
+    let results = controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    // only alice's ballot was counted
+    assert_eq!(
+        results[0].results()[favorable.as_byte() as usize],
+        997.into()
+    );
+    let vote_plan_status = controller.active_vote_plan(&vote_plan, &ledger).unwrap();
+    assert_eq!(
+        vote_plan_status.proposals[0]
+            .participation()
+            .unwrap()
+            .voters(),
+        1
+    );
+}
+
+#[test]
+pub fn private_vote_cast_batch_on_several_proposals() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+    let rejection = Choice::new(0);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_voters(vec![wallet(BOB).with(1_000).delegates_to(STAKE_POOL)])
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let bob = controller.wallet(BOB).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+
+    let votes = [
+        (vote_plan.proposal(0).id(), favorable),
+        (vote_plan.proposal(1).id(), rejection),
+        (vote_plan.proposal(2).id(), favorable),
+    ];
+    controller
+        .cast_votes_private(&alice, &vote_plan, &votes, &mut ledger, &mut rng)
+        .unwrap();
+    alice.confirm_transaction();
+
+    // a batch holding two ballots for the same proposal is rejected as a
+    // whole, none of its ballots are counted
+    let duplicated_votes = [
+        (vote_plan.proposal(0).id(), favorable),
+        (vote_plan.proposal(1).id(), favorable),
+        (vote_plan.proposal(0).id(), rejection),
+    ];
+    assert_eq!(
+        controller.cast_votes_private(&bob, &vote_plan, &duplicated_votes, &mut ledger, &mut rng),
+        Err(LedgerError::VoteCastBatchInvalidBallots)
+    );
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = controller.active_vote_plan(&vote_plan, &ledger).unwrap();
+    for proposal in vote_plan_status.proposals.iter() {
+        assert_eq!(proposal.participation().unwrap().voters(), 1);
+    }
+
+    let shares = members.decrypt_shares(&mut rng, &vote_plan_status);
+    let results = controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    // the fee of the batch is charged once: Alice only paid for the batch
+    // by the end of the voting window
+    let alice_stake = 1_000 - 3;
+    for ((_, choice), result) in votes.iter().zip(results.iter()) {
+        let mut expected = [0.into(); 3];
+        expected[choice.as_byte() as usize] = alice_stake.into();
+        assert_eq!(result.results(), &expected[..]);
+    }
+}
+
+#[test]
+pub fn private_vote_tally_with_serialized_shares() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let favorable = Choice::new(1);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+
+    for proposal_index in 0..2 {
+        controller
+            .cast_vote_private(
+                &alice,
+                &vote_plan,
+                &vote_plan.proposal(proposal_index).id(),
+                favorable,
+                &mut ledger,
+                &mut rng,
+            )
+            .unwrap();
+        alice.confirm_transaction();
+    }
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = controller.active_vote_plan(&vote_plan, &ledger).unwrap();
+
+    // every member computes its shares on its own and sends them over
+    let member_indices = [0u8, 2];
+    let members_shares = member_indices
+        .iter()
+        .map(|member_index| {
+            vote_plan_status
+                .member_decrypt_shares(
+                    &mut rng,
+                    members.members()[usize::from(*member_index)].secret_key(),
+                )
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let serialized = member_indices
+        .iter()
+        .zip(members_shares.iter())
+        .map(|(member_index, shares)| {
+            TallyDecryptShares::serialize_member(*member_index, shares).unwrap()
+        })
+        .collect();
+
+    let shares = TallyDecryptShares::from_serialized(serialized).unwrap();
+    let in_memory = TallyDecryptShares::from_members(
+        (0..2)
+            .map(|proposal_index| {
+                member_indices
+                    .iter()
+                    .zip(members_shares.iter())
+                    .map(|(member_index, shares)| (*member_index, shares[proposal_index].clone()))
+                    .collect()
+            })
+            .collect(),
+    );
+    assert_eq!(shares, in_memory);
+
+    let results = controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    // Alice paid the fees of her 2 ballots by the end of the voting window
+    let mut expected = [0.into(); 3];
+    expected[favorable.as_byte() as usize] = (1_000 - 2 * 3).into();
+    for result in results.iter() {
+        assert_eq!(result.results(), &expected[..]);
+    }
+}
+
+#[test]
+pub fn private_vote_cast_for_last_option() {
+    const MEMBERS_NO: usize = 3;
+    const THRESHOLD: usize = 2;
+
+    let last_option = Choice::new(2);
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .payload_type(PayloadType::Private)
+        .committee(MEMBERS_NO, THRESHOLD)
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let members = controller.vote_plan_committee(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            last_option,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .encrypted_tally(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    let vote_plan_status = controller.active_vote_plan(&vote_plan, &ledger).unwrap();
+    let shares = members.decrypt_shares(&mut rng, &vote_plan_status);
+    let results = controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    // Alice paid the fee of her ballot by the end of the voting window
+    let mut expected = [0.into(); 3];
+    expected[last_option.as_byte() as usize] = (1_000 - 3).into();
+    assert_eq!(results[0].results(), &expected[..]);
+    assert_eq!(results[0].winner(), Some(last_option));
 }
@@ -1,44 +1,38 @@
 use crate::testing::VoteTestGen;
 use crate::{
+    certificate::{VoteCast, VotePlan},
     fee::{LinearFee, PerCertificateFee, PerVoteCertificateFee},
     header::BlockDate,
+    ledger::Error as LedgerError,
+    rewards::Ratio,
+    stake::Stake,
     testing::{
         ledger::ConfigBuilder,
-        scenario::{prepare_scenario, proposal, vote_plan, wallet},
+        scenario::{
+            prepare_scenario, prepare_vote_scenario, proposal, vote_plan, wallet, ALICE,
+            STAKE_POOL, VOTE_PLAN,
+        },
         verifiers::LedgerStateVerifier,
     },
     value::Value,
-    vote::Choice,
+    vote::{Choice, Decision, Payload, PayloadType, TieBreak, VoteError, VotePlanLedgerError},
 };
 use core::num::NonZeroU64;
+use imhamt::UpdateError;
 
-const ALICE: &str = "Alice";
 const BOB: &str = "Bob";
-const STAKE_POOL: &str = "stake_pool";
-const VOTE_PLAN: &str = "fund1";
 
 #[test]
 pub fn vote_cast_action_transfer_to_rewards() {
     let favorable = Choice::new(1);
 
-    let (mut ledger, controller) = prepare_scenario()
-        .with_config(
-            ConfigBuilder::new(0)
-                .with_fee(LinearFee::new(1, 1, 1))
-                .with_rewards(Value(1000)),
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_rewards(100),
         )
-        .with_initials(vec![wallet(ALICE)
-            .with(1_000)
-            .owns(STAKE_POOL)
-            .committee_member()])
-        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
-            .owner(ALICE)
-            .consecutive_epoch_dates()
-            .with_proposal(
-                proposal(VoteTestGen::external_proposal_id())
-                    .options(3)
-                    .action_transfer_to_rewards(100),
-            )])
         .build()
         .unwrap();
 
@@ -56,9 +50,10 @@ pub fn vote_cast_action_transfer_to_rewards() {
         slot_id: 1,
     });
 
-    controller
+    let results = controller
         .tally_vote_public(&alice, &vote_plan, &mut ledger)
         .unwrap();
+    assert_eq!(results[0].winner(), Some(favorable));
 
     ledger.fast_forward_to(BlockDate {
         epoch: 1,
@@ -73,28 +68,75 @@ pub fn vote_cast_action_transfer_to_rewards() {
         .has_remaining_rewards_equals_to(&Value(1100));
 }
 
+#[test]
+pub fn vote_cast_action_transfer_to_rewards_without_quorum() {
+    let favorable = Choice::new(1);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_voters(vec![wallet(BOB).with(1_500).delegates_to(STAKE_POOL)])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .quorum(Ratio {
+                    numerator: 50,
+                    denominator: NonZeroU64::new(100).unwrap(),
+                })
+                .action_transfer_to_rewards(100),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    // alice's stake is enough for the governance criteria but not for the
+    // quorum of the proposal
+    controller
+        .cast_vote_public(&alice, &vote_plan, &proposal.id(), favorable, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    ledger.apply_protocol_changes().unwrap();
+
+    let mut verifier = LedgerStateVerifier::new(ledger.into());
+    verifier
+        .info("quorum is not reached")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_decision(Decision::NoQuorum);
+    verifier
+        .info("rewards pot is unchanged")
+        .pots()
+        .has_remaining_rewards_equals_to(&Value(1000));
+}
+
 #[test]
 pub fn vote_cast_action_action_parameters_no_op() {
     let favorable = Choice::new(1);
 
-    let (mut ledger, controller) = prepare_scenario()
-        .with_config(
-            ConfigBuilder::new(0)
-                .with_fee(LinearFee::new(1, 1, 1))
-                .with_rewards(Value(1000)),
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_parameters_no_op(),
         )
-        .with_initials(vec![wallet(ALICE)
-            .with(1_000)
-            .owns(STAKE_POOL)
-            .committee_member()])
-        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
-            .owner(ALICE)
-            .consecutive_epoch_dates()
-            .with_proposal(
-                proposal(VoteTestGen::external_proposal_id())
-                    .options(3)
-                    .action_parameters_no_op(),
-            )])
         .build()
         .unwrap();
 
@@ -129,36 +171,315 @@ pub fn vote_cast_action_action_parameters_no_op() {
         .has_remaining_rewards_equals_to(&Value(1000));
 }
 
+#[test]
+pub fn vote_cast_action_update_fee() {
+    let favorable = Choice::new(1);
+    let new_fee = LinearFee::new(2, 3, 4);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_update_fee(new_fee),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_public(&alice, &vote_plan, &proposal.id(), favorable, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+
+    assert_eq!(ledger.fee(), LinearFee::new(1, 1, 1));
+
+    ledger.apply_protocol_changes().unwrap();
+
+    assert_eq!(ledger.fee(), new_fee);
+}
+
+#[test]
+pub fn vote_cast_action_update_fee_twice_in_epoch() {
+    let favorable = Choice::new(1);
+    let first_fee = LinearFee::new(2, 3, 4);
+    let second_fee = LinearFee::new(5, 6, 7);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_update_fee(first_fee),
+        )
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_update_fee(second_fee),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+
+    for proposal in vote_plan.proposals() {
+        controller
+            .cast_vote_public(&alice, &vote_plan, &proposal.id(), favorable, &mut ledger)
+            .unwrap();
+        alice.confirm_transaction();
+    }
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+
+    ledger.apply_protocol_changes().unwrap();
+
+    // both proposals passed, only the first fee update is applied
+    assert_eq!(ledger.fee(), first_fee);
+}
+
+#[test]
+pub fn vote_cast_public_ballots_from_two_wallets() {
+    let favorable = Choice::new(1);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_voters(vec![wallet(BOB).with(3_000).delegates_to(STAKE_POOL)])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_rewards_add(100),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let mut bob = controller.wallet(BOB).unwrap();
+
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    // a public vote plan needs no committee keys
+    let certificate: VotePlan = vote_plan.clone().into();
+    assert_eq!(certificate.payload_type(), PayloadType::Public);
+    assert!(certificate.committee_public_keys().is_empty());
+
+    controller
+        .cast_vote_public(&alice, &vote_plan, &proposal.id(), favorable, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_public(&bob, &vote_plan, &proposal.id(), favorable, &mut ledger)
+        .unwrap();
+    bob.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    let results = controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    assert_eq!(results[0].winner(), Some(favorable));
+
+    ledger.apply_protocol_changes().unwrap();
+
+    let mut verifier = LedgerStateVerifier::new(ledger.into());
+
+    let mut expected = [0.into(); 3];
+    expected[favorable.as_byte() as usize] = (997 + 2997).into();
+    verifier
+        .info("tally counts the plain choices of both wallets")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_result(&expected);
+
+    verifier
+        .info("rewards pot is increased")
+        .pots()
+        .has_remaining_rewards_equals_to(&Value(1100));
+}
+
+#[test]
+pub fn vote_cast_tally_tie() {
+    let favorable = Choice::new(1);
+    let rejection = Choice::new(2);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_voters(vec![
+            // both pay the fee of their ballots by the end of the voting window
+            wallet(BOB).with(1_000).delegates_to(STAKE_POOL),
+        ])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .tie_break(TieBreak::LowestOption)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let mut bob = controller.wallet(BOB).unwrap();
+
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+
+    for index in 0..2 {
+        let proposal = vote_plan.proposal(index);
+        controller
+            .cast_vote_public(&alice, &vote_plan, &proposal.id(), favorable, &mut ledger)
+            .unwrap();
+        alice.confirm_transaction();
+        controller
+            .cast_vote_public(&bob, &vote_plan, &proposal.id(), rejection, &mut ledger)
+            .unwrap();
+        bob.confirm_transaction();
+    }
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    let results = controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    for result in results.iter() {
+        assert!(result.is_tie());
+        assert_eq!(result.winner(), None);
+        assert_eq!(result.winner_with(TieBreak::LowestOption), Some(favorable));
+    }
+
+    let mut verifier = LedgerStateVerifier::new(ledger.into());
+    verifier
+        .info("a tie has no winner")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_decision(Decision::Tie);
+    verifier
+        .info("a tie is won by the lowest option")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 1)
+        .has_decision(Decision::Passed);
+}
+
+#[test]
+pub fn vote_cast_tally_tie_on_treasury_action() {
+    let favorable = Choice::new(1);
+    let rejection = Choice::new(2);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_voters(vec![
+            // both pay the fee of their ballots by the end of the voting window
+            wallet(BOB).with(1_000).delegates_to(STAKE_POOL),
+        ])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_rewards(100),
+        )
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .tie_break(TieBreak::LowestOption)
+                .action_transfer_to_rewards(10),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let mut bob = controller.wallet(BOB).unwrap();
+
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+
+    for index in 0..2 {
+        let proposal = vote_plan.proposal(index);
+        controller
+            .cast_vote_public(&alice, &vote_plan, &proposal.id(), favorable, &mut ledger)
+            .unwrap();
+        alice.confirm_transaction();
+        controller
+            .cast_vote_public(&bob, &vote_plan, &proposal.id(), rejection, &mut ledger)
+            .unwrap();
+        bob.confirm_transaction();
+    }
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+
+    ledger.apply_protocol_changes().unwrap();
+
+    // the governance requires more than half of the stake in favor, the
+    // tie break still decides the tie
+    let mut verifier = LedgerStateVerifier::new(ledger.into());
+    verifier
+        .info("a tie has no winner by default")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_decision(Decision::Tie);
+    verifier
+        .info("the favorable choice is the lowest tied option")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 1)
+        .has_decision(Decision::Passed);
+    verifier
+        .info("only the action of the proposal which passed is applied")
+        .pots()
+        .has_remaining_rewards_equals_to(&Value(1010));
+}
+
 #[test]
 pub fn vote_cast_tally_50_percent() {
     let _blank = Choice::new(0);
     let favorable = Choice::new(1);
     let rejection = Choice::new(2);
 
-    let (mut ledger, controller) = prepare_scenario()
-        .with_config(
-            ConfigBuilder::new(0)
-                .with_fee(LinearFee::new(1, 1, 1))
-                .with_rewards(Value(1000)),
-        )
-        .with_initials(vec![
-            wallet(ALICE)
-                .with(1_000)
-                .owns(STAKE_POOL)
-                .committee_member(),
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_voters(vec![
+            // alice holds just over half of the stake at the end of the voting window
             wallet(BOB)
-                .with(1_000)
+                .with(999)
                 .delegates_to(STAKE_POOL)
                 .committee_member(),
         ])
-        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
-            .owner(ALICE)
-            .consecutive_epoch_dates()
-            .with_proposal(
-                proposal(VoteTestGen::external_proposal_id())
-                    .options(3)
-                    .action_rewards_add(100),
-            )])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_rewards_add(100),
+        )
         .build()
         .unwrap();
 
@@ -205,30 +526,17 @@ pub fn vote_cast_tally_50_percent_unsuccesful() {
     let _favorable = Choice::new(1);
     let rejection = Choice::new(2);
 
-    let (mut ledger, controller) = prepare_scenario()
-        .with_config(
-            ConfigBuilder::new(0)
-                .with_fee(LinearFee::new(1, 1, 1))
-                .with_rewards(Value(1000)),
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_voters(vec![wallet(BOB)
+            .with(1_000)
+            .delegates_to(STAKE_POOL)
+            .committee_member()])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_rewards_add(100),
         )
-        .with_initials(vec![
-            wallet(ALICE)
-                .with(1_000)
-                .owns(STAKE_POOL)
-                .committee_member(),
-            wallet(BOB)
-                .with(1_000)
-                .delegates_to(STAKE_POOL)
-                .committee_member(),
-        ])
-        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
-            .owner(ALICE)
-            .consecutive_epoch_dates()
-            .with_proposal(
-                proposal(VoteTestGen::external_proposal_id())
-                    .options(3)
-                    .action_rewards_add(100),
-            )])
         .build()
         .unwrap();
 
@@ -296,27 +604,14 @@ pub fn vote_cast_by_non_committe_member() {
     let favorable = Choice::new(1);
     let rejection = Choice::new(2);
 
-    let (mut ledger, controller) = prepare_scenario()
-        .with_config(
-            ConfigBuilder::new(0)
-                .with_fee(LinearFee::new(1, 1, 1))
-                .with_rewards(Value(1000)),
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_voters(vec![wallet(BOB).with(1_000).delegates_to(STAKE_POOL)])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_rewards_add(100),
         )
-        .with_initials(vec![
-            wallet(ALICE)
-                .with(1_000)
-                .owns(STAKE_POOL)
-                .committee_member(),
-            wallet(BOB).with(1_000).delegates_to(STAKE_POOL),
-        ])
-        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
-            .owner(ALICE)
-            .consecutive_epoch_dates()
-            .with_proposal(
-                proposal(VoteTestGen::external_proposal_id())
-                    .options(3)
-                    .action_rewards_add(100),
-            )])
         .build()
         .unwrap();
 
@@ -353,24 +648,17 @@ pub fn votes_with_fees() {
     );
     fees.per_vote_certificate_fees(vote_fees);
 
-    let (mut ledger, controller) = prepare_scenario()
+    let (mut ledger, controller) = prepare_vote_scenario()
         .with_config(
             ConfigBuilder::new(0)
                 .with_fee(fees)
                 .with_rewards(Value(initial_rewards)),
         )
-        .with_initials(vec![wallet(ALICE)
-            .with(1_000)
-            .owns(STAKE_POOL)
-            .committee_member()])
-        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
-            .owner(ALICE)
-            .consecutive_epoch_dates()
-            .with_proposal(
-                proposal(VoteTestGen::external_proposal_id())
-                    .options(3)
-                    .action_transfer_to_rewards(rewards_add),
-            )])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_rewards(rewards_add),
+        )
         .build()
         .unwrap();
 
@@ -412,3 +700,497 @@ pub fn votes_with_fees() {
         .info("total value is the same")
         .total_value_is(&expected_ada_after);
 }
+
+#[test]
+pub fn vote_cast_tally_weighted_by_stake() {
+    let favorable = Choice::new(1);
+    let rejection = Choice::new(2);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_voters(vec![wallet(BOB)
+            .with(3_000)
+            .delegates_to(STAKE_POOL)
+            .committee_member()])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_rewards(100),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let mut bob = controller.wallet(BOB).unwrap();
+
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_public(&alice, &vote_plan, &proposal.id(), favorable, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_public(&bob, &vote_plan, &proposal.id(), rejection, &mut ledger)
+        .unwrap();
+    bob.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    ledger.apply_protocol_changes().unwrap();
+
+    let mut verifier = LedgerStateVerifier::new(ledger.into());
+
+    // each ballot weights the stake of its caster at the end of the voting
+    // window (initial value minus the fees paid so far)
+    let mut expected = [0.into(); 3];
+    expected[favorable.as_byte() as usize] = 997.into();
+    expected[rejection.as_byte() as usize] = 2997.into();
+    verifier
+        .info("tally is weighted by stake")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_result(&expected);
+
+    verifier
+        .info("rewards pot is unchanged as the heavier stake rejected the proposal")
+        .pots()
+        .has_remaining_rewards_equals_to(&Value(1000));
+}
+
+#[test]
+pub fn vote_cast_tally_weighted_by_stake_at_end_of_voting() {
+    let favorable = Choice::new(1);
+    let rejection = Choice::new(2);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_voters(vec![wallet(BOB).with(3_000).delegates_to(STAKE_POOL)])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let mut bob = controller.wallet(BOB).unwrap();
+
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_public(&alice, &vote_plan, &proposal.id(), favorable, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_public(&bob, &vote_plan, &proposal.id(), rejection, &mut ledger)
+        .unwrap();
+    bob.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    // once the voting window is over, moving the stake around does not
+    // change the weight of the ballots already cast
+    let transfer = controller
+        .fragment_factory()
+        .transaction(&bob, &alice, &mut ledger, 2_500);
+    ledger.apply_fragment(&transfer, ledger.date()).unwrap();
+    bob.confirm_transaction();
+
+    let results = controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    assert_eq!(results[0].winner(), Some(rejection));
+
+    let mut expected = [0.into(); 3];
+    expected[favorable.as_byte() as usize] = 997.into();
+    expected[rejection.as_byte() as usize] = 2_997.into();
+    LedgerStateVerifier::new(ledger.into())
+        .info("tally is weighted by the stake at the end of the voting window")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_result(&expected);
+}
+
+#[test]
+pub fn vote_cast_tally_participation() {
+    let favorable = Choice::new(1);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_voters(vec![wallet(BOB).with(3_000).delegates_to(STAKE_POOL)])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let mut bob = controller.wallet(BOB).unwrap();
+
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let everyone_votes = vote_plan.proposal(0);
+    let bob_abstains = vote_plan.proposal(1);
+
+    for proposal in &[&everyone_votes, &bob_abstains] {
+        controller
+            .cast_vote_public(&alice, &vote_plan, &proposal.id(), favorable, &mut ledger)
+            .unwrap();
+        alice.confirm_transaction();
+    }
+    controller
+        .cast_vote_public(
+            &bob,
+            &vote_plan,
+            &everyone_votes.id(),
+            favorable,
+            &mut ledger,
+        )
+        .unwrap();
+    bob.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+
+    let mut verifier = LedgerStateVerifier::new(ledger.into());
+
+    // stake of each voter at the end of the voting window (initial value
+    // minus the fees paid so far)
+    let alice_stake = Stake::from_value(Value(994));
+    let bob_stake = Stake::from_value(Value(2997));
+    let eligible = alice_stake + bob_stake;
+
+    verifier
+        .info("everyone voted")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_participation(eligible, eligible);
+    verifier
+        .info("bob abstained")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 1)
+        .has_participation(eligible, alice_stake);
+}
+
+#[test]
+pub fn vote_cast_tally_quorum_reached_with_abstains_only() {
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_voters(vec![wallet(BOB)
+            .with(1_000)
+            .delegates_to(STAKE_POOL)
+            .committee_member()])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_rewards(100),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let mut bob = controller.wallet(BOB).unwrap();
+
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_public(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            Choice::ABSTAIN,
+            &mut ledger,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_public(
+            &bob,
+            &vote_plan,
+            &proposal.id(),
+            Choice::ABSTAIN,
+            &mut ledger,
+        )
+        .unwrap();
+    bob.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .tally_vote_public(&bob, &vote_plan, &mut ledger)
+        .unwrap();
+
+    let vote_plan_status = ledger.ledger.active_vote_plans().pop().unwrap();
+    let result = vote_plan_status.proposals[0]
+        .tally
+        .as_ref()
+        .unwrap()
+        .result()
+        .cloned()
+        .unwrap();
+
+    // the whole stake participated, but only to abstain
+    assert_eq!(result.results(), &[0.into(), 0.into(), 0.into()]);
+    assert_eq!(result.abstain(), 1994.into());
+    assert_eq!(result.participation(), Stake(1994));
+    assert_eq!(result.winner(), None);
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    ledger.apply_protocol_changes().unwrap();
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("rewards pot is unchanged as no choice won")
+        .pots()
+        .has_remaining_rewards_equals_to(&Value(1000));
+}
+
+#[test]
+pub fn vote_cast_tally_approval_ratio_excludes_abstains() {
+    let favorable = Choice::new(1);
+    let rejection = Choice::new(2);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_rewards(Value(1000))
+        .with_voters(vec![
+            wallet(BOB).with(500).delegates_to(STAKE_POOL),
+            wallet("Clarice").with(3_000).delegates_to(STAKE_POOL),
+        ])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_transfer_to_rewards(100),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let mut bob = controller.wallet(BOB).unwrap();
+    let mut clarice = controller.wallet("Clarice").unwrap();
+
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_public(&alice, &vote_plan, &proposal.id(), favorable, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_public(&bob, &vote_plan, &proposal.id(), rejection, &mut ledger)
+        .unwrap();
+    bob.confirm_transaction();
+    controller
+        .cast_vote_public(
+            &clarice,
+            &vote_plan,
+            &proposal.id(),
+            Choice::ABSTAIN,
+            &mut ledger,
+        )
+        .unwrap();
+    clarice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    let results = controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+
+    // the abstentions outweigh both other choices but only count toward
+    // the quorum: 997 in favor out of the 997 + 497 for or against
+    assert_eq!(results[0].abstain(), 2997.into());
+    assert_eq!(results[0].participation(), Stake(997 + 497 + 2997));
+    assert_eq!(results[0].winner(), Some(favorable));
+
+    ledger.apply_protocol_changes().unwrap();
+
+    let mut verifier = LedgerStateVerifier::new(ledger.into());
+    verifier
+        .info("the proposal passed")
+        .vote_plans()
+        .tally_for(&vote_plan.to_id(), 0)
+        .has_decision(Decision::Passed);
+    verifier
+        .info("rewards pot is increased")
+        .pots()
+        .has_remaining_rewards_equals_to(&Value(1100));
+}
+
+#[test]
+pub fn vote_cast_after_vote_end_is_rejected() {
+    let favorable = Choice::new(1);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    // the vote ends when the committee starts, at epoch 1
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    assert_eq!(
+        controller.cast_vote_public(&alice, &vote_plan, &proposal.id(), favorable, &mut ledger),
+        Err(LedgerError::VotePlan(VotePlanLedgerError::VoteError {
+            id: vote_plan.to_id(),
+            reason: UpdateError::ValueCallbackError(VoteError::NotVoteTime {
+                start: BlockDate {
+                    epoch: 0,
+                    slot_id: 0,
+                },
+                end: BlockDate {
+                    epoch: 1,
+                    slot_id: 0,
+                },
+                vote: VoteCast::new(vote_plan.to_id(), 0, Payload::public(favorable)),
+            }),
+        }))
+    );
+}
+
+#[test]
+pub fn vote_tally_before_committee_start_is_rejected() {
+    let favorable = Choice::new(1);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_public(&alice, &vote_plan, &proposal.id(), favorable, &mut ledger)
+        .unwrap();
+    alice.confirm_transaction();
+
+    // still in the voting window
+    assert_eq!(
+        controller.tally_vote_public(&alice, &vote_plan, &mut ledger),
+        Err(LedgerError::VotePlan(VotePlanLedgerError::VoteError {
+            id: vote_plan.to_id(),
+            reason: UpdateError::ValueCallbackError(VoteError::NotCommitteeTime {
+                start: BlockDate {
+                    epoch: 1,
+                    slot_id: 0,
+                },
+                end: BlockDate {
+                    epoch: 2,
+                    slot_id: 0,
+                },
+            }),
+        }))
+    );
+}
+
+#[test]
+pub fn vote_cast_random_ballots() {
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    const VOTERS: [&str; 4] = ["Voter1", "Voter2", "Voter3", "Voter4"];
+
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+    let (mut ledger, controller) = prepare_vote_scenario()
+        .with_voters(vec![
+            wallet(VOTERS[0]).with(1_000).delegates_to(STAKE_POOL),
+            wallet(VOTERS[1]).with(1_000).delegates_to(STAKE_POOL),
+            wallet(VOTERS[2]).with(1_000).delegates_to(STAKE_POOL),
+            wallet(VOTERS[3]).with(1_000).delegates_to(STAKE_POOL),
+        ])
+        .with_proposal(
+            proposal(VoteTestGen::external_proposal_id())
+                .options(3)
+                .action_off_chain(),
+        )
+        .build()
+        .unwrap();
+
+    let alice = controller.wallet(ALICE).unwrap();
+    let mut voters = VOTERS
+        .iter()
+        .map(|voter| controller.wallet(voter).unwrap())
+        .collect::<Vec<_>>();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+
+    let ballots = VoteTestGen::random_ballots(&vote_plan.clone().into(), 0, VOTERS.len(), &mut rng);
+
+    // each voter paid the fee of their ballot
+    let mut expected = [0u64; 3];
+    for ballot in ballots.iter() {
+        match ballot.payload() {
+            Payload::Public { choice } => expected[choice.as_byte() as usize] += 1_000 - 3,
+            Payload::Private { .. } => unreachable!(),
+        }
+    }
+
+    controller
+        .cast_ballots(&mut voters, ballots, &mut ledger)
+        .unwrap();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    let results = controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+    let expected = expected.iter().map(|w| (*w).into()).collect::<Vec<_>>();
+    assert_eq!(results[0].results(), &expected[..]);
+}
@@ -6,10 +6,11 @@ use crate::{
         VoteTally,
     },
     ledger::governance::{ParametersGovernance, TreasuryGovernance},
-    vote::{self, Choice},
+    vote::{self, Choice, EncryptedVote, ProofOfCorrectVote},
 };
 use chain_core::property::BlockDate as BlockDateProp;
 use chain_crypto::digest::DigestOf;
+use rand_core::{CryptoRng, RngCore};
 use typed_bytes::ByteBuilder;
 
 pub struct VoteTestGen;
@@ -104,6 +105,64 @@ impl VoteTestGen {
         VoteCast::new(vote_plan.to_id(), 0, VoteTestGen::vote_cast_payload())
     }
 
+    pub fn private_vote_cast_payload<R>(
+        vote_plan: &VotePlan,
+        proposal: &Proposal,
+        choice: Choice,
+        rng: &mut R,
+    ) -> vote::Payload
+    where
+        R: RngCore + CryptoRng,
+    {
+        let encrypting_key = vote_plan.proposal_election_key(proposal);
+
+        let (encrypted_vote, proof) = chain_vote::encrypt_vote(
+            rng,
+            &encrypting_key,
+            chain_vote::Vote::new(Self::options_count(proposal), choice.as_byte() as usize),
+        );
+
+        vote::Payload::Private {
+            encrypted_vote: EncryptedVote::from_inner(encrypted_vote),
+            proof: ProofOfCorrectVote::from_inner(proof),
+        }
+    }
+
+    /// `n` ballots for the proposal at `proposal_index` of the vote plan,
+    /// with random choices that are all valid for the proposal
+    pub fn random_ballots<R>(
+        vote_plan: &VotePlan,
+        proposal_index: u8,
+        n: usize,
+        rng: &mut R,
+    ) -> Vec<VoteCast>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let proposal = &vote_plan.proposals()[proposal_index as usize];
+        let payload_type = vote_plan.proposal_payload_type(proposal);
+        let options_count = Self::options_count(proposal);
+
+        (0..n)
+            .map(|_| {
+                let choice = Choice::new((rng.next_u32() as usize % options_count) as u8);
+                let payload = match payload_type {
+                    vote::PayloadType::Public => vote::Payload::public(choice),
+                    vote::PayloadType::Private => {
+                        Self::private_vote_cast_payload(vote_plan, proposal, choice, rng)
+                    }
+                };
+                VoteCast::new(vote_plan.to_id(), proposal_index, payload)
+            })
+            .collect()
+    }
+
+    /// number of options of the proposal, which is also the number of
+    /// entries its encrypted votes (and tally) are made of
+    fn options_count(proposal: &Proposal) -> usize {
+        proposal.options().choice_range().end as usize
+    }
+
     pub fn treasury_governance() -> TreasuryGovernance {
         let mut governance = TreasuryGovernance::new();
         governance.set_default_acceptance_criteria(Default::default());
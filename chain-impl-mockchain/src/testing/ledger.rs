@@ -504,6 +504,7 @@ impl TestLedger {
 
     pub fn apply_protocol_changes(&mut self) -> Result<(), Error> {
         self.ledger = self.ledger.apply_protocol_changes()?;
+        self.parameters = self.ledger.get_ledger_parameters();
         Ok(())
     }
 
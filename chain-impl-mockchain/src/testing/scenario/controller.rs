@@ -1,17 +1,21 @@
 use crate::{
     certificate::{
-        EncryptedVoteTally, ExternalProposalId, Proposal, TallyDecryptShares, VoteCast, VotePlan,
-        VoteTally,
+        CommitteeHandover, EncryptedVoteTally, ExternalProposalId, Proposal, TallyDecryptShares,
+        VoteCast, VoteCastBatch, VotePlan, VoteTally,
     },
     fee::LinearFee,
     key::Hash,
     ledger::Error as LedgerError,
     testing::{
-        data::{StakePool, Wallet},
+        data::{CommitteeMembersManager, StakePool, Wallet},
         ledger::TestLedger,
         scenario::template::VotePlanDef,
+        VoteTestGen,
+    },
+    vote::{
+        Choice, EncryptedVote, Payload, PayloadType, ProofOfCorrectVote, TallyResult,
+        VotePlanStatus,
     },
-    vote::{Choice, Payload, PayloadType, ProofOfCorrectVote},
 };
 
 #[cfg(test)]
@@ -20,7 +24,6 @@ use super::FragmentFactory;
 #[cfg(test)]
 use chain_addr::Discrimination;
 
-use crate::vote::EncryptedVote;
 use rand_core::{CryptoRng, RngCore};
 use thiserror::Error;
 
@@ -32,8 +35,14 @@ pub enum ControllerError {
     UnknownStakePool { alias: String },
     #[error("cannot find vote plan with alias {alias}")]
     UnknownVotePlan { alias: String },
+    #[error("cannot find committee with alias {alias}")]
+    UnknownCommittee { alias: String },
+    #[error("vote plan with alias {alias} does not use a scenario committee")]
+    NoCommitteeForVotePlan { alias: String },
     #[error("cannot find vote proposal with alias {id}")]
     UnknownVoteProposal { id: ExternalProposalId },
+    #[error("vote plan with alias {alias} is not active in the ledger")]
+    InactiveVotePlan { alias: String },
 }
 
 pub struct Controller {
@@ -41,6 +50,7 @@ pub struct Controller {
     pub declared_wallets: Vec<Wallet>,
     pub declared_stake_pools: Vec<StakePool>,
     pub declared_vote_plans: Vec<VotePlanDef>,
+    pub declared_committees: Vec<CommitteeMembersManager>,
     fragment_factory: FragmentFactory,
 }
 
@@ -51,12 +61,14 @@ impl Controller {
         declared_wallets: Vec<Wallet>,
         declared_stake_pools: Vec<StakePool>,
         declared_vote_plans: Vec<VotePlanDef>,
+        declared_committees: Vec<CommitteeMembersManager>,
     ) -> Self {
         Controller {
             block0_hash,
             declared_wallets,
             declared_stake_pools,
             declared_vote_plans,
+            declared_committees,
             fragment_factory: FragmentFactory::new(block0_hash, fee),
         }
     }
@@ -81,6 +93,44 @@ impl Controller {
             })
     }
 
+    pub fn committee(&self, alias: &str) -> Result<&CommitteeMembersManager, ControllerError> {
+        self.declared_committees
+            .iter()
+            .find(|x| x.alias() == alias)
+            .ok_or(ControllerError::UnknownCommittee {
+                alias: alias.to_owned(),
+            })
+    }
+
+    /// the state of the given vote plan in the ledger
+    pub fn active_vote_plan(
+        &self,
+        vote_plan_def: &VotePlanDef,
+        test_ledger: &TestLedger,
+    ) -> Result<VotePlanStatus, ControllerError> {
+        let id = vote_plan_def.to_id();
+        test_ledger
+            .ledger
+            .active_vote_plans()
+            .into_iter()
+            .find(|status| status.id == id)
+            .ok_or_else(|| ControllerError::InactiveVotePlan {
+                alias: vote_plan_def.alias(),
+            })
+    }
+
+    pub fn vote_plan_committee(
+        &self,
+        vote_plan_alias: &str,
+    ) -> Result<&CommitteeMembersManager, ControllerError> {
+        let committee_alias = self.vote_plan(vote_plan_alias)?.committee_alias().ok_or(
+            ControllerError::NoCommitteeForVotePlan {
+                alias: vote_plan_alias.to_owned(),
+            },
+        )?;
+        self.committee(&committee_alias)
+    }
+
     pub fn initial_stake_pools(&self) -> Vec<StakePool> {
         self.declared_stake_pools.clone()
     }
@@ -186,6 +236,19 @@ impl Controller {
         test_ledger.apply_fragment(&fragment, test_ledger.date())
     }
 
+    pub fn retire_at<'a>(
+        &'a self,
+        owners: impl IntoIterator<Item = &'a Wallet>,
+        stake_pool: &'a StakePool,
+        retirement_time: u64,
+        test_ledger: &'a mut TestLedger,
+    ) -> Result<(), LedgerError> {
+        let fragment =
+            self.fragment_factory
+                .stake_pool_retire_at(owners, stake_pool, retirement_time);
+        test_ledger.apply_fragment(&fragment, test_ledger.date())
+    }
+
     pub fn update<'a>(
         &'a self,
         stake_pool: &'a StakePool,
@@ -199,6 +262,34 @@ impl Controller {
         test_ledger.apply_fragment(&fragment, test_ledger.date())
     }
 
+    /// cast a vote for the given choice, the payload (public or private) is
+    /// chosen according to the vote plan's payload type.
+    pub fn cast_vote<R>(
+        &self,
+        owner: &Wallet,
+        vote_plan_def: &VotePlanDef,
+        id: &ExternalProposalId,
+        choice: Choice,
+        test_ledger: &mut TestLedger,
+        rng: &mut R,
+    ) -> Result<(), LedgerError>
+    where
+        R: RngCore + CryptoRng,
+    {
+        self.cast_vote_with(
+            owner,
+            vote_plan_def,
+            id,
+            test_ledger,
+            |vote_plan, proposal| match vote_plan.proposal_payload_type(proposal) {
+                PayloadType::Public => Payload::Public { choice },
+                PayloadType::Private => {
+                    VoteTestGen::private_vote_cast_payload(vote_plan, proposal, choice, rng)
+                }
+            },
+        )
+    }
+
     pub fn cast_vote_public(
         &self,
         owner: &Wallet,
@@ -207,12 +298,12 @@ impl Controller {
         choice: Choice,
         test_ledger: &mut TestLedger,
     ) -> Result<(), LedgerError> {
-        self.cast_vote(
+        self.cast_vote_with(
             owner,
             vote_plan_def,
             id,
             test_ledger,
-            |vote_plan, _proposal| match vote_plan.payload_type() {
+            |vote_plan, proposal| match vote_plan.proposal_payload_type(proposal) {
                 PayloadType::Public => Payload::Public { choice },
                 PayloadType::Private => panic!("this is a private vote plan"),
             },
@@ -231,37 +322,77 @@ impl Controller {
     where
         R: RngCore + CryptoRng,
     {
-        self.cast_vote(
+        self.cast_vote_with(
             owner,
             vote_plan_def,
             id,
             test_ledger,
-            |vote_plan, proposal| match vote_plan.payload_type() {
+            |vote_plan, proposal| match vote_plan.proposal_payload_type(proposal) {
                 PayloadType::Public => panic!("this is a public vote plan"),
                 PayloadType::Private => {
-                    let encrypting_key = chain_vote::EncryptingVoteKey::from_participants(
-                        vote_plan.committee_public_keys(),
-                    );
-
-                    let (encrypted_vote, proof) = chain_vote::encrypt_vote(
-                        rng,
-                        &encrypting_key,
-                        chain_vote::Vote::new(
-                            proposal.options().choice_range().clone().max().unwrap() as usize,
-                            choice.as_byte() as usize,
-                        ),
-                    );
-
-                    Payload::Private {
-                        encrypted_vote: EncryptedVote::from_inner(encrypted_vote),
-                        proof: ProofOfCorrectVote::from_inner(proof),
-                    }
+                    VoteTestGen::private_vote_cast_payload(vote_plan, proposal, choice, rng)
                 }
             },
         )
     }
 
-    fn cast_vote<F>(
+    /// cast private votes on several proposals of the same vote plan in a
+    /// single transaction, the ledger applies them all or none of them
+    pub fn cast_votes_private<R>(
+        &self,
+        owner: &Wallet,
+        vote_plan_def: &VotePlanDef,
+        votes: &[(ExternalProposalId, Choice)],
+        test_ledger: &mut TestLedger,
+        rng: &mut R,
+    ) -> Result<(), LedgerError>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let vote_plan: VotePlan = vote_plan_def.clone().into();
+        let ballots = votes
+            .iter()
+            .map(|(id, choice)| {
+                let (index, proposal) = vote_plan
+                    .proposals()
+                    .iter()
+                    .enumerate()
+                    .find(|(_, x)| *x.external_id() == *id)
+                    .map(|(index, proposal)| (index as u8, proposal))
+                    .expect("cannot find proposal");
+                let payload = match vote_plan.proposal_payload_type(proposal) {
+                    PayloadType::Public => panic!("this is a public vote plan"),
+                    PayloadType::Private => {
+                        VoteTestGen::private_vote_cast_payload(&vote_plan, proposal, *choice, rng)
+                    }
+                };
+                (index, payload)
+            })
+            .collect();
+        let vote_cast_batch = VoteCastBatch::new(vote_plan.to_id(), ballots);
+        let fragment = self
+            .fragment_factory
+            .vote_cast_batch(owner, vote_cast_batch);
+        test_ledger.apply_fragment(&fragment, test_ledger.date())
+    }
+
+    /// cast a private vote made of the given encrypted vote and proof as they
+    /// are, without checking the proof matches the encrypted vote
+    pub fn cast_vote_private_raw(
+        &self,
+        owner: &Wallet,
+        vote_plan_def: &VotePlanDef,
+        id: &ExternalProposalId,
+        encrypted_vote: EncryptedVote,
+        proof: ProofOfCorrectVote,
+        test_ledger: &mut TestLedger,
+    ) -> Result<(), LedgerError> {
+        self.cast_vote_with(owner, vote_plan_def, id, test_ledger, |_, _| {
+            Payload::private(encrypted_vote.clone(), proof.clone())
+        })
+    }
+
+    fn cast_vote_with<F>(
         &self,
         owner: &Wallet,
         vote_plan_def: &VotePlanDef,
@@ -286,6 +417,23 @@ impl Controller {
         test_ledger.apply_fragment(&fragment, test_ledger.date())
     }
 
+    /// cast each of the ballots with the corresponding owner, confirming the
+    /// owners' transactions as they are applied
+    pub fn cast_ballots(
+        &self,
+        owners: &mut [Wallet],
+        ballots: Vec<VoteCast>,
+        test_ledger: &mut TestLedger,
+    ) -> Result<(), LedgerError> {
+        assert_eq!(owners.len(), ballots.len(), "one ballot per owner");
+        for (owner, ballot) in owners.iter_mut().zip(ballots) {
+            let fragment = self.fragment_factory.vote_cast(owner, ballot);
+            test_ledger.apply_fragment(&fragment, test_ledger.date())?;
+            owner.confirm_transaction();
+        }
+        Ok(())
+    }
+
     pub fn encrypted_tally(
         &self,
         owner: &Wallet,
@@ -300,31 +448,66 @@ impl Controller {
         test_ledger.apply_fragment(&fragment, test_ledger.date())
     }
 
-    pub fn tally_vote_public(
+    /// hand the committee of the vote plan over to the members of the given
+    /// committee
+    pub fn committee_handover(
         &self,
         owner: &Wallet,
         vote_plan_def: &VotePlanDef,
+        committee: &CommitteeMembersManager,
         test_ledger: &mut TestLedger,
     ) -> Result<(), LedgerError> {
-        let vote_plan: VotePlan = vote_plan_def.clone().into();
-        let vote_tally = VoteTally::new_public(vote_plan.to_id());
+        let handover = CommitteeHandover::new(vote_plan_def.to_id(), committee.public_keys());
+        let fragment = self.fragment_factory.committee_handover(owner, handover);
+        test_ledger.apply_fragment(&fragment, test_ledger.date())
+    }
+
+    /// tally the public vote plan, returning the results of its proposals
+    pub fn tally_vote_public(
+        &self,
+        owner: &Wallet,
+        vote_plan_def: &VotePlanDef,
+        test_ledger: &mut TestLedger,
+    ) -> Result<Vec<TallyResult>, LedgerError> {
+        let vote_tally = VoteTally::new_public(vote_plan_def.to_id());
 
         let fragment = self.fragment_factory.vote_tally(owner, vote_tally);
-        test_ledger.apply_fragment(&fragment, test_ledger.date())
+        test_ledger.apply_fragment(&fragment, test_ledger.date())?;
+        Ok(self.tally_results(vote_plan_def, test_ledger))
     }
 
+    /// tally the private vote plan, returning the decrypted results of its
+    /// proposals
     pub fn tally_vote_private(
         &self,
         owner: &Wallet,
         vote_plan_def: &VotePlanDef,
         shares: TallyDecryptShares,
         test_ledger: &mut TestLedger,
-    ) -> Result<(), LedgerError> {
-        let vote_plan: VotePlan = vote_plan_def.clone().into();
-        let vote_tally = VoteTally::new_private(vote_plan.to_id(), shares);
+    ) -> Result<Vec<TallyResult>, LedgerError> {
+        let vote_tally = VoteTally::new_private(vote_plan_def.to_id(), shares);
 
         let fragment = self.fragment_factory.vote_tally(owner, vote_tally);
-        test_ledger.apply_fragment(&fragment, test_ledger.date())
+        test_ledger.apply_fragment(&fragment, test_ledger.date())?;
+        Ok(self.tally_results(vote_plan_def, test_ledger))
+    }
+
+    fn tally_results(
+        &self,
+        vote_plan_def: &VotePlanDef,
+        test_ledger: &TestLedger,
+    ) -> Vec<TallyResult> {
+        self.active_vote_plan(vote_plan_def, test_ledger)
+            .expect("cannot find vote plan")
+            .proposals
+            .iter()
+            .map(|proposal| {
+                proposal
+                    .tally_result()
+                    .expect("proposal is not tallied")
+                    .clone()
+            })
+            .collect()
     }
 }
 
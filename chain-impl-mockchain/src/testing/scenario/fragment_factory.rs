@@ -1,7 +1,8 @@
 use crate::{
     accounting::account::{DelegationRatio, DelegationType},
     certificate::{
-        Certificate, EncryptedVoteTally, PoolId, PoolUpdate, VoteCast, VotePlan, VoteTally,
+        Certificate, CommitteeHandover, EncryptedVoteTally, PoolId, PoolUpdate, VoteCast,
+        VoteCastBatch, VotePlan, VoteTally,
     },
     fee::LinearFee,
     fragment::Fragment,
@@ -99,7 +100,16 @@ impl FragmentFactory {
         owners: impl IntoIterator<Item = &'a Wallet>,
         stake_pool: &StakePool,
     ) -> Fragment {
-        let certificate = build_stake_pool_retirement_cert(stake_pool.id(), 0);
+        self.stake_pool_retire_at(owners, stake_pool, 0)
+    }
+
+    pub fn stake_pool_retire_at<'a>(
+        &self,
+        owners: impl IntoIterator<Item = &'a Wallet>,
+        stake_pool: &StakePool,
+        retirement_time: u64,
+    ) -> Fragment {
+        let certificate = build_stake_pool_retirement_cert(stake_pool.id(), retirement_time);
         self.transaction_with_cert(owners, &certificate)
     }
 
@@ -126,6 +136,10 @@ impl FragmentFactory {
         self.transaction_with_cert(Some(owner), &vote_cast.into())
     }
 
+    pub fn vote_cast_batch(&self, owner: &Wallet, vote_cast_batch: VoteCastBatch) -> Fragment {
+        self.transaction_with_cert(Some(owner), &vote_cast_batch.into())
+    }
+
     pub fn vote_encrypted_tally(
         &self,
         owner: &Wallet,
@@ -138,6 +152,10 @@ impl FragmentFactory {
         self.transaction_with_cert(Some(owner), &vote_tally.into())
     }
 
+    pub fn committee_handover(&self, owner: &Wallet, handover: CommitteeHandover) -> Fragment {
+        self.transaction_with_cert(Some(owner), &handover.into())
+    }
+
     fn transaction_with_cert<'a>(
         &self,
         wallets: impl IntoIterator<Item = &'a Wallet>,
@@ -2,7 +2,11 @@ mod controller;
 mod fragment_factory;
 mod scenario_builder;
 pub mod template;
+mod vote_scenario;
 
 pub use controller::Controller;
 pub use fragment_factory::FragmentFactory;
-pub use scenario_builder::{prepare_scenario, proposal, stake_pool, vote_plan, wallet};
+pub use scenario_builder::{committee, prepare_scenario, proposal, stake_pool, vote_plan, wallet};
+pub use vote_scenario::{
+    prepare_vote_scenario, VoteScenarioBuilder, ALICE, COMMITTEE, STAKE_POOL, VOTE_PLAN,
+};
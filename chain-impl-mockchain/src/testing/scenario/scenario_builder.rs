@@ -1,7 +1,7 @@
 use super::{
     template::{
-        StakePoolDefBuilder, StakePoolTemplate, StakePoolTemplateBuilder, WalletTemplate,
-        WalletTemplateBuilder,
+        CommitteeDef, CommitteeDefBuilder, StakePoolDefBuilder, StakePoolTemplate,
+        StakePoolTemplateBuilder, WalletTemplate, WalletTemplateBuilder,
     },
     Controller,
 };
@@ -17,12 +17,14 @@ use crate::{
             StakePoolBuilder,
         },
         create_initial_vote_plan,
-        data::{AddressDataValue, StakePool, Wallet},
+        data::{AddressDataValue, CommitteeMembersManager, StakePool, Wallet},
         ledger::{ConfigBuilder, LedgerBuilder, TestLedger},
         scenario::template::{VotePlanDef, VotePlanDefBuilder},
     },
 };
 use chain_addr::Discrimination;
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
 
 use thiserror::Error;
 
@@ -36,6 +38,8 @@ pub enum ScenarioBuilderError {
     NoOwnersForStakePool { alias: String },
     #[error("with(...) method must be used for '{alias}' wallet in scenario builder. ")]
     UndefinedValueForWallet { alias: String },
+    #[error("committee '{alias}' must be defined with with_committees(...) method in scenario builder. ")]
+    UndefinedCommittee { alias: String },
 }
 
 pub struct ScenarioBuilder {
@@ -43,6 +47,7 @@ pub struct ScenarioBuilder {
     initials: Option<Vec<WalletTemplateBuilder>>,
     stake_pools: Option<Vec<StakePoolDefBuilder>>,
     vote_plans: Vec<VotePlanDefBuilder>,
+    committees: Vec<CommitteeDefBuilder>,
 }
 
 pub fn prepare_scenario() -> ScenarioBuilder {
@@ -55,6 +60,7 @@ pub fn prepare_scenario() -> ScenarioBuilder {
         initials: None,
         stake_pools: None,
         vote_plans: Vec::new(),
+        committees: Vec::new(),
     }
 }
 
@@ -74,6 +80,11 @@ impl ScenarioBuilder {
         self
     }
 
+    pub fn with_committees(&mut self, committees: Vec<&mut CommitteeDefBuilder>) -> &mut Self {
+        self.committees = committees.iter().map(|x| (**x).clone()).collect();
+        self
+    }
+
     pub fn with_stake_pools(&mut self, stake_pools: Vec<&mut StakePoolDefBuilder>) -> &mut Self {
         self.stake_pools = Some(stake_pools.iter().map(|x| (**x).clone()).collect());
         self
@@ -107,14 +118,12 @@ impl ScenarioBuilder {
         let faucets: Vec<AddressDataValue> =
             wallets.iter().cloned().map(|x| x.as_account()).collect();
 
-        let vote_plan_defs: Vec<VotePlanDef> =
-            self.vote_plans.iter().map(|x| x.clone().build()).collect();
-        let vote_plan_fragments: Vec<Fragment> = self
-            .vote_plans
+        let committees = self.build_committees();
+        let vote_plan_defs = self.build_vote_plans(&committees)?;
+        let vote_plan_fragments: Vec<Fragment> = vote_plan_defs
             .iter()
             .cloned()
-            .map(|x| {
-                let vote_plan_def = x.build();
+            .map(|vote_plan_def| {
                 let owner = wallets
                     .iter()
                     .cloned()
@@ -145,10 +154,55 @@ impl ScenarioBuilder {
 
         Ok((
             test_ledger,
-            Controller::new(block0_hash, fee, wallets, stake_pools, vote_plan_defs),
+            Controller::new(
+                block0_hash,
+                fee,
+                wallets,
+                stake_pools,
+                vote_plan_defs,
+                committees,
+            ),
         ))
     }
 
+    fn build_committees(&self) -> Vec<CommitteeMembersManager> {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        self.committees
+            .iter()
+            .map(|x| x.build())
+            .map(|committee_def: CommitteeDef| {
+                CommitteeMembersManager::new(
+                    &committee_def.alias,
+                    &mut rng,
+                    committee_def.threshold,
+                    committee_def.members_no,
+                )
+            })
+            .collect()
+    }
+
+    fn build_vote_plans(
+        &self,
+        committees: &[CommitteeMembersManager],
+    ) -> Result<Vec<VotePlanDef>, ScenarioBuilderError> {
+        self.vote_plans
+            .iter()
+            .cloned()
+            .map(|mut vote_plan_builder| {
+                if let Some(committee_alias) = vote_plan_builder.committee_alias() {
+                    let committee = committees
+                        .iter()
+                        .find(|x| x.alias() == committee_alias)
+                        .ok_or(ScenarioBuilderError::UndefinedCommittee {
+                            alias: committee_alias.clone(),
+                        })?;
+                    vote_plan_builder.committee_keys(committee.public_keys());
+                }
+                Ok(vote_plan_builder.build())
+            })
+            .collect()
+    }
+
     fn build_stake_pools_fragments(
         &self,
         stake_pools: &[StakePool],
@@ -244,6 +298,10 @@ pub fn stake_pool(alias: &str) -> StakePoolDefBuilder {
     StakePoolDefBuilder::new(alias)
 }
 
+pub fn committee(alias: &str) -> CommitteeDefBuilder {
+    CommitteeDefBuilder::new(alias)
+}
+
 pub fn vote_plan(alias: &str) -> VotePlanDefBuilder {
     VotePlanDefBuilder::new(alias)
 }
@@ -1,16 +1,19 @@
 use super::{StakePoolTemplate, WalletTemplate};
 use crate::certificate::VoteAction;
 use crate::ledger::governance::{ParametersGovernanceAction, TreasuryGovernanceAction};
+use crate::testing::scenario::template::CommitteeDef;
 use crate::testing::scenario::template::ExternalProposalId;
 use crate::testing::scenario::template::ProposalDef;
 use crate::testing::scenario::template::VotePlanDef;
 use crate::{
+    account::Identifier,
     date::BlockDate,
+    fee::LinearFee,
     rewards::{Ratio, TaxType},
     testing::data::Wallet,
     testing::scenario::{scenario_builder::ScenarioBuilderError, template::StakePoolDef},
     value::Value,
-    vote::PayloadType,
+    vote::{PayloadType, TieBreak},
 };
 use std::{
     collections::{HashMap, HashSet},
@@ -190,14 +193,10 @@ impl StakePoolDefBuilder {
     }
 
     pub fn tax_ratio(&mut self, numerator: u64, denominator: u64) -> &mut Self {
-        self.tax_type = Some(TaxType {
-            fixed: Value(0),
-            ratio: Ratio {
-                numerator,
-                denominator: NonZeroU64::new(denominator).unwrap(),
-            },
-            max_limit: None,
-        });
+        self.tax_type.get_or_insert_with(TaxType::zero).ratio = Ratio {
+            numerator,
+            denominator: NonZeroU64::new(denominator).unwrap(),
+        };
         self
     }
 
@@ -210,11 +209,7 @@ impl StakePoolDefBuilder {
     }
 
     pub fn fixed_tax(&mut self, value: u64) -> &mut Self {
-        self.tax_type = Some(TaxType {
-            fixed: Value(value),
-            ratio: Ratio::zero(),
-            max_limit: None,
-        });
+        self.tax_type.get_or_insert_with(TaxType::zero).fixed = Value(value);
         self
     }
 
@@ -233,6 +228,41 @@ impl StakePoolDefBuilder {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct CommitteeDefBuilder {
+    alias: String,
+    members_no: usize,
+    threshold: usize,
+}
+
+impl CommitteeDefBuilder {
+    pub fn new(alias: &str) -> Self {
+        CommitteeDefBuilder {
+            alias: alias.to_owned(),
+            members_no: 1,
+            threshold: 1,
+        }
+    }
+
+    pub fn members_no(&mut self, members_no: usize) -> &mut Self {
+        self.members_no = members_no;
+        self
+    }
+
+    pub fn threshold(&mut self, threshold: usize) -> &mut Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn build(&self) -> CommitteeDef {
+        CommitteeDef {
+            alias: self.alias.clone(),
+            members_no: self.members_no,
+            threshold: self.threshold,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct VotePlanDefBuilder {
     alias: String,
@@ -241,6 +271,7 @@ pub struct VotePlanDefBuilder {
     vote_date: Option<BlockDate>,
     tally_date: Option<BlockDate>,
     end_tally_date: Option<BlockDate>,
+    committee_alias: Option<String>,
     committee_keys: Vec<MemberPublicKey>,
     proposals: Vec<ProposalDef>,
 }
@@ -254,6 +285,7 @@ impl VotePlanDefBuilder {
             vote_date: Option::None,
             tally_date: Option::None,
             end_tally_date: Option::None,
+            committee_alias: Option::None,
             committee_keys: Vec::new(),
             proposals: Vec::new(),
         }
@@ -274,6 +306,15 @@ impl VotePlanDefBuilder {
         self
     }
 
+    pub fn committee(&mut self, committee_alias: &str) -> &mut Self {
+        self.committee_alias = Some(committee_alias.to_string());
+        self
+    }
+
+    pub fn committee_alias(&self) -> Option<String> {
+        self.committee_alias.clone()
+    }
+
     pub fn vote_phases(&mut self, start_epoch: u32, tally_epoch: u32, end_epoch: u32) -> &mut Self {
         self.vote_date = Some(BlockDate {
             epoch: start_epoch,
@@ -320,6 +361,7 @@ impl VotePlanDefBuilder {
             tally_date: self.tally_date.unwrap(),
             end_tally_date: self.end_tally_date.unwrap(),
             proposals: self.proposals,
+            committee_alias: self.committee_alias,
             committee_keys: self.committee_keys,
         }
     }
@@ -330,6 +372,10 @@ pub struct ProposalDefBuilder {
     id: ExternalProposalId,
     options: u8,
     action_type: VoteAction,
+    payload_type: Option<PayloadType>,
+    quorum: Option<Ratio>,
+    approval_threshold: Option<Ratio>,
+    tie_break: TieBreak,
 }
 
 impl ProposalDefBuilder {
@@ -338,6 +384,10 @@ impl ProposalDefBuilder {
             id,
             options: 3,
             action_type: VoteAction::OffChain,
+            payload_type: None,
+            quorum: None,
+            approval_threshold: None,
+            tie_break: TieBreak::default(),
         }
     }
 
@@ -346,6 +396,28 @@ impl ProposalDefBuilder {
         self
     }
 
+    /// vote for the proposal with a different payload type than the vote
+    /// plan's
+    pub fn payload_type(&mut self, payload_type: PayloadType) -> &mut Self {
+        self.payload_type = Some(payload_type);
+        self
+    }
+
+    pub fn quorum(&mut self, quorum: Ratio) -> &mut Self {
+        self.quorum = Some(quorum);
+        self
+    }
+
+    pub fn approval_threshold(&mut self, approval_threshold: Ratio) -> &mut Self {
+        self.approval_threshold = Some(approval_threshold);
+        self
+    }
+
+    pub fn tie_break(&mut self, tie_break: TieBreak) -> &mut Self {
+        self.tie_break = tie_break;
+        self
+    }
+
     pub fn action_off_chain(&mut self) -> &mut Self {
         self.action_type = VoteAction::OffChain;
         self
@@ -360,6 +432,28 @@ impl ProposalDefBuilder {
         self
     }
 
+    pub fn action_transfer_to_account(&mut self, account: Identifier, value: u64) -> &mut Self {
+        self.action_type = VoteAction::Treasury {
+            action: TreasuryGovernanceAction::TransferToAccount {
+                account,
+                value: Value(value),
+            },
+        };
+        self
+    }
+
+    pub fn action_transfer_to_accounts(&mut self, payees: Vec<(Identifier, u64)>) -> &mut Self {
+        self.action_type = VoteAction::Treasury {
+            action: TreasuryGovernanceAction::TransferToAccounts {
+                payees: payees
+                    .into_iter()
+                    .map(|(account, value)| (account, Value(value)))
+                    .collect(),
+            },
+        };
+        self
+    }
+
     pub fn action_transfer_to_rewards(&mut self, value: u64) -> &mut Self {
         self.action_type = VoteAction::Parameters {
             action: ParametersGovernanceAction::RewardAdd {
@@ -369,6 +463,13 @@ impl ProposalDefBuilder {
         self
     }
 
+    pub fn action_update_fee(&mut self, fee: LinearFee) -> &mut Self {
+        self.action_type = VoteAction::Parameters {
+            action: ParametersGovernanceAction::FeeUpdate { fee },
+        };
+        self
+    }
+
     pub fn action_parameters_no_op(&mut self) -> &mut Self {
         self.action_type = VoteAction::Parameters {
             action: ParametersGovernanceAction::NoOp,
@@ -381,6 +482,10 @@ impl ProposalDefBuilder {
             id: self.id,
             options: self.options,
             action_type: self.action_type,
+            payload_type: self.payload_type,
+            quorum: self.quorum,
+            approval_threshold: self.approval_threshold,
+            tie_break: self.tie_break,
         }
     }
 }
@@ -1,11 +1,13 @@
 mod builders;
 
 use crate::{
-    certificate::{ExternalProposalId, PoolPermissions, Proposal, Proposals, VoteAction, VotePlan},
+    certificate::{
+        ExternalProposalId, PoolPermissions, Proposal, Proposals, VoteAction, VotePlan, VotePlanId,
+    },
     header::BlockDate,
-    rewards::TaxType,
+    rewards::{Ratio, TaxType},
     value::Value,
-    vote::{Options, PayloadType},
+    vote::{Options, PayloadType, TieBreak},
 };
 pub use builders::*;
 use chain_crypto::{Ed25519, PublicKey};
@@ -96,6 +98,13 @@ impl StakePoolDef {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct CommitteeDef {
+    pub alias: String,
+    pub members_no: usize,
+    pub threshold: usize,
+}
+
 #[derive(Clone, Debug)]
 pub struct VotePlanDef {
     alias: String,
@@ -104,6 +113,7 @@ pub struct VotePlanDef {
     vote_date: BlockDate,
     tally_date: BlockDate,
     end_tally_date: BlockDate,
+    committee_alias: Option<String>,
     committee_keys: Vec<MemberPublicKey>,
     proposals: Vec<ProposalDef>,
 }
@@ -117,6 +127,10 @@ impl VotePlanDef {
         self.owner_alias.clone()
     }
 
+    pub fn committee_alias(&self) -> Option<String> {
+        self.committee_alias.clone()
+    }
+
     pub fn proposals(&self) -> Vec<ProposalDef> {
         self.proposals.iter().cloned().map(Into::into).collect()
     }
@@ -126,8 +140,12 @@ impl VotePlanDef {
     }
 
     pub fn id(&self) -> String {
+        self.to_id().to_string()
+    }
+
+    pub fn to_id(&self) -> VotePlanId {
         let vote_plan: VotePlan = self.clone().into();
-        vote_plan.to_id().to_string()
+        vote_plan.to_id()
     }
 }
 
@@ -154,6 +172,10 @@ pub struct ProposalDef {
     id: ExternalProposalId,
     options: u8,
     action_type: VoteAction,
+    payload_type: Option<PayloadType>,
+    quorum: Option<Ratio>,
+    approval_threshold: Option<Ratio>,
+    tie_break: TieBreak,
 }
 
 impl ProposalDef {
@@ -164,10 +186,23 @@ impl ProposalDef {
 
 impl Into<Proposal> for ProposalDef {
     fn into(self) -> Proposal {
-        Proposal::new(
+        let proposal = Proposal::new(
             self.id,
             Options::new_length(self.options).unwrap(),
             self.action_type,
         )
+        .with_tie_break(self.tie_break);
+        let proposal = match self.payload_type {
+            Some(payload_type) => proposal.with_payload_type(payload_type),
+            None => proposal,
+        };
+        let proposal = match self.quorum {
+            Some(quorum) => proposal.with_quorum(quorum),
+            None => proposal,
+        };
+        match self.approval_threshold {
+            Some(approval_threshold) => proposal.with_approval_threshold(approval_threshold),
+            None => proposal,
+        }
     }
 }
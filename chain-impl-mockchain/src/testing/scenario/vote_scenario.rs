@@ -0,0 +1,105 @@
+use super::{
+    scenario_builder::{committee, prepare_scenario, vote_plan, wallet, ScenarioBuilderError},
+    template::{
+        CommitteeDefBuilder, ProposalDefBuilder, VotePlanDefBuilder, WalletTemplateBuilder,
+    },
+    Controller,
+};
+use crate::{
+    fee::LinearFee,
+    testing::ledger::{ConfigBuilder, TestLedger},
+    value::Value,
+    vote::PayloadType,
+};
+
+pub const ALICE: &str = "Alice";
+pub const STAKE_POOL: &str = "stake_pool";
+pub const VOTE_PLAN: &str = "fund1";
+pub const COMMITTEE: &str = "committee";
+
+/// scenario shared by the vote tests
+///
+/// `ALICE` holds 1_000, owns `STAKE_POOL`, is a committee member and owns
+/// the `VOTE_PLAN` vote plan, whose voting, tally and end dates are the
+/// first three epochs. The other voters are added with `with_voters`.
+pub struct VoteScenarioBuilder {
+    config: ConfigBuilder,
+    voters: Vec<WalletTemplateBuilder>,
+    committee: Option<CommitteeDefBuilder>,
+    vote_plan: VotePlanDefBuilder,
+}
+
+pub fn prepare_vote_scenario() -> VoteScenarioBuilder {
+    let mut vote_plan = vote_plan(VOTE_PLAN);
+    vote_plan.owner(ALICE).consecutive_epoch_dates();
+
+    VoteScenarioBuilder {
+        config: ConfigBuilder::new(0).with_fee(LinearFee::new(1, 1, 1)),
+        voters: Vec::new(),
+        committee: None,
+        vote_plan,
+    }
+}
+
+impl VoteScenarioBuilder {
+    pub fn with_config(&mut self, config: ConfigBuilder) -> &mut Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_rewards(&mut self, value: Value) -> &mut Self {
+        self.config = self.config.clone().with_rewards(value);
+        self
+    }
+
+    pub fn with_treasury(&mut self, value: Value) -> &mut Self {
+        self.config = self.config.clone().with_treasury(value);
+        self
+    }
+
+    pub fn with_voters(&mut self, voters: Vec<&mut WalletTemplateBuilder>) -> &mut Self {
+        self.voters = voters.iter().map(|x| (**x).clone()).collect();
+        self
+    }
+
+    pub fn payload_type(&mut self, payload_type: PayloadType) -> &mut Self {
+        self.vote_plan.payload_type(payload_type);
+        self
+    }
+
+    /// the vote plan is decided by `COMMITTEE`, `threshold` of its
+    /// `members_no` members being needed to decrypt a private tally
+    pub fn committee(&mut self, members_no: usize, threshold: usize) -> &mut Self {
+        self.committee = Some(
+            committee(COMMITTEE)
+                .members_no(members_no)
+                .threshold(threshold)
+                .clone(),
+        );
+        self.vote_plan.committee(COMMITTEE);
+        self
+    }
+
+    pub fn with_proposal(&mut self, proposal_builder: &mut ProposalDefBuilder) -> &mut Self {
+        self.vote_plan.with_proposal(proposal_builder);
+        self
+    }
+
+    pub fn build(&self) -> Result<(TestLedger, Controller), ScenarioBuilderError> {
+        let mut initials = vec![wallet(ALICE)
+            .with(1_000)
+            .owns(STAKE_POOL)
+            .committee_member()
+            .clone()];
+        initials.extend(self.voters.iter().cloned());
+        let mut committees: Vec<_> = self.committee.iter().cloned().collect();
+        let mut vote_plan = self.vote_plan.clone();
+
+        prepare_scenario()
+            .with_config(self.config.clone())
+            .with_initials(initials.iter_mut().collect())
+            .with_committees(committees.iter_mut().collect())
+            .with_vote_plans(vec![&mut vote_plan])
+            .build()
+    }
+}
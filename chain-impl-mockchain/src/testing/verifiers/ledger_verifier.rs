@@ -1,13 +1,16 @@
 use crate::{
     account::{Identifier, Ledger as AccountLedger},
     accounting::account::{account_state::AccountState, DelegationType},
-    certificate::{PoolId, PoolRegistration},
+    certificate::{PoolId, PoolRegistration, VotePlanId},
+    date::BlockDate,
+    leadership::genesis::LeadershipData,
     ledger::{ledger::Ledger, Pots},
     stake::PoolsState,
     stake::{Stake, StakeDistribution},
     testing::data::{AddressData, StakePool},
     utxo,
     value::Value,
+    vote::{Decision, Tally, VotePlanStatus, Weight},
 };
 use chain_addr::Address;
 use chain_crypto::{Ed25519, PublicKey};
@@ -136,6 +139,39 @@ impl LedgerStateVerifier {
         )
     }
 
+    /// check the stake pool is elected to produce the block at the given
+    /// date, according to the current stake distribution
+    pub fn is_block_leader(&self, stake_pool: &StakePool, date: BlockDate) -> &Self {
+        assert!(
+            self.block_leader(stake_pool, date),
+            "stake pool {} should be the leader at {} {}",
+            stake_pool.alias(),
+            date,
+            self.info
+        );
+        self
+    }
+
+    /// check the stake pool is not elected to produce the block at the given
+    /// date, according to the current stake distribution
+    pub fn is_not_block_leader(&self, stake_pool: &StakePool, date: BlockDate) -> &Self {
+        assert!(
+            !self.block_leader(stake_pool, date),
+            "stake pool {} should not be the leader at {} {}",
+            stake_pool.alias(),
+            date,
+            self.info
+        );
+        self
+    }
+
+    fn block_leader(&self, stake_pool: &StakePool, date: BlockDate) -> bool {
+        LeadershipData::new(date.epoch, &self.ledger)
+            .leader(&stake_pool.id(), stake_pool.vrf().private_key(), date)
+            .expect("cannot evaluate the leadership")
+            .is_some()
+    }
+
     pub fn total_value_is(&self, value: &Value) -> &Self {
         let actual_value = self.ledger.get_total_value().expect("total amount too big");
         assert_eq!(
@@ -184,6 +220,102 @@ impl LedgerStateVerifier {
     pub fn pots(&self) -> PotsVerifier {
         PotsVerifier::new(self.ledger.pots.clone(), self.info.clone())
     }
+
+    pub fn vote_plans(&self) -> VotePlansVerifier {
+        VotePlansVerifier::new(self.ledger.active_vote_plans(), self.info.clone())
+    }
+}
+
+pub struct VotePlansVerifier {
+    vote_plans: Vec<VotePlanStatus>,
+    info: Info,
+}
+
+impl VotePlansVerifier {
+    pub fn new(vote_plans: Vec<VotePlanStatus>, info: Info) -> Self {
+        VotePlansVerifier { vote_plans, info }
+    }
+
+    pub fn tally_for(&self, vote_plan_id: &VotePlanId, proposal_index: usize) -> TallyVerifier {
+        let vote_plan = self
+            .vote_plans
+            .iter()
+            .find(|vote_plan| vote_plan.id == *vote_plan_id)
+            .unwrap_or_else(|| panic!("vote plan {} does not exist {}", vote_plan_id, self.info));
+        let proposal = vote_plan.proposals.get(proposal_index).unwrap_or_else(|| {
+            panic!(
+                "vote plan {} has no proposal with index {} {}",
+                vote_plan_id, proposal_index, self.info
+            )
+        });
+        TallyVerifier::new(proposal.tally.clone(), self.info.clone())
+    }
+}
+
+pub struct TallyVerifier {
+    tally: Option<Tally>,
+    info: Info,
+}
+
+impl TallyVerifier {
+    pub fn new(tally: Option<Tally>, info: Info) -> Self {
+        TallyVerifier { tally, info }
+    }
+
+    /// check the weights of each option of a public tally, or of a
+    /// private tally once decrypted
+    pub fn has_result(&self, expected: &[Weight]) -> &Self {
+        let result = self
+            .tally
+            .as_ref()
+            .unwrap_or_else(|| panic!("proposal is not tallied {}", self.info))
+            .result()
+            .unwrap_or_else(|| panic!("private tally is not decrypted {}", self.info));
+        assert_eq!(
+            result.results(),
+            expected,
+            "incorrect tally result {}",
+            self.info
+        );
+        self
+    }
+
+    pub fn has_decision(&self, expected: Decision) -> &Self {
+        let decision = self
+            .tally
+            .as_ref()
+            .unwrap_or_else(|| panic!("proposal is not tallied {}", self.info))
+            .decision()
+            .unwrap_or_else(|| panic!("private tally is not decrypted {}", self.info));
+        assert_eq!(decision, expected, "incorrect decision {}", self.info);
+        self
+    }
+
+    /// check the voting power that could and did take part in the tally
+    pub fn has_participation(&self, eligible: Stake, voted: Stake) -> &Self {
+        let participation = self
+            .tally
+            .as_ref()
+            .unwrap_or_else(|| panic!("proposal is not tallied {}", self.info))
+            .participation();
+        assert_eq!(
+            participation.eligible(),
+            eligible,
+            "incorrect eligible stake {}",
+            self.info
+        );
+        assert_eq!(
+            participation.voted(),
+            voted,
+            "incorrect voted stake {}",
+            self.info
+        );
+        self
+    }
+
+    pub fn and(&self) -> &Self {
+        self
+    }
 }
 
 pub struct AccountVerifier {
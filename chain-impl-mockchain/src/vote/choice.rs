@@ -25,6 +25,7 @@ pub struct Options {
 ///
 /// A `Choice` is a representation of a choice that has been made and must
 /// be compliant with the `Options`. A way to validate it is with `Options::validate`.
+/// The only exception is `Choice::ABSTAIN`, which is never one of the options.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub struct Choice(u8);
 
@@ -66,6 +67,12 @@ impl Options {
 }
 
 impl Choice {
+    /// the choice of a public ballot which abstains
+    ///
+    /// the stake of the ballot counts toward the participation of the
+    /// tally but not toward any of the options of the proposal.
+    pub const ABSTAIN: Self = Choice(u8::MAX);
+
     pub fn new(choice: u8) -> Self {
         Choice(choice)
     }
@@ -73,6 +80,10 @@ impl Choice {
     pub fn as_byte(self) -> u8 {
         self.0
     }
+
+    pub fn is_abstain(self) -> bool {
+        self == Self::ABSTAIN
+    }
 }
 
 #[cfg(any(test, feature = "property-test-api"))]
@@ -121,6 +132,7 @@ mod tests {
         validate_choices(&options, (0..num_choices).map(Choice::new), true);
 
         validate_choices(&options, (num_choices..=u8::MAX).map(Choice::new), false);
+        validate_choices(&options, Some(Choice::ABSTAIN), false);
     }
 
     #[test]
@@ -1,6 +1,10 @@
-use crate::certificate::{EncryptedVoteTally, EncryptedVoteTallyProof};
+use crate::certificate::{
+    CommitteeHandover, CommitteeHandoverProof, EncryptedVoteTally, EncryptedVoteTallyProof,
+};
 use crate::{
-    certificate::{TallyProof, VoteAction, VoteCast, VotePlan, VotePlanId, VoteTally},
+    certificate::{
+        TallyProof, VoteAction, VoteCast, VoteCastBatch, VotePlan, VotePlanId, VoteTally,
+    },
     date::BlockDate,
     ledger::governance::Governance,
     stake::StakeControl,
@@ -9,6 +13,7 @@ use crate::{
 };
 use imhamt::{Hamt, InsertError, UpdateError};
 use std::collections::{hash_map::DefaultHasher, HashSet};
+use std::sync::Arc;
 use thiserror::Error;
 
 #[derive(Clone, PartialEq, Eq)]
@@ -54,6 +59,39 @@ impl VotePlanLedger {
         Self { plans: Hamt::new() }
     }
 
+    /// record the stake of the voters of the plans whose voting window ended
+    /// by the given date, the votes being weighted with it when the plans
+    /// are tallied
+    ///
+    /// `stake` is only called if the stake of one of the plans needs to be
+    /// recorded.
+    #[must_use = "This function does not modify the object, the result contains the resulted new version of the vote plan ledger"]
+    pub fn snapshot_voting_stake<F>(&self, date: BlockDate, stake: F) -> Self
+    where
+        F: FnOnce() -> StakeControl,
+    {
+        let ended = self
+            .plans
+            .iter()
+            .filter(|(_, manager)| manager.needs_voting_stake(date))
+            .map(|(id, manager)| (id.clone(), manager.clone()))
+            .collect::<Vec<_>>();
+        if ended.is_empty() {
+            return self.clone();
+        }
+
+        let stake = Arc::new(stake());
+        let mut plans = self.plans.clone();
+        for (id, manager) in ended {
+            if let Ok((updated, _)) =
+                plans.replace(&id, manager.with_voting_stake(Arc::clone(&stake)))
+            {
+                plans = updated;
+            }
+        }
+        Self { plans }
+    }
+
     /// attempt to apply the vote to the appropriate Vote Proposal
     ///
     /// # errors
@@ -82,6 +120,54 @@ impl VotePlanLedger {
         }
     }
 
+    /// apply the ballots of a batch to their Vote Plan
+    ///
+    /// the proofs of the private ballots are verified once for the whole
+    /// batch, see `VotePlanManager::vote_batch`
+    ///
+    /// fails for the same reasons as `apply_vote`
+    ///
+    pub fn apply_vote_batch(
+        &self,
+        block_date: BlockDate,
+        identifier: UnspecifiedAccountIdentifier,
+        batch: &VoteCastBatch,
+    ) -> Result<Self, VotePlanLedgerError> {
+        let id = batch.vote_plan().clone();
+        let ballots: Vec<VoteCast> = batch.vote_casts().collect();
+
+        let r = self.plans.update(&id, move |v| {
+            v.vote_batch(block_date, identifier, &ballots).map(Some)
+        });
+
+        match r {
+            Err(reason) => Err(VotePlanLedgerError::VoteError { reason, id }),
+            Ok(plans) => Ok(Self { plans }),
+        }
+    }
+
+    /// check the vote could be applied to the appropriate Vote Proposal,
+    /// the `VotePlanLedger` is not modified
+    ///
+    /// fails for the same reasons as `apply_vote`
+    ///
+    pub fn validate_vote(
+        &self,
+        block_date: BlockDate,
+        vote: &VoteCast,
+    ) -> Result<(), VotePlanLedgerError> {
+        let id = vote.vote_plan().clone();
+
+        let reason = match self.plans.lookup(&id) {
+            None => UpdateError::KeyNotFound,
+            Some(v) => match v.validate_vote(block_date, vote) {
+                Ok(()) => return Ok(()),
+                Err(e) => UpdateError::ValueCallbackError(e),
+            },
+        };
+        Err(VotePlanLedgerError::VoteError { reason, id })
+    }
+
     /// add the vote plan in a new `VotePlanLedger`
     ///
     /// the given `VotePlanLedger` is not modified and instead a new `VotePlanLedger` is
@@ -119,6 +205,16 @@ impl VotePlanLedger {
             }
         }
 
+        let proposal_missing_committee = vote_plan.proposals().iter().any(|proposal| {
+            vote_plan.proposal_payload_type(proposal) == PayloadType::Private
+                && vote_plan
+                    .proposal_committee_public_keys(proposal)
+                    .is_empty()
+        });
+        if proposal_missing_committee {
+            return Err(VotePlanLedgerError::VotePlanMissingCommitteeMemberKey);
+        }
+
         let id = vote_plan.to_id();
         let manager = VotePlanManager::new(vote_plan, committee);
 
@@ -130,6 +226,10 @@ impl VotePlanLedger {
 
     /// apply the committee result for the associated vote plan
     ///
+    /// `f` applies the action of every proposal that passed and tells if it
+    /// could be applied, the decision of the proposal recording when it could
+    /// not.
+    ///
     /// # Errors
     ///
     /// This function may fail:
@@ -147,7 +247,7 @@ impl VotePlanLedger {
         f: &mut F,
     ) -> Result<Self, VotePlanLedgerError>
     where
-        F: FnMut(&VoteAction),
+        F: FnMut(&VoteAction) -> bool,
     {
         let id = tally.id().clone();
 
@@ -161,7 +261,8 @@ impl VotePlanLedger {
                 .map(Some),
             TallyProof::Private { .. } => {
                 let shares = tally.decrypt_shares().unwrap();
-                v.private_tally_finish(&shares, governance, f).map(Some)
+                v.private_tally_finish(stake, &shares, governance, f)
+                    .map(Some)
             }
         });
 
@@ -173,6 +274,10 @@ impl VotePlanLedger {
 
     /// apply the committee result for the associated vote plan
     ///
+    /// `f` applies the action of every proposal that passed and tells if it
+    /// could be applied, the decision of the proposal recording when it could
+    /// not.
+    ///
     /// # Errors
     ///
     /// This function may fail:
@@ -198,6 +303,35 @@ impl VotePlanLedger {
             Ok(plans) => Ok(Self { plans }),
         }
     }
+
+    /// hand the committee of the associated vote plan over to new members
+    ///
+    /// # Errors
+    ///
+    /// This function may fail:
+    ///
+    /// * if the vote plan has elapsed
+    /// * if the proof is not signed by one of the committee
+    /// * if the new members do not share the election key of the plan
+    ///
+    pub fn apply_committee_handover(
+        &self,
+        block_date: BlockDate,
+        handover: &CommitteeHandover,
+        sig: CommitteeHandoverProof,
+    ) -> Result<Self, VotePlanLedgerError> {
+        let id = handover.id().clone();
+
+        let r = self.plans.update(&id, move |v| {
+            v.committee_handover(block_date, sig.id, handover.committee_public_keys())
+                .map(Some)
+        });
+
+        match r {
+            Err(reason) => Err(VotePlanLedgerError::VoteError { reason, id }),
+            Ok(plans) => Ok(Self { plans }),
+        }
+    }
 }
 
 impl Default for VotePlanLedger {
@@ -1,14 +1,19 @@
 use crate::vote::{Choice, Payload, TallyError};
 use crate::{
-    certificate::{Proposal, TallyDecryptShares, VoteAction, VoteCast, VotePlan, VotePlanId},
+    certificate::{
+        Proposal, TallyDecryptShares, VoteAction, VoteCast, VotePlan, VotePlanId, VotePlanVersion,
+    },
     date::BlockDate,
     ledger::governance::{Governance, GovernanceAcceptanceCriteria},
     rewards::Ratio,
     stake::{Stake, StakeControl},
     transaction::UnspecifiedAccountIdentifier,
-    vote::{self, CommitteeId, Options, Tally, TallyResult, VotePlanStatus, VoteProposalStatus},
+    vote::{
+        self, CommitteeId, Decision, Options, Participation, PrivateTallyState, Tally, TallyResult,
+        TieBreak, VotePlanStatus, VoteProposalStatus,
+    },
 };
-use chain_vote::EncryptedTally;
+use chain_vote::{EncryptedTally, MemberPublicKey};
 use imhamt::Hamt;
 use std::{
     collections::{hash_map::DefaultHasher, HashSet},
@@ -26,6 +31,12 @@ pub struct VotePlanManager {
     id: VotePlanId,
     plan: Arc<VotePlan>,
     committee: Arc<HashSet<CommitteeId>>,
+    /// member keys of the committee of the plan, replaced by the ones of the
+    /// new members when the committee is handed over
+    committee_public_keys: Arc<Vec<MemberPublicKey>>,
+    /// stake of the voters when the voting window ended, which the votes
+    /// are weighted with when the plan is tallied
+    voting_stake: Option<Arc<StakeControl>>,
 
     proposal_managers: ProposalManagers,
 }
@@ -39,6 +50,9 @@ struct ProposalManager {
     options: Options,
     tally: Option<Tally>,
     action: VoteAction,
+    quorum: Option<Ratio>,
+    approval_threshold: Option<Ratio>,
+    tie_break: TieBreak,
 }
 
 #[derive(Debug, Error, Clone, PartialEq, Eq)]
@@ -74,12 +88,36 @@ pub enum VoteError {
     #[error("Unexpected TallyProof's public ID, expected one of the committee")]
     InvalidTallyCommittee,
 
+    #[error(
+        "It is no longer possible to hand the committee over, the committee time ended at {end}."
+    )]
+    NotHandoverTime { end: BlockDate },
+
+    #[error("Unexpected CommitteeHandoverProof's public ID, expected one of the committee")]
+    InvalidHandoverCommittee,
+
+    #[error("The new committee members do not share the election key of the vote plan")]
+    InvalidCommitteeHandover,
+
     #[error("Cannot tally votes")]
     CannotTallyVotes {
         #[from]
         source: vote::TallyError,
     },
 
+    #[error(
+        "Choice {} is out of range, the proposal only has {} options",
+        .choice.as_byte(),
+        .options.choice_range().end
+    )]
+    ChoiceOutOfRange { choice: Choice, options: Options },
+
+    #[error(
+        "Encrypted vote of {size} options does not match the proposal's {} options",
+        .options.choice_range().end
+    )]
+    InvalidEncryptedVoteSize { size: usize, options: Options },
+
     #[error("Invalid private vote verification")]
     VoteVerificationError,
 
@@ -101,7 +139,40 @@ impl ProposalManager {
             options: proposal.options().clone(),
             tally: None,
             action: proposal.action().clone(),
+            quorum: proposal.quorum(),
+            approval_threshold: proposal.approval_threshold(),
+            tie_break: proposal.tie_break(),
+        }
+    }
+
+    /// number of entries of the encrypted votes (and of the encrypted tally)
+    /// of this proposal, one per option
+    fn encrypted_vote_size(&self) -> usize {
+        self.options.choice_range().end as usize
+    }
+
+    /// check the payload of a vote cast fits the proposal's options
+    fn validate_vote(&self, payload: &vote::Payload) -> Result<(), VoteError> {
+        match payload {
+            vote::Payload::Public { choice } => {
+                if !choice.is_abstain() && !self.options.validate(*choice) {
+                    return Err(VoteError::ChoiceOutOfRange {
+                        choice: *choice,
+                        options: self.options.clone(),
+                    });
+                }
+            }
+            vote::Payload::Private { encrypted_vote, .. } => {
+                let size = encrypted_vote.as_inner().len();
+                if size != self.encrypted_vote_size() {
+                    return Err(VoteError::InvalidEncryptedVoteSize {
+                        size,
+                        options: self.options.clone(),
+                    });
+                }
+            }
         }
+        Ok(())
     }
 
     /// apply the given vote cast to the proposal
@@ -109,12 +180,19 @@ impl ProposalManager {
     /// if there is already a vote present for this proposal it will
     /// simply replace the previously set one
     ///
+    /// public choices need to be within the proposal's options. Private
+    /// votes need to be encrypted over as many entries as the proposal's
+    /// encrypted tally: the proof of correct vote then attests the
+    /// encrypted choice is one of them.
+    ///
     #[must_use = "Add the vote in a new ProposalManager, does not modify self"]
     pub fn vote(
         &self,
         identifier: UnspecifiedAccountIdentifier,
         cast: VoteCast,
     ) -> Result<Self, VoteError> {
+        self.validate_vote(cast.payload())?;
+
         let payload = cast.into_payload();
 
         // we don't mind if we are replacing a vote
@@ -126,6 +204,9 @@ impl ProposalManager {
             tally: self.tally.clone(),
             options: self.options.clone(),
             action: self.action.clone(),
+            quorum: self.quorum,
+            approval_threshold: self.approval_threshold,
+            tie_break: self.tie_break,
         })
     }
 
@@ -137,9 +218,10 @@ impl ProposalManager {
         f: &mut F,
     ) -> Result<Self, VoteError>
     where
-        F: FnMut(&VoteAction),
+        F: FnMut(&VoteAction) -> bool,
     {
         let mut results = TallyResult::new(self.options.clone());
+        let mut participation = Participation::new(stake.assigned());
 
         for (id, payload) in self.votes_by_voters.iter() {
             if let Some(account_id) = id.to_single_account() {
@@ -147,6 +229,7 @@ impl ProposalManager {
                     match payload {
                         vote::Payload::Public { choice } => {
                             results.add_vote(*choice, stake)?;
+                            participation.add_voter(stake);
                         }
                         vote::Payload::Private { .. } => {
                             return Err(VoteError::InvalidPayloadType {
@@ -159,22 +242,26 @@ impl ProposalManager {
             }
         }
 
-        if self.check(stake.assigned(), governance, &results) {
-            f(&self.action)
-        }
+        let decision = match self.decide(stake.assigned(), governance, &results) {
+            Decision::Passed if !f(&self.action) => Decision::ActionFailed,
+            decision => decision,
+        };
 
         Ok(Self {
             votes_by_voters: self.votes_by_voters.clone(),
             options: self.options.clone(),
-            tally: Some(Tally::new_public(results)),
+            tally: Some(Tally::new_public(results, participation, decision)),
             action: self.action.clone(),
+            quorum: self.quorum,
+            approval_threshold: self.approval_threshold,
+            tie_break: self.tie_break,
         })
     }
 
     #[must_use = "Compute the PrivateTally in a new ProposalManager, does not modify self"]
     pub fn private_tally(&self, stake: &StakeControl) -> Result<Self, VoteError> {
-        let mut tally =
-            EncryptedTally::new(self.options.choice_range().clone().max().unwrap() as usize);
+        let mut tally = EncryptedTally::new(self.encrypted_vote_size());
+        let mut participation = Participation::new(stake.assigned());
 
         for (id, payload) in self.votes_by_voters.iter() {
             if let Some(account_id) = id.to_single_account() {
@@ -191,6 +278,7 @@ impl ProposalManager {
                             proof: _,
                         } => {
                             tally.add(encrypted_vote.as_inner(), stake.0);
+                            participation.add_voter(stake);
                         }
                     }
                 }
@@ -200,140 +288,162 @@ impl ProposalManager {
         Ok(Self {
             votes_by_voters: self.votes_by_voters.clone(),
             options: self.options.clone(),
-            tally: Some(Tally::new_private(tally, stake.assigned())),
+            tally: Some(Tally::new_private(tally, participation)),
             action: self.action.clone(),
+            quorum: self.quorum,
+            approval_threshold: self.approval_threshold,
+            tie_break: self.tie_break,
         })
     }
 
     pub fn finalize_private_tally<F>(
         &self,
-        shares: &[chain_vote::TallyDecryptShare],
+        version: VotePlanVersion,
+        shares: &[(u8, chain_vote::TallyDecryptShare)],
         governance: &Governance,
         f: &mut F,
     ) -> Result<Self, TallyError>
     where
-        F: FnMut(&VoteAction),
+        F: FnMut(&VoteAction) -> bool,
     {
-        use std::convert::TryInto;
         let tally = self.tally.as_ref().ok_or(TallyError::NoEncryptedTally)?;
-        let (encrypted_tally, total_stake) = tally.private_encrypted()?;
-        let state = encrypted_tally.state();
-        // total voting power + 1
-        let max_votes = total_stake.0 + 1;
-        let table_size = (max_votes / 3) as usize;
-        let private_result = chain_vote::result(max_votes, table_size, &state, shares);
-        let mut result = TallyResult::new(self.options.clone());
-        for (choice, weight) in private_result
-            .votes
-            .iter()
-            .map(|maybe_vote| maybe_vote.unwrap_or_default())
-            .enumerate()
-        {
-            result.add_vote(
-                Choice::new(choice.try_into().map_err(|_| {
-                    TallyError::InvalidPrivateChoiceSize {
-                        choice: choice as u64,
-                    }
-                })?),
-                weight,
-            )?;
-        }
-
-        dbg!(&result);
-
-        if self.check(*total_stake, governance, &result) {
-            f(&self.action);
-        }
+        let (encrypted_tally, participation) = tally.private_encrypted()?;
+        let total_stake = participation.eligible();
+        let result = PrivateTallyState::decrypt(
+            version,
+            &self.options,
+            encrypted_tally,
+            total_stake,
+            shares,
+        )?;
+
+        let decision = match self.decide(total_stake, governance, &result) {
+            Decision::Passed if !f(&self.action) => Decision::ActionFailed,
+            decision => decision,
+        };
 
-        let tally = tally.clone().private_set_result(result)?;
+        let tally = tally
+            .clone()
+            .private_set_result(shares.into(), result, decision)?;
 
         Ok(Self {
             votes_by_voters: self.votes_by_voters.clone(),
             options: self.options.clone(),
             tally: Some(tally),
             action: self.action.clone(),
+            quorum: self.quorum,
+            approval_threshold: self.approval_threshold,
+            tie_break: self.tie_break,
         })
     }
 
-    fn check(&self, total: Stake, governance: &Governance, results: &TallyResult) -> bool {
-        match &self.action {
-            VoteAction::OffChain => false,
-            VoteAction::Treasury { action } => {
-                let t = action.to_type();
-                let acceptance = governance.treasury.acceptance_criteria_for(t);
-
-                self.check_governance_criteria(total, acceptance, results)
-            }
-            VoteAction::Parameters { action } => {
-                let t = action.to_type();
-                let acceptance = governance.parameters.acceptance_criteria_for(t);
-
-                self.check_governance_criteria(total, acceptance, results)
-            }
-        }
-    }
+    /// decide whether the proposal passed given the results of its tally
+    ///
+    /// both the governance acceptance criteria of the action and the quorum
+    /// and approval threshold of the proposal itself need to be met. Off chain
+    /// proposals are only bound by the latter. A tie between the favorable and
+    /// the rejecting stake is decided with the tie break of the proposal.
+    fn decide(&self, total: Stake, governance: &Governance, results: &TallyResult) -> Decision {
+        let acceptance = match &self.action {
+            VoteAction::OffChain => None,
+            VoteAction::Treasury { action } => Some(
+                governance
+                    .treasury
+                    .acceptance_criteria_for(action.to_type()),
+            ),
+            VoteAction::Parameters { action } => Some(
+                governance
+                    .parameters
+                    .acceptance_criteria_for(action.to_type()),
+            ),
+        };
+        let default_acceptance = GovernanceAcceptanceCriteria::default();
+        let acceptance_choices = acceptance.unwrap_or(&default_acceptance);
+
+        let quorums = [
+            acceptance.and_then(|a| a.minimum_stake_participation),
+            self.quorum,
+        ];
+        let approvals = [
+            acceptance.and_then(|a| a.minimum_approval),
+            self.approval_threshold,
+        ];
 
-    fn check_governance_criteria(
-        &self,
-        total: Stake,
-        acceptance: &GovernanceAcceptanceCriteria,
-        results: &TallyResult,
-    ) -> bool {
         let total = if let Some(t) = NonZeroU64::new(total.into()) {
             t
         } else {
-            return false;
+            return Decision::NoQuorum;
         };
         let participation = if let Some(p) = NonZeroU64::new(results.participation().into()) {
             p
         } else {
-            return false;
+            return Decision::NoQuorum;
         };
+
+        let ratio_participation = Ratio {
+            numerator: participation.into(),
+            denominator: total,
+        };
+
+        if quorums
+            .iter()
+            .flatten()
+            .any(|criteria| ratio_participation <= *criteria)
+        {
+            return Decision::NoQuorum;
+        }
+
         let favorable: u64 = if let Some(weight) = results
             .results()
-            .get(acceptance.favorable.as_byte() as usize)
+            .get(acceptance_choices.favorable.as_byte() as usize)
         {
             (*weight).into()
         } else {
-            return false;
+            return Decision::Rejected;
         };
         let non_blanks = if let Some(weight) = results
             .results()
-            .get(acceptance.rejection.as_byte() as usize)
+            .get(acceptance_choices.rejection.as_byte() as usize)
         {
             let v: u64 = (*weight).into();
             if let Some(v) = NonZeroU64::new(v + favorable) {
                 v
             } else {
-                return false;
+                return Decision::Rejected;
             }
         } else {
-            return false;
+            return Decision::Rejected;
         };
 
+        // as much stake is for as against the proposal: the tie break
+        // decides, the approval thresholds cannot be met anyway
+        if favorable == non_blanks.get() - favorable {
+            return match self.tie_break {
+                TieBreak::NoWinner => Decision::Tie,
+                TieBreak::LowestOption => {
+                    if acceptance_choices.favorable < acceptance_choices.rejection {
+                        Decision::Passed
+                    } else {
+                        Decision::Rejected
+                    }
+                }
+            };
+        }
+
         let ratio_favorable = Ratio {
             numerator: favorable,
             denominator: non_blanks,
         };
 
-        let ratio_participation = Ratio {
-            numerator: participation.into(),
-            denominator: total,
-        };
-
-        if let Some(criteria) = acceptance.minimum_stake_participation {
-            if ratio_participation <= criteria {
-                return false;
-            }
-        }
-
-        if let Some(criteria) = acceptance.minimum_approval {
-            if ratio_favorable <= criteria {
-                return false;
-            }
+        if approvals
+            .iter()
+            .flatten()
+            .any(|criteria| ratio_favorable <= *criteria)
+        {
+            return Decision::Rejected;
         }
 
-        true
+        Decision::Passed
     }
 }
 
@@ -348,6 +458,18 @@ impl ProposalManagers {
         Self(proposal_managers)
     }
 
+    /// check the vote could be applied to one of the proposals, without
+    /// applying it
+    fn validate_vote(&self, cast: &VoteCast) -> Result<(), VoteError> {
+        match self.0.get(cast.proposal_index() as usize) {
+            Some(manager) => manager.validate_vote(cast.payload()),
+            None => Err(VoteError::InvalidVoteProposal {
+                num_proposals: self.0.len(),
+                vote: cast.clone(),
+            }),
+        }
+    }
+
     /// attempt to apply the vote to one of the proposals
     ///
     /// if the proposal is not found this function will return an error.
@@ -389,7 +511,7 @@ impl ProposalManagers {
         f: &mut F,
     ) -> Result<Self, VoteError>
     where
-        F: FnMut(&VoteAction),
+        F: FnMut(&VoteAction) -> bool,
     {
         let mut proposals = Vec::with_capacity(self.0.len());
         for proposal in self.0.iter() {
@@ -399,27 +521,87 @@ impl ProposalManagers {
         Ok(Self(proposals))
     }
 
-    pub fn private_tally_start(&self, stake: &StakeControl) -> Result<Self, VoteError> {
+    /// start the private tally of the private proposals of the plan, the
+    /// public proposals are left untouched
+    pub fn private_tally_start(
+        &self,
+        plan: &VotePlan,
+        stake: &StakeControl,
+    ) -> Result<Self, VoteError> {
         let mut proposals = Vec::with_capacity(self.0.len());
-        for proposal in self.0.iter() {
-            proposals.push(proposal.private_tally(stake)?);
+        for (proposal_manager, proposal) in self.0.iter().zip(plan.proposals().iter()) {
+            match plan.proposal_payload_type(proposal) {
+                vote::PayloadType::Public => proposals.push(proposal_manager.clone()),
+                vote::PayloadType::Private => {
+                    proposals.push(proposal_manager.private_tally(stake)?)
+                }
+            }
         }
 
         Ok(Self(proposals))
     }
 
+    /// decrypt the tally of the private proposals of the plan with the
+    /// shares, given in the order of the private proposals. The public
+    /// proposals of the plan are tallied with the current stake.
+    ///
+    /// The shares of the proposals without a committee of their own are
+    /// verified against `committee_public_keys`, the current members of the
+    /// committee of the plan.
     pub fn private_tally_finalize<F>(
         &self,
+        plan: &VotePlan,
+        committee_public_keys: &[MemberPublicKey],
+        stake: &StakeControl,
         shares: &TallyDecryptShares,
         governance: &Governance,
         f: &mut F,
     ) -> Result<Self, VoteError>
     where
-        F: FnMut(&VoteAction),
+        F: FnMut(&VoteAction) -> bool,
     {
+        let encrypted_tallies = self
+            .0
+            .iter()
+            .zip(plan.proposals().iter())
+            .filter(|(_, proposal)| {
+                plan.proposal_payload_type(proposal) == vote::PayloadType::Private
+            })
+            .map(|(proposal_manager, proposal)| {
+                let tally = proposal_manager
+                    .tally
+                    .as_ref()
+                    .ok_or(TallyError::NoEncryptedTally)?;
+                let committee_public_keys = if proposal.committee_public_keys().is_empty() {
+                    committee_public_keys
+                } else {
+                    proposal.committee_public_keys()
+                };
+                tally
+                    .private_encrypted()
+                    .map(|(encrypted_tally, _)| (committee_public_keys, encrypted_tally))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        shares.verify(plan.version(), &encrypted_tallies)?;
+
+        let mut shares = shares.iter();
         let mut proposals = Vec::with_capacity(self.0.len());
-        for (proposal_manager, shares) in self.0.iter().zip(shares.iter()) {
-            proposals.push(proposal_manager.finalize_private_tally(shares, governance, f)?);
+        for (proposal_manager, proposal) in self.0.iter().zip(plan.proposals().iter()) {
+            match plan.proposal_payload_type(proposal) {
+                vote::PayloadType::Public => {
+                    proposals.push(proposal_manager.public_tally(stake, governance, f)?)
+                }
+                vote::PayloadType::Private => {
+                    // the shares were verified to cover every private proposal
+                    let shares = shares.next().unwrap();
+                    proposals.push(proposal_manager.finalize_private_tally(
+                        plan.version(),
+                        shares,
+                        governance,
+                        f,
+                    )?)
+                }
+            }
         }
         Ok(Self(proposals))
     }
@@ -429,12 +611,31 @@ impl VotePlanManager {
     pub fn new(plan: VotePlan, committee: HashSet<CommitteeId>) -> Self {
         let id = plan.to_id();
         let proposal_managers = ProposalManagers::new(&plan);
+        let committee_public_keys = plan.committee_public_keys().to_vec();
 
         Self {
             id,
             plan: Arc::new(plan),
             proposal_managers,
             committee: Arc::new(committee),
+            committee_public_keys: Arc::new(committee_public_keys),
+            voting_stake: None,
+        }
+    }
+
+    /// restore the manager of a vote plan from a snapshot of the ledger,
+    /// along with the keys of the committee the plan was handed over to and
+    /// the stake recorded at the end of the voting window
+    pub(crate) fn restore(
+        plan: VotePlan,
+        committee_public_keys: Vec<MemberPublicKey>,
+        voting_stake: Option<StakeControl>,
+    ) -> Self {
+        // TODO: the committee is not recorded in the snapshots
+        Self {
+            committee_public_keys: Arc::new(committee_public_keys),
+            voting_stake: voting_stake.map(Arc::new),
+            ..Self::new(plan, HashSet::new())
         }
     }
 
@@ -446,6 +647,18 @@ impl VotePlanManager {
         &self.plan
     }
 
+    /// member keys of the committee of the plan, the ones of the committee
+    /// the plan was handed over to if any
+    pub fn committee_public_keys(&self) -> &[MemberPublicKey] {
+        &self.committee_public_keys
+    }
+
+    /// stake of the voters recorded at the end of the voting window, if the
+    /// window ended already
+    pub fn recorded_voting_stake(&self) -> Option<&StakeControl> {
+        self.voting_stake.as_deref()
+    }
+
     pub fn statuses(&self) -> VotePlanStatus {
         let proposals = self
             .plan()
@@ -457,15 +670,18 @@ impl VotePlanManager {
                 index: index as u8,
                 proposal_id: proposal.external_id().clone(),
                 options: proposal.options().clone(),
+                payload: self.plan().proposal_payload_type(proposal),
+                committee_public_keys: self.proposal_committee_public_keys(proposal).to_vec(),
                 tally: manager.tally.clone(),
                 votes: manager.votes_by_voters.clone(),
             })
             .collect();
 
-        let committee_public_keys = self.plan().committee_public_keys().to_vec();
+        let committee_public_keys = self.committee_public_keys.to_vec();
 
         VotePlanStatus {
             id: self.id.clone(),
+            version: self.plan().version(),
             payload: self.plan().payload_type(),
             vote_start: self.plan().vote_start(),
             vote_end: self.plan().vote_end(),
@@ -487,6 +703,20 @@ impl VotePlanManager {
         &self.committee
     }
 
+    /// member keys of the committee tallying the proposal, the ones of the
+    /// committee the plan was handed over to if the proposal does not have
+    /// a committee of its own
+    pub fn proposal_committee_public_keys<'a>(
+        &'a self,
+        proposal: &'a Proposal,
+    ) -> &'a [MemberPublicKey] {
+        if proposal.committee_public_keys().is_empty() {
+            &self.committee_public_keys
+        } else {
+            proposal.committee_public_keys()
+        }
+    }
+
     /// return true if the vote plan has elapsed i.e. the vote is
     /// no longer interesting to track in the ledger and it can be
     /// GCed.
@@ -498,6 +728,31 @@ impl VotePlanManager {
         self.committee_set().contains(id)
     }
 
+    /// tells if the voting window ended by the given date while the stake
+    /// of the voters has not been recorded yet
+    pub fn needs_voting_stake(&self, date: BlockDate) -> bool {
+        self.plan().vote_finished(date) && self.voting_stake.is_none()
+    }
+
+    /// record the stake of the voters at the end of the voting window, the
+    /// votes being weighted with it whenever the plan is tallied
+    pub fn with_voting_stake(&self, stake: Arc<StakeControl>) -> Self {
+        Self {
+            proposal_managers: self.proposal_managers.clone(),
+            plan: Arc::clone(&self.plan),
+            id: self.id.clone(),
+            committee: Arc::clone(&self.committee),
+            committee_public_keys: Arc::clone(&self.committee_public_keys),
+            voting_stake: Some(stake),
+        }
+    }
+
+    /// stake the votes are weighted with: the one recorded at the end of the
+    /// voting window, or the given stake if it was not recorded yet
+    fn voting_stake<'a>(&'a self, stake: &'a StakeControl) -> &'a StakeControl {
+        self.voting_stake.as_deref().unwrap_or(stake)
+    }
+
     /// attempt to apply the vote to one of the proposals
     ///
     /// If the given identifier already had a vote, the previous vote will
@@ -517,50 +772,174 @@ impl VotePlanManager {
         identifier: UnspecifiedAccountIdentifier,
         cast: VoteCast,
     ) -> Result<Self, VoteError> {
+        self.validate_vote(block_date, &cast)?;
+
+        let proposal_managers = self.proposal_managers.vote(identifier, cast)?;
+
+        Ok(Self {
+            proposal_managers,
+            plan: Arc::clone(&self.plan),
+            id: self.id.clone(),
+            committee: Arc::clone(&self.committee),
+            committee_public_keys: Arc::clone(&self.committee_public_keys),
+            voting_stake: self.voting_stake.clone(),
+        })
+    }
+
+    /// apply the ballots of a batch, in order, as successive calls to `vote`
+    /// would
+    ///
+    /// The zero knowledge proofs of the private ballots are verified once for
+    /// the whole batch with `verify_ballots_batch` and are not verified again
+    /// when the ballots are applied.
+    ///
+    /// # errors
+    ///
+    /// * fails for the same reasons as `vote` and `verify_ballots_batch`
+    ///
+    pub fn vote_batch(
+        &self,
+        block_date: BlockDate,
+        identifier: UnspecifiedAccountIdentifier,
+        ballots: &[VoteCast],
+    ) -> Result<Self, VoteError> {
+        for cast in ballots {
+            self.check_ballot(block_date, cast)?;
+        }
+        self.verify_ballots_batch(ballots)?;
+
+        let mut proposal_managers = self.proposal_managers.clone();
+        for cast in ballots {
+            proposal_managers = proposal_managers.vote(identifier.clone(), cast.clone())?;
+        }
+
+        Ok(Self {
+            proposal_managers,
+            plan: Arc::clone(&self.plan),
+            id: self.id.clone(),
+            committee: Arc::clone(&self.committee),
+            committee_public_keys: Arc::clone(&self.committee_public_keys),
+            voting_stake: self.voting_stake.clone(),
+        })
+    }
+
+    /// run all the checks of `vote` without applying the vote
+    pub fn validate_vote(&self, block_date: BlockDate, cast: &VoteCast) -> Result<(), VoteError> {
+        let proposal = self.plan().proposals().get(cast.proposal_index() as usize);
+
+        self.check_ballot(block_date, cast)?;
+
+        // verify vote if private
+        if let (
+            Some(proposal),
+            Payload::Private {
+                encrypted_vote,
+                proof,
+            },
+        ) = (proposal, cast.payload())
+        {
+            let pk = self.plan().proposal_election_key(proposal);
+            if !chain_vote::verify_vote(&pk, encrypted_vote.as_inner(), proof.as_inner()) {
+                return Err(VoteError::VoteVerificationError);
+            }
+        }
+
+        self.proposal_managers.validate_vote(cast)
+    }
+
+    /// check the ballot is cast for this vote plan, during the voting period
+    /// and with the payload type expected by its proposal, leaving the proof
+    /// of private ballots aside
+    fn check_ballot(&self, block_date: BlockDate, cast: &VoteCast) -> Result<(), VoteError> {
+        let proposal = self.plan().proposals().get(cast.proposal_index() as usize);
+
         if cast.vote_plan() != self.id() {
             Err(VoteError::InvalidVotePlan {
                 expected: self.id().clone(),
-                vote: cast,
+                vote: cast.clone(),
             })
         } else if !self.can_vote(block_date) {
             Err(VoteError::NotVoteTime {
                 start: self.plan().vote_start(),
                 end: self.plan().vote_end(),
-                vote: cast,
+                vote: cast.clone(),
             })
-        } else if self.plan().payload_type() != cast.payload().payload_type() {
+        } else if let Some(expected) = proposal
+            .map(|proposal| self.plan().proposal_payload_type(proposal))
+            .filter(|expected| *expected != cast.payload().payload_type())
+        {
             Err(VoteError::InvalidPayloadType {
-                expected: self.plan().payload_type(),
+                expected,
                 received: cast.payload().payload_type(),
             })
-        // verify vote if private
-        } else if let Err(e) = match &cast.payload() {
-            Payload::Public { .. } => Ok(()),
-            Payload::Private {
+        } else {
+            Ok(())
+        }
+    }
+
+    /// verify the payloads of many ballots cast for this vote plan at once
+    ///
+    /// The zero knowledge proofs of private ballots are verified together,
+    /// which is faster than verifying them one by one as done in `vote`.
+    /// Public ballots carry no proof and are only checked for their vote plan
+    /// and payload type.
+    ///
+    /// # errors
+    ///
+    /// * if one of the ballots was not cast for this vote plan
+    /// * if the payload type of one of the ballots is not the expected one
+    /// * if the proof of any of the private ballots is invalid, the whole
+    ///   batch is rejected without telling which ballot is invalid
+    ///
+    pub fn verify_ballots_batch(&self, ballots: &[VoteCast]) -> Result<(), VoteError> {
+        // the private votes are verified by batches of the same committee
+        let mut private_votes: Vec<(&[MemberPublicKey], Vec<_>)> = Vec::new();
+        for cast in ballots {
+            if cast.vote_plan() != self.id() {
+                return Err(VoteError::InvalidVotePlan {
+                    expected: self.id().clone(),
+                    vote: cast.clone(),
+                });
+            }
+            let proposal = self
+                .plan()
+                .proposals()
+                .get(cast.proposal_index() as usize)
+                .ok_or_else(|| VoteError::InvalidVoteProposal {
+                    num_proposals: self.plan().proposals().len(),
+                    vote: cast.clone(),
+                })?;
+            let expected = self.plan().proposal_payload_type(proposal);
+            if expected != cast.payload().payload_type() {
+                return Err(VoteError::InvalidPayloadType {
+                    expected,
+                    received: cast.payload().payload_type(),
+                });
+            }
+            if let Payload::Private {
                 encrypted_vote,
                 proof,
-            } => {
-                let pk = chain_vote::EncryptingVoteKey::from_participants(
-                    self.plan.committee_public_keys(),
-                );
-                if !chain_vote::verify_vote(&pk, encrypted_vote.as_inner(), proof.as_inner()) {
-                    Err(VoteError::VoteVerificationError)
-                } else {
-                    Ok(())
+            } = cast.payload()
+            {
+                let committee_public_keys = self.plan().proposal_committee_public_keys(proposal);
+                let vote = (encrypted_vote.as_inner(), proof.as_inner());
+                match private_votes
+                    .iter_mut()
+                    .find(|(keys, _)| *keys == committee_public_keys)
+                {
+                    Some((_, votes)) => votes.push(vote),
+                    None => private_votes.push((committee_public_keys, vec![vote])),
                 }
             }
-        } {
-            Err(e)
-        } else {
-            let proposal_managers = self.proposal_managers.vote(identifier, cast)?;
+        }
 
-            Ok(Self {
-                proposal_managers,
-                plan: Arc::clone(&self.plan),
-                id: self.id.clone(),
-                committee: Arc::clone(&self.committee),
-            })
+        for (committee_public_keys, votes) in private_votes {
+            let pk = self.plan().version().election_key(committee_public_keys);
+            if !chain_vote::verify_votes_batch(&pk, &votes) {
+                return Err(VoteError::VoteVerificationError);
+            }
         }
+        Ok(())
     }
 
     pub fn public_tally<F>(
@@ -572,7 +951,7 @@ impl VotePlanManager {
         f: &mut F,
     ) -> Result<Self, VoteError>
     where
-        F: FnMut(&VoteAction),
+        F: FnMut(&VoteAction) -> bool,
     {
         if !self.can_committee(block_date) {
             return Err(VoteError::NotCommitteeTime {
@@ -585,17 +964,23 @@ impl VotePlanManager {
             return Err(VoteError::InvalidTallyCommittee);
         }
 
-        if self.plan.payload_type() != vote::PayloadType::Public {
+        // the public proposals of a plan with private proposals are tallied
+        // along with the private ones
+        if self.plan.has_private_proposals() {
             return Err(TallyError::InvalidPrivacy.into());
         }
 
-        let proposal_managers = self.proposal_managers.public_tally(stake, governance, f)?;
+        let proposal_managers =
+            self.proposal_managers
+                .public_tally(self.voting_stake(stake), governance, f)?;
 
         Ok(Self {
             proposal_managers,
             plan: Arc::clone(&self.plan),
             id: self.id.clone(),
             committee: Arc::clone(&self.committee),
+            committee_public_keys: Arc::clone(&self.committee_public_keys),
+            voting_stake: self.voting_stake.clone(),
         })
     }
 
@@ -616,37 +1001,104 @@ impl VotePlanManager {
             return Err(VoteError::InvalidTallyCommittee);
         }
 
-        if self.plan.payload_type() != vote::PayloadType::Private {
+        if !self.plan.has_private_proposals() {
             return Err(TallyError::InvalidPrivacy.into());
         }
 
-        let proposal_managers = self.proposal_managers.private_tally_start(stake)?;
+        let proposal_managers = self
+            .proposal_managers
+            .private_tally_start(&self.plan, self.voting_stake(stake))?;
 
         Ok(Self {
             proposal_managers,
             plan: Arc::clone(&self.plan),
             id: self.id.clone(),
             committee: Arc::clone(&self.committee),
+            committee_public_keys: Arc::clone(&self.committee_public_keys),
+            voting_stake: self.voting_stake.clone(),
         })
     }
 
     pub fn private_tally_finish<F>(
         &self,
+        stake: &StakeControl,
         shares: &TallyDecryptShares,
         governance: &Governance,
         f: &mut F,
     ) -> Result<Self, VoteError>
     where
-        F: FnMut(&VoteAction),
+        F: FnMut(&VoteAction) -> bool,
     {
-        let proposal_managers = self
-            .proposal_managers
-            .private_tally_finalize(shares, governance, f)?;
+        let proposal_managers = self.proposal_managers.private_tally_finalize(
+            &self.plan,
+            &self.committee_public_keys,
+            self.voting_stake(stake),
+            shares,
+            governance,
+            f,
+        )?;
         Ok(Self {
             proposal_managers,
             plan: Arc::clone(&self.plan),
             id: self.id.clone(),
             committee: Arc::clone(&self.committee),
+            committee_public_keys: Arc::clone(&self.committee_public_keys),
+            voting_stake: self.voting_stake.clone(),
+        })
+    }
+
+    /// hand the committee of the plan over to the members with the given
+    /// public keys, who received shares of the keys of the current members
+    ///
+    /// The election key the votes were encrypted to is left unchanged by a
+    /// handover, so the votes already cast are tallied by the new members.
+    ///
+    /// # errors
+    ///
+    /// * if the vote plan has elapsed
+    /// * if the proof is not signed by one of the committee
+    /// * if the plan has no committee member keys, or the new members do
+    ///   not share the election key of the current ones
+    ///
+    pub fn committee_handover(
+        &self,
+        block_date: BlockDate,
+        sig: CommitteeId,
+        committee_public_keys: &[MemberPublicKey],
+    ) -> Result<Self, VoteError> {
+        if self.vote_plan_elapsed(block_date) {
+            return Err(VoteError::NotHandoverTime {
+                end: self.plan().committee_end(),
+            });
+        }
+
+        if !self.valid_committee(&sig) {
+            return Err(VoteError::InvalidHandoverCommittee);
+        }
+
+        if self.committee_public_keys.is_empty()
+            || committee_public_keys.is_empty()
+            || self
+                .plan()
+                .version()
+                .election_key(committee_public_keys)
+                .to_bytes()
+                != self
+                    .plan()
+                    .version()
+                    .election_key(&self.committee_public_keys)
+                    .to_bytes()
+        {
+            return Err(VoteError::InvalidCommitteeHandover);
+        }
+
+        Ok(Self {
+            proposal_managers: self.proposal_managers.clone(),
+            plan: Arc::clone(&self.plan),
+            id: self.id.clone(),
+            committee: Arc::clone(&self.committee),
+            committee_public_keys: Arc::new(committee_public_keys.to_vec()),
+            voting_stake: self.voting_stake.clone(),
         })
     }
 }
@@ -654,6 +1106,7 @@ impl VotePlanManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::account::Identifier;
     use crate::block::BlockDate;
     use crate::certificate::TallyProof;
 
@@ -683,6 +1136,60 @@ mod tests {
         assert_eq!(*actual_vote_cast_payload, vote_cast_payload);
     }
 
+    #[test]
+    pub fn proposal_manager_approval_excludes_abstentions() {
+        let blank = Choice::new(0);
+        let favorable = Choice::new(1);
+        let rejection = Choice::new(2);
+        let governance = governance_50_percent(blank, favorable, rejection);
+        let proposal_manager =
+            ProposalManager::new(&VoteTestGen::proposal_with_action(VoteAction::Treasury {
+                action: TreasuryGovernanceAction::TransferToRewards { value: Value(30) },
+            }));
+
+        let for_voter = TestGen::identifier();
+        let against_voter = TestGen::identifier();
+        let abstaining_voter = TestGen::identifier();
+        let stake = StakeControl::new()
+            .add_to(for_voter.clone(), Stake(20))
+            .add_to(against_voter.clone(), Stake(10))
+            .add_to(abstaining_voter.clone(), Stake(60));
+        let vote = |proposal_manager: &ProposalManager, voter: &Identifier, choice: Choice| {
+            let vote_cast = VoteCast::new(
+                VoteTestGen::vote_plan().to_id(),
+                0,
+                vote::Payload::public(choice),
+            );
+            proposal_manager
+                .vote(
+                    UnspecifiedAccountIdentifier::from_single_account(voter.clone()),
+                    vote_cast,
+                )
+                .unwrap()
+        };
+
+        let proposal_manager = vote(&proposal_manager, &for_voter, favorable);
+        let proposal_manager = vote(&proposal_manager, &against_voter, rejection);
+        let tally = proposal_manager
+            .public_tally(&stake, &governance, &mut |_| true)
+            .unwrap()
+            .tally
+            .unwrap();
+        assert_eq!(tally.participation().voted(), Stake(30));
+        assert_eq!(tally.decision(), Some(Decision::NoQuorum));
+
+        // the abstentions reach the quorum but are left out of the approval
+        // ratio: 20 in favor out of the 30 for or against the proposal
+        let proposal_manager = vote(&proposal_manager, &abstaining_voter, Choice::ABSTAIN);
+        let tally = proposal_manager
+            .public_tally(&stake, &governance, &mut |_| true)
+            .unwrap()
+            .tally
+            .unwrap();
+        assert_eq!(tally.participation().voted(), Stake(90));
+        assert_eq!(tally.decision(), Some(Decision::Passed));
+    }
+
     #[test]
     pub fn proposal_manager_replace_vote() {
         let vote_plan = VoteTestGen::vote_plan();
@@ -712,7 +1219,7 @@ mod tests {
     }
 
     const CENT: NonZeroU64 = unsafe { NonZeroU64::new_unchecked(100) };
-    use crate::certificate::Proposals;
+    use crate::certificate::{Proposals, PushProposal};
     use crate::ledger::governance::{ParametersGovernance, ParametersGovernanceAction};
     use crate::ledger::governance::{TreasuryGovernance, TreasuryGovernanceAction};
     use crate::value::Value;
@@ -741,6 +1248,9 @@ mod tests {
         assert_eq!(status.vote_end, vote_plan.vote_end());
         assert_eq!(status.committee_end, vote_plan.committee_end());
         assert_eq!(status.proposals.len(), 3);
+        assert!(status.proposals.iter().all(|proposal| {
+            proposal.encrypted_tally().is_none() && proposal.tally_result().is_none()
+        }));
 
         assert_eq!(vote_plan_manager.committee_set().len(), 0);
     }
@@ -813,16 +1323,25 @@ mod tests {
             TallyProof::Public { id, .. } => id,
             TallyProof::Private { id, .. } => id,
         };
-        vote_plan_manager
+        let vote_plan_manager = vote_plan_manager
             .public_tally(
                 block_date,
                 &stake_controlled,
                 &governance,
                 committee_id,
-                &mut |_| action_hit = true,
+                &mut |_| {
+                    action_hit = true;
+                    true
+                },
             )
             .unwrap();
-        assert!(action_hit)
+        assert!(action_hit);
+
+        let status = vote_plan_manager.statuses();
+        let proposal = &status.proposals[0];
+        assert!(proposal.encrypted_tally().is_none());
+        let result = proposal.tally_result().unwrap();
+        assert_eq!(result.results()[favorable.as_byte() as usize], 51.into());
     }
 
     #[test]
@@ -872,7 +1391,7 @@ mod tests {
                     &stake_controlled,
                     &governance,
                     committee_id,
-                    &mut |_| ()
+                    &mut |_| true
                 )
                 .err()
                 .unwrap()
@@ -929,7 +1448,7 @@ mod tests {
                     &stake_controlled,
                     &governance,
                     committee_id,
-                    &mut |_| ()
+                    &mut |_| true
                 )
                 .err()
                 .unwrap()
@@ -1049,6 +1568,7 @@ mod tests {
         proposal_managers
             .public_tally(&stake_controlled, &governance, &mut |_vote_action| {
                 vote_action_hit = true;
+                true
             })
             .unwrap();
     }
@@ -1062,6 +1582,7 @@ mod tests {
         proposal_manager
             .public_tally(&stake_controlled, &governance, &mut |_vote_action| {
                 vote_action_hit = true;
+                true
             })
             .unwrap();
 
@@ -1289,4 +1810,372 @@ mod tests {
             )
             .is_ok());
     }
+
+    #[test]
+    pub fn vote_manager_choice_out_of_range() {
+        let vote_plan = VotePlan::new(
+            BlockDate::from_epoch_slot_id(1, 0),
+            BlockDate::from_epoch_slot_id(2, 0),
+            BlockDate::from_epoch_slot_id(3, 0),
+            VoteTestGen::proposals(3),
+            vote::PayloadType::Public,
+            Vec::new(),
+        );
+
+        let vote_plan_manager = VotePlanManager::new(vote_plan.clone(), HashSet::new());
+        let options = vote_plan.proposals()[0].options().clone();
+        let choice = Choice::new(options.choice_range().end);
+        let vote_cast = VoteCast::new(vote_plan.to_id(), 0, vote::Payload::public(choice));
+
+        assert_eq!(
+            vote_plan_manager
+                .vote(
+                    BlockDate::from_epoch_slot_id(1, 1),
+                    TestGen::unspecified_account_identifier(),
+                    vote_cast
+                )
+                .err()
+                .unwrap(),
+            VoteError::ChoiceOutOfRange { choice, options }
+        );
+    }
+
+    #[test]
+    pub fn vote_manager_private_choice_out_of_range() {
+        use chain_vote::{EncryptingVoteKey, MemberCommunicationKey, MemberState, Vote, CRS};
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let crs = CRS::random(&mut rng);
+        let communication_keys = [MemberCommunicationKey::new(&mut rng).to_public()];
+        let member = MemberState::new(&mut rng, 1, &crs, &communication_keys, 0);
+
+        let vote_plan = VotePlan::new(
+            BlockDate::from_epoch_slot_id(1, 0),
+            BlockDate::from_epoch_slot_id(2, 0),
+            BlockDate::from_epoch_slot_id(3, 0),
+            VoteTestGen::proposals(3),
+            vote::PayloadType::Private,
+            vec![member.public_key()],
+        );
+        let vote_plan_manager = VotePlanManager::new(vote_plan.clone(), HashSet::new());
+        let election_key = EncryptingVoteKey::from_participants(&[member.public_key()]);
+        let options = vote_plan.proposals()[0].options().clone();
+
+        // a valid ballot for a choice past the proposal's options can only
+        // be encrypted over more entries than the proposal has
+        let size = options.choice_range().end as usize + 1;
+        let (encrypted_vote, proof) =
+            vote::encrypt_vote(&mut rng, &election_key, Vote::new(size, size - 1));
+        let vote_cast = VoteCast::new(
+            vote_plan.to_id(),
+            0,
+            Payload::private(encrypted_vote, proof),
+        );
+
+        assert_eq!(
+            vote_plan_manager
+                .vote(
+                    BlockDate::from_epoch_slot_id(1, 1),
+                    TestGen::unspecified_account_identifier(),
+                    vote_cast
+                )
+                .err()
+                .unwrap(),
+            VoteError::InvalidEncryptedVoteSize { size, options }
+        );
+    }
+
+    #[test]
+    pub fn vote_plan_manager_random_private_ballots_cover_every_option() {
+        use chain_vote::{EncryptedTally, MemberCommunicationKey, MemberState, CRS};
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let crs = CRS::random(&mut rng);
+        let communication_keys = [MemberCommunicationKey::new(&mut rng).to_public()];
+        let member = MemberState::new(&mut rng, 1, &crs, &communication_keys, 0);
+
+        let vote_plan = VotePlan::new(
+            BlockDate::from_epoch_slot_id(1, 0),
+            BlockDate::from_epoch_slot_id(2, 0),
+            BlockDate::from_epoch_slot_id(3, 0),
+            VoteTestGen::proposals(1),
+            vote::PayloadType::Private,
+            vec![member.public_key()],
+        );
+        let vote_plan_manager = VotePlanManager::new(vote_plan.clone(), HashSet::new());
+        let options_count = vote_plan.proposals()[0].options().choice_range().end as usize;
+
+        let mut voted = vec![false; options_count];
+        for ballot in VoteTestGen::random_ballots(&vote_plan, 0, 32, &mut rng) {
+            assert!(vote_plan_manager
+                .vote(
+                    BlockDate::from_epoch_slot_id(1, 1),
+                    TestGen::unspecified_account_identifier(),
+                    ballot.clone()
+                )
+                .is_ok());
+
+            // decrypt the ballot on its own to find out its choice
+            let encrypted_vote = match ballot.payload() {
+                Payload::Private { encrypted_vote, .. } => encrypted_vote.clone(),
+                Payload::Public { .. } => unreachable!(),
+            };
+            let mut tally = EncryptedTally::new(options_count);
+            tally.add(encrypted_vote.as_inner(), 1);
+            let (state, share) = tally.finish(&mut rng, member.secret_key());
+            let result = chain_vote::result_from_members(2, 1, &state, &[(0, &share)]);
+            let choice = result.votes.iter().position(|v| *v == Some(1)).unwrap();
+            voted[choice] = true;
+        }
+
+        assert!(voted.iter().all(|voted| *voted));
+    }
+
+    #[test]
+    pub fn vote_plan_manager_verify_ballots_batch() {
+        use chain_vote::{EncryptingVoteKey, MemberCommunicationKey, MemberState, Vote, CRS};
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let crs = CRS::random(&mut rng);
+        let communication_keys = [MemberCommunicationKey::new(&mut rng).to_public()];
+        let member = MemberState::new(&mut rng, 1, &crs, &communication_keys, 0);
+
+        let vote_plan = VotePlan::new(
+            BlockDate::from_epoch_slot_id(1, 0),
+            BlockDate::from_epoch_slot_id(2, 0),
+            BlockDate::from_epoch_slot_id(3, 0),
+            VoteTestGen::proposals(3),
+            vote::PayloadType::Private,
+            vec![member.public_key()],
+        );
+        let vote_plan_manager = VotePlanManager::new(vote_plan.clone(), HashSet::new());
+        let election_key = EncryptingVoteKey::from_participants(&[member.public_key()]);
+
+        let mut ballots = (0..3)
+            .map(|choice| {
+                let (encrypted_vote, proof) =
+                    vote::encrypt_vote(&mut rng, &election_key, Vote::new(4, choice));
+                VoteCast::new(
+                    vote_plan.to_id(),
+                    choice as u8,
+                    Payload::private(encrypted_vote, proof),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(vote_plan_manager.verify_ballots_batch(&ballots), Ok(()));
+
+        let identifier = TestGen::unspecified_account_identifier();
+        let vote_date = BlockDate::from_epoch_slot_id(1, 1);
+        let voted_manager = vote_plan_manager
+            .vote_batch(vote_date, identifier.clone(), &ballots)
+            .unwrap();
+        for proposal in voted_manager.statuses().proposals {
+            assert_eq!(
+                proposal.votes.lookup(&identifier),
+                Some(ballots[proposal.index as usize].payload())
+            );
+        }
+
+        // a single ballot with the proof of another one fails the whole batch
+        let proof = match ballots[2].payload() {
+            Payload::Private { proof, .. } => proof.clone(),
+            Payload::Public { .. } => unreachable!(),
+        };
+        let encrypted_vote = match ballots[0].payload() {
+            Payload::Private { encrypted_vote, .. } => encrypted_vote.clone(),
+            Payload::Public { .. } => unreachable!(),
+        };
+        ballots[0] = VoteCast::new(
+            vote_plan.to_id(),
+            0,
+            Payload::private(encrypted_vote, proof),
+        );
+
+        assert_eq!(
+            vote_plan_manager.verify_ballots_batch(&ballots),
+            Err(VoteError::VoteVerificationError)
+        );
+        assert_eq!(
+            vote_plan_manager
+                .vote_batch(vote_date, identifier, &ballots)
+                .err()
+                .unwrap(),
+            VoteError::VoteVerificationError
+        );
+    }
+
+    #[test]
+    pub fn vote_plan_manager_proposals_with_own_committee() {
+        use chain_vote::{EncryptingVoteKey, MemberCommunicationKey, MemberState, Vote, CRS};
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let crs = CRS::random(&mut rng);
+        let mut committee_key = || {
+            let communication_keys = [MemberCommunicationKey::new(&mut rng).to_public()];
+            MemberState::new(&mut rng, 1, &crs, &communication_keys, 0).public_key()
+        };
+        let committee_a = vec![committee_key()];
+        let committee_b = vec![committee_key()];
+
+        let mut proposals = Proposals::new();
+        for committee in [&committee_a, &committee_b].iter() {
+            let proposal = VoteTestGen::proposal().with_committee_public_keys(committee.to_vec());
+            assert_eq!(proposals.push(proposal), PushProposal::Success);
+        }
+        let vote_plan = VotePlan::new(
+            BlockDate::from_epoch_slot_id(1, 0),
+            BlockDate::from_epoch_slot_id(2, 0),
+            BlockDate::from_epoch_slot_id(3, 0),
+            proposals,
+            vote::PayloadType::Private,
+            Vec::new(),
+        );
+        let vote_plan_manager = VotePlanManager::new(vote_plan.clone(), HashSet::new());
+
+        let mut ballot = |committee: &[MemberPublicKey], proposal_index: u8| {
+            let election_key = EncryptingVoteKey::from_participants(committee);
+            let (encrypted_vote, proof) =
+                vote::encrypt_vote(&mut rng, &election_key, Vote::new(4, 1));
+            VoteCast::new(
+                vote_plan.to_id(),
+                proposal_index,
+                Payload::private(encrypted_vote, proof),
+            )
+        };
+        let ballots = vec![ballot(&committee_a, 0), ballot(&committee_b, 1)];
+        let misdirected_ballot = ballot(&committee_a, 1);
+
+        assert_eq!(vote_plan_manager.verify_ballots_batch(&ballots), Ok(()));
+        for ballot in ballots {
+            assert!(vote_plan_manager
+                .vote(
+                    BlockDate::from_epoch_slot_id(1, 1),
+                    TestGen::unspecified_account_identifier(),
+                    ballot
+                )
+                .is_ok());
+        }
+
+        // the ballot is encrypted for the committee of the other proposal
+        assert_eq!(
+            vote_plan_manager.verify_ballots_batch(std::slice::from_ref(&misdirected_ballot)),
+            Err(VoteError::VoteVerificationError)
+        );
+        assert_eq!(
+            vote_plan_manager
+                .vote(
+                    BlockDate::from_epoch_slot_id(1, 1),
+                    TestGen::unspecified_account_identifier(),
+                    misdirected_ballot
+                )
+                .err()
+                .unwrap(),
+            VoteError::VoteVerificationError
+        );
+    }
+
+    #[test]
+    pub fn vote_plan_manager_first_version_tally_needs_every_member() {
+        use chain_vote::{EncryptingVoteKey, MemberCommunicationKey, MemberState, CRS};
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+
+        let blank = Choice::new(0);
+        let favorable = Choice::new(1);
+        let rejection = Choice::new(2);
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let crs = CRS::random(&mut rng);
+        let communication_keys = [
+            MemberCommunicationKey::new(&mut rng).to_public(),
+            MemberCommunicationKey::new(&mut rng).to_public(),
+        ];
+        let members = (0..communication_keys.len())
+            .map(|i| MemberState::new(&mut rng, 2, &crs, &communication_keys, i))
+            .collect::<Vec<_>>();
+        let member_public_keys = members
+            .iter()
+            .map(|member| member.public_key())
+            .collect::<Vec<_>>();
+        let committee = Wallet::from_value(Value(100));
+
+        let vote_plan = VotePlan::new(
+            BlockDate::from_epoch_slot_id(1, 0),
+            BlockDate::from_epoch_slot_id(2, 0),
+            BlockDate::from_epoch_slot_id(3, 0),
+            VoteTestGen::proposals(1),
+            vote::PayloadType::Private,
+            member_public_keys.clone(),
+        )
+        .with_version(VotePlanVersion::V1);
+        let proposal = &vote_plan.proposals()[0];
+
+        // the votes are encrypted for the sum of the members' keys
+        assert_eq!(
+            vote_plan.proposal_election_key(proposal).to_bytes(),
+            EncryptingVoteKey::from_participants(&member_public_keys).to_bytes()
+        );
+
+        let mut committee_ids = HashSet::new();
+        committee_ids.insert(committee.public_key().into());
+        let vote_cast = VoteCast::new(
+            vote_plan.to_id(),
+            0,
+            VoteTestGen::private_vote_cast_payload(&vote_plan, proposal, favorable, &mut rng),
+        );
+        let vote_plan_manager = VotePlanManager::new(vote_plan.clone(), committee_ids)
+            .vote(
+                BlockDate::from_epoch_slot_id(1, 1),
+                UnspecifiedAccountIdentifier::from_single_account(committee.public_key().into()),
+                vote_cast,
+            )
+            .unwrap();
+
+        let stake = StakeControl::new().add_to(committee.public_key().into(), Stake(51));
+        let vote_plan_manager = vote_plan_manager
+            .private_tally_start(
+                BlockDate::from_epoch_slot_id(2, 1),
+                &stake,
+                committee.public_key().into(),
+            )
+            .unwrap();
+        let status = vote_plan_manager.statuses();
+        let shares = members
+            .iter()
+            .map(|member| {
+                status
+                    .member_decrypt_shares(&mut rng, member.secret_key())
+                    .unwrap()
+                    .remove(0)
+            })
+            .collect::<Vec<_>>();
+        let governance = governance_50_percent(blank, favorable, rejection);
+
+        // a single member cannot decrypt the tally
+        let single = TallyDecryptShares::from_members(vec![vec![(1, shares[1].clone())]]);
+        assert_eq!(
+            vote_plan_manager
+                .private_tally_finish(&stake, &single, &governance, &mut |_| true)
+                .err()
+                .unwrap(),
+            VoteError::from(TallyError::NotEnoughDecryptShares)
+        );
+
+        let every = TallyDecryptShares::new(vec![shares]);
+        let vote_plan_manager = vote_plan_manager
+            .private_tally_finish(&stake, &every, &governance, &mut |_| true)
+            .unwrap();
+        let status = vote_plan_manager.statuses();
+        let result = status.proposals[0].tally_result().unwrap();
+        assert_eq!(result.results()[favorable.as_byte() as usize], 51.into());
+        assert_eq!(status.verify_recorded_tally(), Ok(()));
+    }
 }
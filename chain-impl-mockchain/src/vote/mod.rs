@@ -20,5 +20,8 @@ pub use self::{
     payload::{EncryptedVote, Payload, PayloadType, ProofOfCorrectVote, TryFromIntError},
     privacy::encrypt_vote,
     status::{VotePlanStatus, VoteProposalStatus},
-    tally::{PrivateTallyState, Tally, TallyError, TallyResult, Weight},
+    tally::{
+        Decision, Participation, PrivateTallyState, Tally, TallyError, TallyResult, TieBreak,
+        Weight,
+    },
 };
@@ -1,15 +1,20 @@
 use crate::{
-    certificate::{ExternalProposalId, VotePlanId},
+    certificate::{ExternalProposalId, TallyDecryptShares, VotePlanId, VotePlanVersion},
     date::BlockDate,
     transaction::UnspecifiedAccountIdentifier,
-    vote::{Options, Payload, PayloadType, Tally},
+    vote::{
+        Decision, Options, Participation, Payload, PayloadType, PrivateTallyState, Tally,
+        TallyError, TallyResult,
+    },
 };
-use chain_vote::MemberPublicKey;
+use chain_vote::{EncryptedTally, MemberPublicKey, OpeningVoteKey, TallyDecryptShare};
 use imhamt::Hamt;
+use rand_core::{CryptoRng, RngCore};
 use std::collections::hash_map::DefaultHasher;
 
 pub struct VotePlanStatus {
     pub id: VotePlanId,
+    pub version: VotePlanVersion,
     pub payload: PayloadType,
     pub vote_start: BlockDate,
     pub vote_end: BlockDate,
@@ -22,6 +27,124 @@ pub struct VoteProposalStatus {
     pub index: u8,
     pub proposal_id: ExternalProposalId,
     pub options: Options,
+    pub payload: PayloadType,
+    pub committee_public_keys: Vec<MemberPublicKey>,
     pub tally: Option<Tally>,
     pub votes: Hamt<DefaultHasher, UnspecifiedAccountIdentifier, Payload>,
 }
+
+impl VotePlanStatus {
+    /// compute the decryption shares of one committee member for the
+    /// encrypted tallies of all the private proposals of the vote plan at once
+    ///
+    /// the shares are returned in the order of the private proposals.
+    ///
+    /// # errors
+    ///
+    /// * if the private tally was not started for one of the private proposals
+    /// * if the tally of one of the private proposals is already decrypted
+    ///
+    pub fn member_decrypt_shares<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        secret_key: &OpeningVoteKey,
+    ) -> Result<Vec<TallyDecryptShare>, TallyError> {
+        let encrypted_tallies = self
+            .proposals
+            .iter()
+            .filter(|proposal| proposal.payload == PayloadType::Private)
+            .map(|proposal| {
+                let tally = proposal
+                    .tally
+                    .as_ref()
+                    .ok_or(TallyError::NoEncryptedTally)?;
+                tally
+                    .private_encrypted()
+                    .map(|(encrypted_tally, _)| encrypted_tally)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(chain_vote::finish_all(rng, secret_key, &encrypted_tallies))
+    }
+
+    /// verify the recorded results of the decrypted private tallies
+    ///
+    /// the decrypt shares recorded with every result are verified against
+    /// the encrypted tally and the committee of the proposal, then the
+    /// encrypted tally is decrypted again and compared to the result.
+    ///
+    /// # errors
+    ///
+    /// * if one of the recorded decrypt shares is invalid
+    /// * if a recorded result does not match its decrypted tally
+    ///
+    pub fn verify_recorded_tally(&self) -> Result<(), TallyError> {
+        for proposal in self.proposals.iter() {
+            if let Some(Tally::Private {
+                state:
+                    PrivateTallyState::Decrypted {
+                        encrypted_tally,
+                        participation,
+                        shares,
+                        result,
+                        ..
+                    },
+            }) = &proposal.tally
+            {
+                TallyDecryptShares::from_members(vec![shares.to_vec()]).verify(
+                    self.version,
+                    &[(&proposal.committee_public_keys, encrypted_tally)],
+                )?;
+
+                let decrypted = PrivateTallyState::decrypt(
+                    self.version,
+                    &proposal.options,
+                    encrypted_tally,
+                    participation.eligible(),
+                    shares,
+                )?;
+                if decrypted != *result {
+                    return Err(TallyError::InvalidRecordedResult {
+                        proposal_index: proposal.index,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl VoteProposalStatus {
+    /// the encrypted tally of the proposal
+    ///
+    /// only available once the private tally started and until it is
+    /// decrypted.
+    pub fn encrypted_tally(&self) -> Option<&EncryptedTally> {
+        let (encrypted_tally, _) = self.tally.as_ref()?.private_encrypted().ok()?;
+        Some(encrypted_tally)
+    }
+
+    /// the result of the tally of the proposal
+    ///
+    /// only available once the public tally is done or the private tally is
+    /// decrypted.
+    pub fn tally_result(&self) -> Option<&TallyResult> {
+        self.tally.as_ref()?.result()
+    }
+
+    /// the voting power that could and did take part in the tally of the
+    /// proposal
+    ///
+    /// available as soon as the tally, public or private, is done.
+    pub fn participation(&self) -> Option<&Participation> {
+        self.tally.as_ref().map(Tally::participation)
+    }
+
+    /// whether the proposal passed
+    ///
+    /// only available once the public tally is done or the private tally is
+    /// decrypted.
+    pub fn decision(&self) -> Option<Decision> {
+        self.tally.as_ref()?.decision()
+    }
+}
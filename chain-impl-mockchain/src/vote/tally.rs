@@ -1,10 +1,12 @@
 use crate::{
+    certificate::VotePlanVersion,
+    rewards::Ratio,
     stake::Stake,
     value::Value,
     vote::{Choice, Options},
 };
-use chain_vote::EncryptedTally;
-use std::fmt;
+use chain_vote::{EncryptedTally, TallyDecryptShare};
+use std::{fmt, num::NonZeroU64};
 use thiserror::Error;
 
 /// weight of a vote
@@ -19,23 +21,83 @@ pub struct Weight(u64);
 pub struct TallyResult {
     results: Box<[Weight]>,
 
+    /// weight of the ballots cast with `Choice::ABSTAIN`
+    abstain: Weight,
+
     options: Options,
 }
 
+/// the voting power that could and did take part in the tally of a proposal
+///
+/// for private tallies it is known without decrypting the tally as it only
+/// accounts for the ballots folded in the encrypted tally, not their choices.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct Participation {
+    eligible: Stake,
+    voted: Stake,
+    voters: u64,
+}
+
+/// the outcome of the tally of a proposal
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Decision {
+    /// the proposal was accepted, its action is applied
+    Passed,
+    /// not enough of the stake that voted is in favor of the proposal
+    Rejected,
+    /// not enough of the eligible stake took part in the vote
+    NoQuorum,
+    /// the favorable and the rejecting stake are equal and the proposal
+    /// does not break ties
+    Tie,
+    /// the proposal was accepted but its action could not be applied, the
+    /// treasury not covering it for instance
+    ActionFailed,
+}
+
+/// how to decide between options of equal weight
+///
+/// a tie between the favorable and the rejecting stake of a proposal is
+/// decided by its tie break alone, whatever the approval thresholds. By
+/// default the proposal ends in `Decision::Tie` and its action is not
+/// applied.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[repr(u8)]
+pub enum TieBreak {
+    /// a tie has no winner
+    #[default]
+    NoWinner = 0,
+    /// the tied option with the lowest index wins: the proposal passes if
+    /// its favorable choice comes before the rejecting one
+    LowestOption = 1,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Tally {
-    Public { result: TallyResult },
-    Private { state: PrivateTallyState },
+    Public {
+        result: TallyResult,
+        participation: Participation,
+        decision: Decision,
+    },
+    Private {
+        state: PrivateTallyState,
+    },
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum PrivateTallyState {
     Encrypted {
         encrypted_tally: EncryptedTally,
-        total_stake: Stake,
+        participation: Participation,
     },
+    /// the encrypted tally and the decrypt shares are kept so the result
+    /// can be verified again later on
     Decrypted {
+        encrypted_tally: EncryptedTally,
+        participation: Participation,
+        shares: Box<[(u8, TallyDecryptShare)]>,
         result: TallyResult,
+        decision: Decision,
     },
 }
 
@@ -51,6 +113,22 @@ pub enum TallyError {
     TallyAlreadyDecrypted,
     #[error("the encrypted tally was not provided yet")]
     NoEncryptedTally,
+    #[error("decrypt shares were provided for {found} proposals, expected {expected}")]
+    InvalidDecryptSharesProposals { expected: usize, found: usize },
+    #[error("{found} decrypt shares were provided for a proposal, expected at least one and at most one per committee member ({expected})")]
+    InvalidDecryptSharesCount { expected: usize, found: usize },
+    #[error("the decrypt share of the committee member {member_index} has an invalid proof")]
+    InvalidShareProof { member_index: usize },
+    #[error("a decrypt share was provided for the committee member {member_index} which is not part of the committee")]
+    InvalidShareMember { member_index: usize },
+    #[error("more than one decrypt share was provided for the committee member {member_index}")]
+    DuplicateShare { member_index: usize },
+    #[error("the decrypt shares are not enough to decrypt the tally")]
+    NotEnoughDecryptShares,
+    #[error(
+        "the recorded result of the proposal {proposal_index} does not match its decrypted tally"
+    )]
+    InvalidRecordedResult { proposal_index: u8 },
 }
 
 impl Weight {
@@ -64,16 +142,65 @@ impl Weight {
     }
 }
 
+impl Participation {
+    pub fn new(eligible: Stake) -> Self {
+        Self {
+            eligible,
+            voted: Stake::zero(),
+            voters: 0,
+        }
+    }
+
+    /// account for a ballot counted in the tally with the given stake
+    pub fn add_voter(&mut self, stake: Stake) {
+        self.voted = Stake(self.voted.0.saturating_add(stake.0));
+        self.voters += 1;
+    }
+
+    /// the total voting power that was allowed to vote
+    pub fn eligible(&self) -> Stake {
+        self.eligible
+    }
+
+    /// the voting power of the ballots counted in the tally
+    pub fn voted(&self) -> Stake {
+        self.voted
+    }
+
+    /// the number of ballots counted in the tally
+    pub fn voters(&self) -> u64 {
+        self.voters
+    }
+
+    /// the share of the eligible voting power that voted
+    ///
+    /// returns `None` if there was no eligible voting power.
+    pub fn ratio(&self) -> Option<Ratio> {
+        Some(Ratio {
+            numerator: self.voted.into(),
+            denominator: NonZeroU64::new(self.eligible.into())?,
+        })
+    }
+}
+
 impl Tally {
-    pub fn new_public(result: TallyResult) -> Self {
-        Self::Public { result }
+    pub fn new_public(
+        result: TallyResult,
+        participation: Participation,
+        decision: Decision,
+    ) -> Self {
+        Self::Public {
+            result,
+            participation,
+            decision,
+        }
     }
 
-    pub fn new_private(encrypted_tally: EncryptedTally, total_stake: Stake) -> Self {
+    pub fn new_private(encrypted_tally: EncryptedTally, participation: Participation) -> Self {
         Self::Private {
             state: PrivateTallyState::Encrypted {
                 encrypted_tally,
-                total_stake,
+                participation,
             },
         }
     }
@@ -88,23 +215,46 @@ impl Tally {
 
     pub fn result(&self) -> Option<&TallyResult> {
         match self {
-            Self::Public { result } => Some(result),
+            Self::Public { result, .. } => Some(result),
             Self::Private {
-                state: PrivateTallyState::Decrypted { result },
+                state: PrivateTallyState::Decrypted { result, .. },
             } => Some(result),
             _ => None,
         }
     }
 
-    pub fn private_encrypted(&self) -> Result<(&EncryptedTally, &Stake), TallyError> {
+    /// the outcome of the tally, once its result is known
+    pub fn decision(&self) -> Option<Decision> {
+        match self {
+            Self::Public { decision, .. }
+            | Self::Private {
+                state: PrivateTallyState::Decrypted { decision, .. },
+            } => Some(*decision),
+            _ => None,
+        }
+    }
+
+    pub fn participation(&self) -> &Participation {
+        match self {
+            Self::Public { participation, .. }
+            | Self::Private {
+                state: PrivateTallyState::Encrypted { participation, .. },
+            }
+            | Self::Private {
+                state: PrivateTallyState::Decrypted { participation, .. },
+            } => participation,
+        }
+    }
+
+    pub fn private_encrypted(&self) -> Result<(&EncryptedTally, &Participation), TallyError> {
         match self {
             Self::Private {
                 state:
                     PrivateTallyState::Encrypted {
                         encrypted_tally,
-                        total_stake,
+                        participation,
                     },
-            } => Ok((encrypted_tally, total_stake)),
+            } => Ok((encrypted_tally, participation)),
             Self::Private {
                 state: PrivateTallyState::Decrypted { .. },
             } => Err(TallyError::TallyAlreadyDecrypted),
@@ -112,17 +262,93 @@ impl Tally {
         }
     }
 
-    pub fn private_set_result(mut self, result: TallyResult) -> Result<Self, TallyError> {
-        match &mut self {
-            Self::Private { state } => {
-                if let PrivateTallyState::Decrypted { .. } = state {
-                    return Err(TallyError::TallyAlreadyDecrypted);
-                }
-                *state = PrivateTallyState::Decrypted { result };
-                Ok(self)
+    /// record the result of the private tally along with the decrypt shares
+    /// it was decrypted with
+    pub fn private_set_result(
+        self,
+        shares: Box<[(u8, TallyDecryptShare)]>,
+        result: TallyResult,
+        decision: Decision,
+    ) -> Result<Self, TallyError> {
+        match self {
+            Self::Private {
+                state:
+                    PrivateTallyState::Encrypted {
+                        encrypted_tally,
+                        participation,
+                    },
+            } => Ok(Self::Private {
+                state: PrivateTallyState::Decrypted {
+                    encrypted_tally,
+                    participation,
+                    shares,
+                    result,
+                    decision,
+                },
+            }),
+            Self::Private {
+                state: PrivateTallyState::Decrypted { .. },
+            } => Err(TallyError::TallyAlreadyDecrypted),
+            Self::Public { .. } => Err(TallyError::InvalidPrivacy),
+        }
+    }
+}
+
+impl PrivateTallyState {
+    /// decrypt the result of an encrypted tally with the decrypt shares of
+    /// enough committee members
+    ///
+    /// the shares are expected to have been verified already, the tally
+    /// being decrypted the way the committee of a vote plan of the given
+    /// version shares its key.
+    pub fn decrypt(
+        version: VotePlanVersion,
+        options: &Options,
+        encrypted_tally: &EncryptedTally,
+        total_stake: Stake,
+        shares: &[(u8, TallyDecryptShare)],
+    ) -> Result<TallyResult, TallyError> {
+        use std::convert::TryInto;
+        let state = encrypted_tally.state();
+        // total voting power + 1
+        let max_votes = total_stake.0 + 1;
+        let table_size = (max_votes / 3) as usize;
+        let private_result = match version {
+            VotePlanVersion::V1 => {
+                let shares = shares
+                    .iter()
+                    .map(|(_, share)| share.clone())
+                    .collect::<Vec<_>>();
+                chain_vote::result(max_votes, table_size, &state, &shares)
+            }
+            VotePlanVersion::V2 => {
+                let shares = shares
+                    .iter()
+                    .map(|(member_index, share)| (usize::from(*member_index), share))
+                    .collect::<Vec<_>>();
+                chain_vote::result_from_members(max_votes, table_size, &state, &shares)
             }
-            _ => Err(TallyError::InvalidPrivacy),
+        };
+        // the votes can always be found with the shares of enough members,
+        // otherwise the decrypted values are meaningless
+        let votes = private_result
+            .votes
+            .iter()
+            .copied()
+            .collect::<Option<Vec<_>>>()
+            .ok_or(TallyError::NotEnoughDecryptShares)?;
+        let mut result = TallyResult::new(options.clone());
+        for (choice, weight) in votes.into_iter().enumerate() {
+            result.add_vote(
+                Choice::new(choice.try_into().map_err(|_| {
+                    TallyError::InvalidPrivateChoiceSize {
+                        choice: choice as u64,
+                    }
+                })?),
+                weight,
+            )?;
         }
+        Ok(result)
     }
 }
 
@@ -130,25 +356,67 @@ impl TallyResult {
     pub fn new(options: Options) -> Self {
         let len = options.choice_range().len();
         let results = vec![Weight(0); len].into();
-        Self { results, options }
+        Self {
+            results,
+            abstain: Weight(0),
+            options,
+        }
     }
 
     pub fn results(&self) -> &[Weight] {
         &self.results
     }
 
+    /// the weight of the ballots which abstained
+    pub fn abstain(&self) -> Weight {
+        self.abstain
+    }
+
+    /// the weight of all the ballots counted, abstentions included
     pub fn participation(&self) -> Stake {
         let s: u64 = self.results.iter().map(|w| w.0).sum();
-        Stake::from_value(Value(s))
+        Stake::from_value(Value(s.saturating_add(self.abstain.0)))
     }
 
     pub fn options(&self) -> &Options {
         &self.options
     }
 
+    /// the option with the greatest weight
+    ///
+    /// returns `None` if no vote was counted or if several options share
+    /// the greatest weight.
+    pub fn winner(&self) -> Option<Choice> {
+        self.winner_with(TieBreak::NoWinner)
+    }
+
+    /// the option with the greatest weight, ties being broken with the
+    /// given rule
+    ///
+    /// returns `None` if no vote was counted.
+    pub fn winner_with(&self, tie_break: TieBreak) -> Option<Choice> {
+        let max = self.results.iter().max()?;
+        if max.is_zero() || (self.is_tie() && tie_break == TieBreak::NoWinner) {
+            return None;
+        }
+        self.results
+            .iter()
+            .position(|w| w == max)
+            .map(|index| Choice::new(index as u8))
+    }
+
+    /// several options share the greatest, non null, weight
+    pub fn is_tie(&self) -> bool {
+        match self.results.iter().max() {
+            Some(max) if !max.is_zero() => self.results.iter().filter(|w| *w == max).count() > 1,
+            _ => false,
+        }
+    }
+
     /// add a vote and its weight on the tally
     ///
-    /// if the vote's weight is null (`0`), nothing will be changed.
+    /// if the vote's weight is null (`0`), nothing will be changed. The
+    /// weight of a `Choice::ABSTAIN` vote is not added to any option.
     ///
     /// # Errors
     ///
@@ -159,7 +427,10 @@ impl TallyResult {
     {
         let weight = weight.into();
 
-        if !self.options.validate(choice) {
+        if choice.is_abstain() {
+            self.abstain = self.abstain.saturating_add(weight);
+            Ok(())
+        } else if !self.options.validate(choice) {
             Err(TallyError::InvalidChoice {
                 options: self.options.clone(),
                 choice,
@@ -213,7 +484,7 @@ impl fmt::Display for Weight {
 
 #[cfg(test)]
 mod tests {
-    use super::{Tally, TallyError, TallyResult, Weight};
+    use super::{Decision, Participation, Tally, TallyError, TallyResult, TieBreak, Weight};
     use crate::{
         stake::Stake,
         vote::{Choice, Options},
@@ -241,6 +512,54 @@ mod tests {
         assert_eq!(value, 10);
     }
 
+    #[test]
+    pub fn participation_ratio() {
+        assert_eq!(Participation::new(Stake(0)).ratio(), None);
+
+        let mut participation = Participation::new(Stake(10));
+        participation.add_voter(Stake(3));
+        participation.add_voter(Stake(0));
+        assert_eq!(participation.voted(), Stake(3));
+        assert_eq!(participation.voters(), 2);
+
+        let ratio = participation.ratio().unwrap();
+        assert_eq!(ratio.numerator, 3);
+        assert_eq!(ratio.denominator.get(), 10);
+    }
+
+    #[test]
+    pub fn tally_result_winner() {
+        let options = Options::new_length(3u8).unwrap();
+        let mut tally_result = TallyResult::new(options);
+        assert_eq!(tally_result.winner(), None);
+
+        tally_result.add_vote(Choice::new(1), Weight(10)).unwrap();
+        assert_eq!(tally_result.winner(), Some(Choice::new(1)));
+
+        tally_result.add_vote(Choice::new(2), Weight(10)).unwrap();
+        assert_eq!(tally_result.winner(), None);
+
+        tally_result.add_vote(Choice::new(2), Weight(1)).unwrap();
+        assert_eq!(tally_result.winner(), Some(Choice::new(2)));
+    }
+
+    #[test]
+    pub fn tally_result_tie_break() {
+        let options = Options::new_length(3u8).unwrap();
+        let mut tally_result = TallyResult::new(options);
+        assert!(!tally_result.is_tie());
+        assert_eq!(tally_result.winner_with(TieBreak::LowestOption), None);
+
+        tally_result.add_vote(Choice::new(2), Weight(10)).unwrap();
+        tally_result.add_vote(Choice::new(1), Weight(10)).unwrap();
+        assert!(tally_result.is_tie());
+        assert_eq!(tally_result.winner_with(TieBreak::NoWinner), None);
+        assert_eq!(
+            tally_result.winner_with(TieBreak::LowestOption),
+            Some(Choice::new(1))
+        );
+    }
+
     #[test]
     pub fn tally_result_add_vote_invalid_test() {
         let options = Options::new_length(3u8).unwrap();
@@ -274,9 +593,26 @@ mod tests {
         assert_eq!(*tally_result.options(), options);
     }
 
+    #[test]
+    pub fn tally_result_add_abstain_vote() {
+        let options = Options::new_length(3u8).unwrap();
+        let mut tally_result = TallyResult::new(options);
+        tally_result.add_vote(Choice::new(1), Weight(2)).unwrap();
+        tally_result.add_vote(Choice::ABSTAIN, Weight(5)).unwrap();
+
+        assert_eq!(tally_result.results(), &[Weight(0), Weight(2), Weight(0)]);
+        assert_eq!(tally_result.abstain(), Weight(5));
+        assert_eq!(tally_result.participation(), Stake(7));
+        assert_eq!(tally_result.winner(), Some(Choice::new(1)));
+    }
+
     #[quickcheck]
     pub fn tally(tally_result: TallyResult) -> TestResult {
-        let tally = Tally::new_public(tally_result.clone());
+        let tally = Tally::new_public(
+            tally_result.clone(),
+            Participation::default(),
+            Decision::Passed,
+        );
         TestResult::from_bool(tally.is_public() && (*tally.result().unwrap()) == tally_result)
     }
 }
@@ -58,6 +58,10 @@ impl Commitment {
         self.c.to_bytes()
     }
 
+    pub fn as_group_element(&self) -> &GroupElement {
+        &self.c
+    }
+
     pub fn from_bytes(buf: &[u8]) -> Option<Self> {
         Some(Self {
             c: GroupElement::from_bytes(buf)?,
@@ -2,11 +2,18 @@ use crate::commitment::CommitmentKey;
 use crate::gang::{GroupElement, Scalar};
 use crate::gargamel::{PublicKey, SecretKey};
 use crate::hybrid;
-use crate::math::Polynomial;
+use crate::math::{lagrange_at_zero, Polynomial};
+use cryptoxide::chacha20poly1305::ChaCha20Poly1305;
+use cryptoxide::hmac::Hmac;
+use cryptoxide::pbkdf2::pbkdf2;
+use cryptoxide::sha2::Sha512;
 use rand_core::{CryptoRng, RngCore};
+use zeroize::{Zeroize, Zeroizing};
 
 /// Committee member election secret key
-#[derive(Clone)]
+///
+/// The key is zeroed when dropped and cannot be cloned, so that it is not
+/// copied around in memory.
 pub struct MemberSecretKey(pub(crate) SecretKey);
 
 /// Committee member election public key
@@ -32,7 +39,8 @@ impl ElectionPublicKey {
 }
 
 /// Initial state generated by a Member, which include keys for this election
-#[derive(Clone)]
+///
+/// The secret key of the state is zeroed when the state is dropped.
 pub struct MemberState {
     sk: MemberSecretKey,
     owner_index: usize,
@@ -41,10 +49,16 @@ pub struct MemberState {
     encrypted: Vec<(hybrid::Encrypted, hybrid::Encrypted)>,
 }
 
+/// Common Reference String shared by all the members of a committee.
+///
+/// It has to be agreed upon before the members generate their state, and can
+/// be distributed with `to_bytes` and reloaded with `from_bytes`.
 pub type CRS = GroupElement;
 
 impl MemberState {
     /// Generate a new member state from random, where the number
+    /// of members needed to decrypt the tally (see `threshold_secret_key`)
+    /// is `t`
     pub fn new<R: RngCore + CryptoRng>(
         rng: &mut R,
         t: usize,
@@ -57,8 +71,8 @@ impl MemberState {
         assert!(t <= n);
         assert!(my < n);
 
-        let pcomm = Polynomial::random(rng, t);
-        let pshek = Polynomial::random(rng, t);
+        let pcomm = Polynomial::random(rng, t - 1);
+        let pshek = Polynomial::random(rng, t - 1);
 
         let mut apubs = Vec::new();
         let mut es = Vec::new();
@@ -70,33 +84,29 @@ impl MemberState {
             es.push(e);
         }
 
+        // generate a share for every member, self included, so that each of
+        // them recovers its threshold secret key the same way
         let mut encrypted = Vec::new();
-        #[allow(clippy::needless_range_loop)]
-        for i in 0..n {
-            // don't generate share for self
-            if i == my {
-                continue;
-            } else {
-                let idx = Scalar::from_u64((i + 1) as u64);
-                let share_comm = pcomm.evaluate(&idx);
-                let share_shek = pshek.evaluate(&idx);
-
-                let pk = &committee_pks[i];
-                let ck_comm = CommitmentKey::generate(rng);
-                let ck_shek = CommitmentKey::generate(rng);
+        for (i, pk) in committee_pks.iter().enumerate() {
+            let idx = member_point(i);
+            let share_comm = pcomm.evaluate(&idx);
+            let share_shek = Zeroizing::new(pshek.evaluate(&idx));
 
-                let rcomm = Scalar::random(rng);
-                let rshek = Scalar::random(rng);
-                let ecomm = hybrid::encrypt(&pk.0, &ck_comm, &share_comm.to_bytes(), &rcomm);
-                let eshek = hybrid::encrypt(&pk.0, &ck_shek, &share_shek.to_bytes(), &rshek);
+            let ck_comm = CommitmentKey::generate(rng);
+            let ck_shek = CommitmentKey::generate(rng);
 
-                encrypted.push((ecomm, eshek));
-            }
+            let rcomm = Scalar::random(rng);
+            let rshek = Scalar::random(rng);
+            let ecomm = hybrid::encrypt(&pk.0, &ck_comm, &share_comm.to_bytes(), &rcomm);
+            let share_shek_bytes = Zeroizing::new(share_shek.to_bytes());
+            let eshek = hybrid::encrypt(&pk.0, &ck_shek, &*share_shek_bytes, &rshek);
+
+            encrypted.push((ecomm, eshek));
         }
 
-        assert_eq!(apubs.len(), t + 1);
-        assert_eq!(es.len(), t + 1);
-        assert_eq!(encrypted.len(), n - 1);
+        assert_eq!(apubs.len(), t);
+        assert_eq!(es.len(), t);
+        assert_eq!(encrypted.len(), n);
 
         MemberState {
             sk: MemberSecretKey(SecretKey {
@@ -118,6 +128,224 @@ impl MemberState {
             pk: self.apubs[0].clone(),
         })
     }
+
+    /// Share of this member's secret dealt to the member at the given index
+    /// of the committee, to be sent to that member.
+    pub fn share_for(&self, member: usize) -> Option<MemberShare> {
+        let (_, eshek) = self.encrypted.get(member)?;
+        Some(MemberShare {
+            coefficients: self.apubs.clone(),
+            encrypted: eshek.clone(),
+        })
+    }
+
+    /// Secret key of this member for a threshold decryption of the tally.
+    ///
+    /// It combines the shares dealt to this member by every member of the
+    /// committee, this member included, each of them being checked against
+    /// the coefficients committed by its dealer. When the election key is
+    /// built from the matching public keys with
+    /// `ElectionPublicKey::from_threshold_participants`, the decryption shares
+    /// of any `t` members are enough to decrypt the tally.
+    ///
+    /// Returns `None` if there is not one share from every member of the
+    /// committee, or if one of the shares cannot be decrypted with the
+    /// communication key or does not match the dealer's commitment.
+    pub fn threshold_secret_key(
+        &self,
+        communication_key: &MemberCommunicationKey,
+        shares: &[MemberShare],
+    ) -> Option<MemberSecretKey> {
+        if shares.len() != self.encrypted.len() {
+            return None;
+        }
+        let idx = member_point(self.owner_index - 1);
+        let mut sk = Scalar::zero();
+        for share in shares {
+            let plain = Zeroizing::new(hybrid::decrypt(&communication_key.0, &share.encrypted));
+            let share_shek = Zeroizing::new(Scalar::from_bytes(&plain)?);
+            if GroupElement::generator() * &*share_shek != share.commitment_at(&idx) {
+                return None;
+            }
+            sk = &sk + &*share_shek;
+        }
+        Some(MemberSecretKey(SecretKey { sk }))
+    }
+}
+
+/// Share of a committee member's secret, dealt to another member of the
+/// committee and encrypted to its communication key.
+#[derive(Clone)]
+pub struct MemberShare {
+    coefficients: Vec<GroupElement>,
+    encrypted: hybrid::Encrypted,
+}
+
+impl MemberShare {
+    /// Commitment to the dealer's polynomial evaluated at `x`
+    fn commitment_at(&self, x: &Scalar) -> GroupElement {
+        let mut power = Scalar::one();
+        let mut commitment = GroupElement::zero();
+        for coefficient in &self.coefficients {
+            commitment = commitment + coefficient * &power;
+            power = &power * x;
+        }
+        commitment
+    }
+}
+
+/// Point at which the polynomials are evaluated for the member at the given
+/// index of the committee, members being 1-indexed
+pub(crate) fn member_point(index: usize) -> Scalar {
+    Scalar::from_u64(index as u64 + 1)
+}
+
+const SAVE_SALT_LEN: usize = 16;
+const SAVE_NONCE_LEN: usize = 12;
+const SAVE_TAG_LEN: usize = 16;
+const SAVE_KDF_ITERATIONS: u32 = 20_000;
+
+fn save_cipher(passphrase: &[u8], salt: &[u8], nonce: &[u8]) -> ChaCha20Poly1305 {
+    let mut key = [0u8; 32];
+    pbkdf2(
+        &mut Hmac::new(Sha512::new(), passphrase),
+        salt,
+        SAVE_KDF_ITERATIONS,
+        &mut key,
+    );
+    let cipher = ChaCha20Poly1305::new(&key, nonce, &[]);
+    key.zeroize();
+    cipher
+}
+
+fn write_chunk(bytes: &mut Vec<u8>, chunk: &[u8]) {
+    bytes.extend_from_slice(&(chunk.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(chunk);
+}
+
+fn read_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if bytes.len() < len {
+        return None;
+    }
+    let (read, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Some(read)
+}
+
+fn read_u32(bytes: &mut &[u8]) -> Option<u32> {
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(read_bytes(bytes, 4)?);
+    Some(u32::from_be_bytes(buf))
+}
+
+fn read_chunk<'a>(bytes: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let len = read_u32(bytes)? as usize;
+    read_bytes(bytes, len)
+}
+
+fn read_group_elements(bytes: &mut &[u8]) -> Option<Vec<GroupElement>> {
+    let n = read_u32(bytes)? as usize;
+    (0..n)
+        .map(|_| GroupElement::from_bytes(read_bytes(bytes, GroupElement::BYTES_LEN)?))
+        .collect()
+}
+
+impl MemberState {
+    /// Serialize the member state, secret key included, encrypted with a key
+    /// derived from the passphrase.
+    ///
+    /// The output is `SALT || NONCE || ENCRYPTED-STATE || POLY1305-TAG` and
+    /// can be restored with `MemberState::load`.
+    pub fn save<R: RngCore + CryptoRng>(&self, rng: &mut R, passphrase: &[u8]) -> Vec<u8> {
+        let plain = self.to_bytes();
+
+        let mut out = vec![0u8; SAVE_SALT_LEN + SAVE_NONCE_LEN + plain.len() + SAVE_TAG_LEN];
+        let (salt_and_nonce, rest) = out.split_at_mut(SAVE_SALT_LEN + SAVE_NONCE_LEN);
+        rng.fill_bytes(salt_and_nonce);
+        let (salt, nonce) = salt_and_nonce.split_at(SAVE_SALT_LEN);
+        let (encrypted, tag) = rest.split_at_mut(plain.len());
+        save_cipher(passphrase, salt, nonce).encrypt(&plain, encrypted, tag);
+
+        out
+    }
+
+    /// Restore a member state saved with `MemberState::save`.
+    ///
+    /// Returns `None` if the passphrase is not the one used to save the
+    /// state or if the data is corrupted.
+    pub fn load(passphrase: &[u8], bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < SAVE_SALT_LEN + SAVE_NONCE_LEN + SAVE_TAG_LEN {
+            return None;
+        }
+        let (salt, rest) = bytes.split_at(SAVE_SALT_LEN);
+        let (nonce, rest) = rest.split_at(SAVE_NONCE_LEN);
+        let (encrypted, tag) = rest.split_at(rest.len() - SAVE_TAG_LEN);
+
+        let mut plain = Zeroizing::new(vec![0u8; encrypted.len()]);
+        if save_cipher(passphrase, salt, nonce).decrypt(encrypted, &mut plain, tag) {
+            Self::from_bytes(&plain)
+        } else {
+            None
+        }
+    }
+
+    /// the secret key is copied in a buffer allocated to its final size,
+    /// so that growing it does not leave copies of the key behind
+    fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(self.owner_index as u32).to_be_bytes());
+        for elements in [&self.apubs, &self.es].iter() {
+            bytes.extend_from_slice(&(elements.len() as u32).to_be_bytes());
+            for element in elements.iter() {
+                bytes.extend_from_slice(&element.to_bytes());
+            }
+        }
+        bytes.extend_from_slice(&(self.encrypted.len() as u32).to_be_bytes());
+        for (ecomm, eshek) in &self.encrypted {
+            write_chunk(&mut bytes, &ecomm.to_bytes());
+            write_chunk(&mut bytes, &eshek.to_bytes());
+        }
+
+        let sk = Zeroizing::new(self.sk.to_bytes());
+        let mut out = Zeroizing::new(Vec::with_capacity(bytes.len() + sk.len()));
+        out.extend_from_slice(&*sk);
+        out.extend_from_slice(&bytes);
+        out
+    }
+
+    fn from_bytes(mut bytes: &[u8]) -> Option<Self> {
+        let bytes = &mut bytes;
+        let sk = MemberSecretKey::from_bytes(read_bytes(bytes, Scalar::BYTES_LEN)?)?;
+        let owner_index = read_u32(bytes)? as usize;
+        let apubs = read_group_elements(bytes)?;
+        let es = read_group_elements(bytes)?;
+        let n = read_u32(bytes)? as usize;
+        let encrypted = (0..n)
+            .map(|_| {
+                let ecomm = hybrid::Encrypted::from_bytes(read_chunk(bytes)?)?;
+                let eshek = hybrid::Encrypted::from_bytes(read_chunk(bytes)?)?;
+                Some((ecomm, eshek))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        if !bytes.is_empty() || apubs.is_empty() || apubs.len() != es.len() {
+            return None;
+        }
+
+        Some(MemberState {
+            sk,
+            owner_index,
+            apubs,
+            es,
+            encrypted,
+        })
+    }
+}
+
+impl Zeroize for MemberSecretKey {
+    fn zeroize(&mut self) {
+        self.0.sk.zeroize()
+    }
 }
 
 impl MemberSecretKey {
@@ -129,6 +357,96 @@ impl MemberSecretKey {
         let sk = Scalar::from_bytes(bytes)?;
         Some(Self(SecretKey { sk }))
     }
+
+    pub fn to_public(&self) -> MemberPublicKey {
+        MemberPublicKey(PublicKey {
+            pk: GroupElement::generator() * &self.0.sk,
+        })
+    }
+
+    /// Reshare this threshold secret key to the members of a new committee,
+    /// handing the decryption of the tally over to them.
+    ///
+    /// Returns a share for every member of the new committee, in the order
+    /// of `committee_pks`, any `t` of the new members being needed to decrypt
+    /// the tally. The new members combine the shares dealt by a threshold of
+    /// the previous members with `MemberSecretKey::from_handover`, and the
+    /// election key built from their public keys is left unchanged.
+    pub fn reshare<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        t: usize,
+        committee_pks: &[MemberCommunicationPublicKey],
+    ) -> Vec<MemberShare> {
+        assert!(t > 0);
+        assert!(t <= committee_pks.len());
+
+        let mut coefficients = vec![self.0.sk.clone()];
+        coefficients.extend((1..t).map(|_| Scalar::random(rng)));
+        let pshek = Polynomial::from_vec(coefficients);
+        let apubs = pshek
+            .get_coefficients()
+            .map(|ai| GroupElement::generator() * ai)
+            .collect::<Vec<_>>();
+
+        committee_pks
+            .iter()
+            .enumerate()
+            .map(|(i, pk)| {
+                let share_shek = Zeroizing::new(pshek.evaluate(&member_point(i)));
+                let share_shek_bytes = Zeroizing::new(share_shek.to_bytes());
+                let ck_shek = CommitmentKey::generate(rng);
+                let rshek = Scalar::random(rng);
+                MemberShare {
+                    coefficients: apubs.clone(),
+                    encrypted: hybrid::encrypt(&pk.0, &ck_shek, &*share_shek_bytes, &rshek),
+                }
+            })
+            .collect()
+    }
+
+    /// Secret key of the member at the given index of a new committee, once
+    /// the previous committee handed the decryption of the tally over to it.
+    ///
+    /// `previous` are the public keys of the previous committee, as given to
+    /// `ElectionPublicKey::from_threshold_participants`, and `shares` the
+    /// shares dealt to this member with `reshare`, each with the index of its
+    /// dealer in the previous committee.
+    ///
+    /// Returns `None` if the dealers do not reach the threshold of the
+    /// previous committee, if the shares were not dealt with the same
+    /// threshold, or if one of them cannot be decrypted with the communication
+    /// key or does not match the dealer's commitment and public key.
+    pub fn from_handover(
+        communication_key: &MemberCommunicationKey,
+        member: usize,
+        previous: &[MemberPublicKey],
+        shares: &[(usize, MemberShare)],
+    ) -> Option<Self> {
+        let threshold = shares.first()?.1.coefficients.len();
+        let dealers = shares.iter().map(|(dealer, _)| *dealer).collect::<Vec<_>>();
+        if !ElectionPublicKey::reached_by(previous, &dealers) {
+            return None;
+        }
+
+        let points = dealers.iter().map(|i| member_point(*i)).collect::<Vec<_>>();
+        let idx = member_point(member);
+        let mut sk = Scalar::zero();
+        for ((dealer, share), point) in shares.iter().zip(points.iter()) {
+            if share.coefficients.len() != threshold
+                || share.coefficients.first() != Some(&previous[*dealer].0.pk)
+            {
+                return None;
+            }
+            let plain = Zeroizing::new(hybrid::decrypt(&communication_key.0, &share.encrypted));
+            let share_shek = Zeroizing::new(Scalar::from_bytes(&plain)?);
+            if GroupElement::generator() * &*share_shek != share.commitment_at(&idx) {
+                return None;
+            }
+            sk = &sk + &(&*share_shek * &lagrange_at_zero(point, &points));
+        }
+        Some(MemberSecretKey(SecretKey { sk }))
+    }
 }
 
 impl MemberPublicKey {
@@ -178,16 +496,61 @@ impl MemberCommunicationPublicKey {
     pub fn to_bytes(&self) -> Vec<u8> {
         self.0.to_bytes()
     }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        PublicKey::from_bytes(buf).map(Self)
+    }
 }
 
 impl ElectionPublicKey {
     /// Create an election public key from all the participants of this committee
     pub fn from_participants(pks: &[MemberPublicKey]) -> Self {
-        let mut k = pks[0].0.pk.clone();
-        for pk in &pks[1..] {
-            k = k + &pk.0.pk;
+        ElectionPublicKey(PublicKey {
+            pk: GroupElement::sum(pks.iter().map(|pk| &pk.0.pk)),
+        })
+    }
+
+    /// Create an election public key from all the participants of a committee
+    /// holding threshold secret keys (see `MemberState::threshold_secret_key`)
+    ///
+    /// The participants are expected in the order of their index in the
+    /// committee, the key being the interpolation at zero of their public keys.
+    pub fn from_threshold_participants(pks: &[MemberPublicKey]) -> Self {
+        let points = (0..pks.len()).map(member_point).collect::<Vec<_>>();
+        let weighted = pks
+            .iter()
+            .zip(points.iter())
+            .map(|(pk, point)| &pk.0.pk * lagrange_at_zero(point, &points))
+            .collect::<Vec<_>>();
+        ElectionPublicKey(PublicKey {
+            pk: GroupElement::sum(weighted.iter()),
+        })
+    }
+
+    /// Check the given members of the committee reach the threshold needed
+    /// to decrypt a tally encrypted to the election key of `participants`,
+    /// before decrypting it with their decryption shares.
+    ///
+    /// `participants` are all the members of the committee, as given to
+    /// `from_threshold_participants`, and `members` the indices of some of them. The
+    /// public keys of a threshold of members interpolate at zero to the
+    /// election key, the ones of fewer members do not. Duplicate or out of
+    /// range indices never reach the threshold.
+    pub fn reached_by(participants: &[MemberPublicKey], members: &[usize]) -> bool {
+        let mut sorted = members.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted.len() != members.len() || sorted.iter().any(|i| *i >= participants.len()) {
+            return false;
         }
-        ElectionPublicKey(PublicKey { pk: k })
+
+        let points = members.iter().map(|i| member_point(*i)).collect::<Vec<_>>();
+        let weighted = members
+            .iter()
+            .zip(points.iter())
+            .map(|(i, point)| &participants[*i].0.pk * lagrange_at_zero(point, &points))
+            .collect::<Vec<_>>();
+        GroupElement::sum(weighted.iter()) == Self::from_threshold_participants(participants).0.pk
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
@@ -0,0 +1,132 @@
+//! Non-interactive zero knowledge proof of discrete logarithm equality
+//! (Chaum-Pedersen), made non-interactive with Fiat-Shamir.
+//!
+//! Given the points `g1`, `h1 = g1 * x`, `g2` and `h2 = g2 * x`, the proof
+//! convinces a verifier that `h1` and `h2` have the same discrete logarithm
+//! relative to their respective base without revealing `x`.
+
+use crate::gang::{GroupElement, Scalar};
+use cryptoxide::blake2b::Blake2b;
+use cryptoxide::digest::Digest;
+use rand_core::{CryptoRng, RngCore};
+
+/// Proof of discrete logarithm equality
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Proof {
+    c: Scalar,
+    z: Scalar,
+}
+
+fn challenge(
+    g1: &GroupElement,
+    h1: &GroupElement,
+    g2: &GroupElement,
+    h2: &GroupElement,
+    a1: &GroupElement,
+    a2: &GroupElement,
+) -> Scalar {
+    let mut ctx = Blake2b::new(32);
+    for p in [g1, h1, g2, h2, a1, a2].iter() {
+        ctx.input(&p.to_bytes());
+    }
+    let mut i = 0u32;
+    let mut h = [0u8; 32];
+    loop {
+        let mut c = ctx.clone();
+        c.input(&i.to_be_bytes());
+        c.result(&mut h);
+        match Scalar::from_bytes(&h) {
+            None => i += 1,
+            Some(fe) => break fe,
+        }
+    }
+}
+
+impl Proof {
+    pub const BYTES_LEN: usize = Scalar::BYTES_LEN * 2;
+
+    /// Generate a proof that `h1 = g1 * dlog` and `h2 = g2 * dlog`
+    pub fn generate<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        g1: &GroupElement,
+        h1: &GroupElement,
+        g2: &GroupElement,
+        h2: &GroupElement,
+        dlog: &Scalar,
+    ) -> Self {
+        let w = Scalar::random(rng);
+        let a1 = g1 * &w;
+        let a2 = g2 * &w;
+        let c = challenge(g1, h1, g2, h2, &a1, &a2);
+        let z = &w + &(dlog * &c);
+        Proof { c, z }
+    }
+
+    /// Verify that `h1` and `h2` share the same discrete logarithm relative to
+    /// `g1` and `g2` respectively
+    pub fn verify(
+        &self,
+        g1: &GroupElement,
+        h1: &GroupElement,
+        g2: &GroupElement,
+        h2: &GroupElement,
+    ) -> bool {
+        let a1 = g1 * &self.z - h1 * &self.c;
+        let a2 = g2 * &self.z - h2 * &self.c;
+        self.c == challenge(g1, h1, g2, h2, &a1, &a2)
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES_LEN] {
+        let mut bytes = [0u8; Self::BYTES_LEN];
+        bytes[..Scalar::BYTES_LEN].copy_from_slice(&self.c.to_bytes());
+        bytes[Scalar::BYTES_LEN..].copy_from_slice(&self.z.to_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTES_LEN {
+            return None;
+        }
+        Some(Proof {
+            c: Scalar::from_bytes(&bytes[..Scalar::BYTES_LEN])?,
+            z: Scalar::from_bytes(&bytes[Scalar::BYTES_LEN..])?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn prove_verify() {
+        let mut r = ChaCha20Rng::from_seed([0u8; 32]);
+        let x = Scalar::random(&mut r);
+        let g1 = GroupElement::generator();
+        let g2 = GroupElement::random(&mut r);
+        let h1 = &g1 * &x;
+        let h2 = &g2 * &x;
+
+        let proof = Proof::generate(&mut r, &g1, &h1, &g2, &h2, &x);
+        assert!(proof.verify(&g1, &h1, &g2, &h2));
+
+        let proof = Proof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(proof.verify(&g1, &h1, &g2, &h2));
+    }
+
+    #[test]
+    fn prove_verify_different_dlog() {
+        let mut r = ChaCha20Rng::from_seed([1u8; 32]);
+        let x = Scalar::random(&mut r);
+        let y = Scalar::random(&mut r);
+        let g1 = GroupElement::generator();
+        let g2 = GroupElement::random(&mut r);
+        let h1 = &g1 * &x;
+        let h2 = &g2 * &y;
+
+        let proof = Proof::generate(&mut r, &g1, &h1, &g2, &h2, &x);
+        assert!(!proof.verify(&g1, &h1, &g2, &h2));
+    }
+}
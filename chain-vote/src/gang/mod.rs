@@ -50,6 +50,16 @@ mod tests {
         assert_eq!(ge3_got, ge3);
     }
 
+    #[test]
+    fn identity_from_bytes() {
+        let bytes = GroupElement::zero().to_bytes();
+        assert_eq!(GroupElement::from_bytes(&bytes), None);
+        assert_eq!(
+            GroupElement::from_bytes_allow_identity(&bytes),
+            Some(GroupElement::zero())
+        );
+    }
+
     #[test]
     fn ge_inverse() {
         let fe1 = Scalar::from_u64(124);
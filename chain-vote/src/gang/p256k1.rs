@@ -2,6 +2,8 @@ use eccoxide::curve::sec2::p256k1::{FieldElement, Point, PointAffine, Scalar as
 use rand_core::{CryptoRng, RngCore};
 use std::hash::{Hash, Hasher};
 use std::ops::{Add, Mul, Sub};
+use std::sync::atomic;
+use zeroize::Zeroize;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Scalar(IScalar);
@@ -65,6 +67,9 @@ impl GroupElement {
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTES_LEN {
+            return None;
+        }
         if bytes[0] == 0x4 {
             let x = FieldElement::from_slice(&bytes[1..33])?;
             let y = FieldElement::from_slice(&bytes[33..65])?;
@@ -75,6 +80,16 @@ impl GroupElement {
         }
     }
 
+    /// same as `from_bytes` but also accepts the all zeros encoding that
+    /// `to_bytes` gives to the point at infinity
+    pub fn from_bytes_allow_identity(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() == Self::BYTES_LEN && bytes.iter().all(|b| *b == 0) {
+            Some(Self::zero())
+        } else {
+            Self::from_bytes(bytes)
+        }
+    }
+
     pub fn sum<'a, I>(i: I) -> Self
     where
         I: Iterator<Item = &'a Self>,
@@ -168,6 +183,15 @@ impl Scalar {
     }
 }
 
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        // the limbs of the inner scalar are not exposed, overwrite the whole
+        // value with volatile writes so the compiler doesn't elide it
+        unsafe { std::ptr::write_volatile(self, Scalar::zero()) };
+        atomic::compiler_fence(atomic::Ordering::SeqCst);
+    }
+}
+
 impl From<bool> for Scalar {
     fn from(b: bool) -> Self {
         if b {
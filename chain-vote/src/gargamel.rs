@@ -3,6 +3,7 @@
 use crate::gang::{GroupElement, Scalar};
 use rand_core::{CryptoRng, RngCore};
 use std::ops::{Add, Mul};
+use zeroize::Zeroize;
 
 // ElGamal Ciphertext
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -41,6 +42,12 @@ impl PublicKey {
     }
 }
 
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.sk.zeroize()
+    }
+}
+
 impl SecretKey {
     pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
         let sk = Scalar::random(rng);
@@ -91,6 +98,14 @@ impl Ciphertext {
         Some(Ciphertext { e1, e2 })
     }
 
+    /// same as `from_bytes` but the elements may be the point at infinity,
+    /// as they are in the ciphertext of an empty encrypted tally
+    pub(crate) fn from_bytes_allow_identity(slice: &[u8]) -> Option<Ciphertext> {
+        let e1 = GroupElement::from_bytes_allow_identity(&slice[..GroupElement::BYTES_LEN])?;
+        let e2 = GroupElement::from_bytes_allow_identity(&slice[GroupElement::BYTES_LEN..])?;
+        Some(Ciphertext { e1, e2 })
+    }
+
     pub fn elements(&self) -> (&GroupElement, &GroupElement) {
         (&self.e1, &self.e2)
     }
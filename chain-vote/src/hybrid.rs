@@ -11,6 +11,25 @@ pub struct Encrypted {
     e2: Box<[u8]>,
 }
 
+impl Encrypted {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.e1.to_bytes();
+        bytes.extend_from_slice(&self.e2);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < gargamel::Ciphertext::BYTES_LEN {
+            return None;
+        }
+        let (e1, e2) = bytes.split_at(gargamel::Ciphertext::BYTES_LEN);
+        Some(Encrypted {
+            e1: gargamel::Ciphertext::from_bytes(e1)?,
+            e2: e2.into(),
+        })
+    }
+}
+
 fn bc_key(ck: &GroupElement) -> ChaCha20 {
     let mut out = [0u8; 44];
     let mut h = Blake2b::new(44);
@@ -32,7 +51,6 @@ pub fn encrypt(pk: &PublicKey, ck: &CommitmentKey, m: &[u8], r: &Scalar) -> Encr
     Encrypted { e1, e2 }
 }
 
-#[allow(dead_code)]
 pub fn decrypt(sk: &SecretKey, e: &Encrypted) -> Vec<u8> {
     let ck = gargamel::decrypt_point(sk, &e.e1);
     bc_process(&ck, &e.e2)
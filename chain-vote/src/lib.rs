@@ -2,6 +2,7 @@
 
 mod commitment;
 pub mod committee;
+mod dleq;
 mod encrypted;
 mod gang;
 pub mod gargamel;
@@ -22,7 +23,7 @@ pub mod debug {
 }
 
 pub use committee::{
-    MemberCommunicationKey, MemberCommunicationPublicKey, MemberPublicKey, MemberState,
+    MemberCommunicationKey, MemberCommunicationPublicKey, MemberPublicKey, MemberShare, MemberState,
 };
 pub use encrypted::EncryptingVote;
 use gang::GroupElement;
@@ -76,6 +77,22 @@ pub fn verify_vote(
     shvzk::verify(&public_key.0, vote, proof)
 }
 
+/// Verify many encrypted votes made for the same election key at once.
+///
+/// This is equivalent to calling `verify_vote` on each of the votes, but
+/// faster for large batches. If any of the votes is invalid the whole batch
+/// is rejected.
+pub fn verify_votes_batch(
+    public_key: &EncryptingVoteKey,
+    votes: &[(&EncryptedVote, &ProofOfCorrectVote)],
+) -> bool {
+    let votes = votes
+        .iter()
+        .map(|(vote, proof)| (vote.as_slice(), *proof))
+        .collect::<Vec<_>>();
+    shvzk::verify_batch(&public_key.0, &votes)
+}
+
 /// The encrypted tally
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct EncryptedTally {
@@ -85,6 +102,7 @@ pub struct EncryptedTally {
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct TallyDecryptShare {
     r1s: Vec<gang::GroupElement>,
+    proofs: Vec<dleq::Proof>,
 }
 
 #[derive(Clone)]
@@ -97,6 +115,25 @@ pub struct TallyResult {
     pub votes: Vec<Option<u64>>,
 }
 
+/// The tallies to merge do not have the same number of options
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TallyMergeError {
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl std::fmt::Display for TallyMergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cannot merge a tally of {} options into a tally of {} options",
+            self.actual, self.expected
+        )
+    }
+}
+
+impl std::error::Error for TallyMergeError {}
+
 impl EncryptedTally {
     /// Start a new tally with N different options
     pub fn new(options: usize) -> Self {
@@ -116,15 +153,80 @@ impl EncryptedTally {
         }
     }
 
-    pub fn finish(&self, secret_key: &OpeningVoteKey) -> (TallyState, TallyDecryptShare) {
+    /// Add the votes of another tally to this tally, as if they had been
+    /// added to this tally directly.
+    ///
+    /// This allows the votes to be collected in several partial tallies and
+    /// combined before being decrypted. The other tally needs to have the
+    /// exact same number of options as this tally, otherwise this tally is
+    /// left untouched and an error is returned
+    pub fn merge(&mut self, other: &EncryptedTally) -> Result<(), TallyMergeError> {
+        if other.r.len() != self.r.len() {
+            return Err(TallyMergeError {
+                expected: self.r.len(),
+                actual: other.r.len(),
+            });
+        }
+        for (ri, ci) in self.r.iter_mut().zip(other.r.iter()) {
+            *ri = &*ri + ci;
+        }
+        Ok(())
+    }
+
+    /// Compute the decryption share of the committee member owning the given
+    /// secret key, along with a proof the share was computed with that key.
+    pub fn finish<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        secret_key: &OpeningVoteKey,
+    ) -> (TallyState, TallyDecryptShare) {
+        let pk = GroupElement::generator() * &secret_key.0.sk;
+        self.finish_with(rng, secret_key, &pk)
+    }
+
+    fn finish_with<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        secret_key: &OpeningVoteKey,
+        pk: &GroupElement,
+    ) -> (TallyState, TallyDecryptShare) {
         let mut dshares = Vec::with_capacity(self.r.len());
+        let mut proofs = Vec::with_capacity(self.r.len());
         let mut r2s = Vec::with_capacity(self.r.len());
         for r in &self.r {
             let (r1, r2) = r.elements();
-            dshares.push(r1 * &secret_key.0.sk);
+            let dshare = r1 * &secret_key.0.sk;
+            proofs.push(dleq::Proof::generate(
+                rng,
+                &GroupElement::generator(),
+                pk,
+                r1,
+                &dshare,
+                &secret_key.0.sk,
+            ));
+            dshares.push(dshare);
             r2s.push(r2.clone());
         }
-        (TallyState { r2s }, TallyDecryptShare { r1s: dshares })
+        (
+            TallyState { r2s },
+            TallyDecryptShare {
+                r1s: dshares,
+                proofs,
+            },
+        )
+    }
+
+    /// Number of voting options this encrypted tally is accumulating.
+    pub fn options(&self) -> usize {
+        self.r.len()
+    }
+
+    /// Size of the byte representation for an encrypted tally
+    /// with the given number of options.
+    pub fn bytes_len(options: usize) -> usize {
+        Ciphertext::BYTES_LEN
+            .checked_mul(options)
+            .expect("integer overflow")
     }
 
     pub fn state(&self) -> TallyState {
@@ -135,7 +237,7 @@ impl EncryptedTally {
 
     pub fn to_bytes(&self) -> Vec<u8> {
         use std::io::Write;
-        let mut bytes: Vec<u8> = Vec::with_capacity(Ciphertext::BYTES_LEN * self.r.len());
+        let mut bytes: Vec<u8> = Vec::with_capacity(Self::bytes_len(self.r.len()));
         for ri in &self.r {
             bytes.write_all(ri.to_bytes().as_ref()).unwrap();
         }
@@ -148,7 +250,7 @@ impl EncryptedTally {
         }
         let r = bytes
             .chunks(Ciphertext::BYTES_LEN)
-            .map(Ciphertext::from_bytes)
+            .map(Ciphertext::from_bytes_allow_identity)
             .collect::<Option<Vec<_>>>()?;
         Some(Self { r })
     }
@@ -164,15 +266,77 @@ impl TallyDecryptShare {
     /// Size of the byte representation for a tally decrypt share
     /// with the given number of options.
     pub fn bytes_len(options: usize) -> usize {
+        (GroupElement::BYTES_LEN + dleq::Proof::BYTES_LEN)
+            .checked_mul(options)
+            .expect("integer overflow")
+    }
+
+    /// Size of the byte representation for a tally decrypt share without
+    /// proofs with the given number of options, as encoded before the shares
+    /// carried them.
+    pub fn bytes_len_without_proofs(options: usize) -> usize {
         group_elements_bytes_len(options)
     }
 
+    /// Tells if the share carries the proofs it was computed with the secret
+    /// key of its member, which a share decoded with
+    /// `from_bytes_without_proofs` does not.
+    pub fn has_proofs(&self) -> bool {
+        self.proofs.len() == self.r1s.len()
+    }
+
+    /// Verify the share was computed from the given encrypted tally with the
+    /// secret key matching the committee member public key.
+    pub fn verify(&self, encrypted_tally: &EncryptedTally, pk: &MemberPublicKey) -> bool {
+        if self.r1s.len() != encrypted_tally.r.len() || self.proofs.len() != self.r1s.len() {
+            return false;
+        }
+
+        let g = GroupElement::generator();
+        self.r1s
+            .iter()
+            .zip(self.proofs.iter())
+            .zip(encrypted_tally.r.iter())
+            .all(|((dshare, proof), r)| proof.verify(&g, &pk.0.pk, r.elements().0, dshare))
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
-        group_elements_to_bytes(&self.r1s)
+        use std::io::Write;
+        let mut bytes: Vec<u8> = Vec::with_capacity(Self::bytes_len(self.r1s.len()));
+        for (r1, proof) in self.r1s.iter().zip(self.proofs.iter()) {
+            bytes.write_all(r1.to_bytes().as_ref()).unwrap();
+            bytes.write_all(proof.to_bytes().as_ref()).unwrap();
+        }
+        bytes
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        group_elements_from_bytes(bytes).map(|r1s| Self { r1s })
+        let chunks = bytes.chunks_exact(GroupElement::BYTES_LEN + dleq::Proof::BYTES_LEN);
+        if !chunks.remainder().is_empty() {
+            return None;
+        }
+
+        let mut r1s = Vec::with_capacity(chunks.len());
+        let mut proofs = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            r1s.push(GroupElement::from_bytes(&chunk[..GroupElement::BYTES_LEN])?);
+            proofs.push(dleq::Proof::from_bytes(&chunk[GroupElement::BYTES_LEN..])?);
+        }
+        Some(Self { r1s, proofs })
+    }
+
+    /// Encode the share without its proofs, as the shares were encoded
+    /// before they carried them.
+    pub fn to_bytes_without_proofs(&self) -> Vec<u8> {
+        group_elements_to_bytes(&self.r1s)
+    }
+
+    /// Decode a share encoded without proofs, which never verifies.
+    pub fn from_bytes_without_proofs(bytes: &[u8]) -> Option<Self> {
+        group_elements_from_bytes(bytes).map(|r1s| Self {
+            r1s,
+            proofs: Vec::new(),
+        })
     }
 }
 
@@ -219,6 +383,26 @@ fn group_elements_from_bytes(bytes: &[u8]) -> Option<Vec<gang::GroupElement>> {
     Some(elements)
 }
 
+/// Compute the decryption shares of the committee member owning the given
+/// secret key for many encrypted tallies (e.g. all the proposals of a vote
+/// plan) at once.
+///
+/// This is equivalent to calling `EncryptedTally::finish` on each of the
+/// tallies, the shares are returned in the same order as the tallies.
+pub fn finish_all<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    secret_key: &OpeningVoteKey,
+    encrypted_tallies: &[&EncryptedTally],
+) -> Vec<TallyDecryptShare> {
+    let pk = GroupElement::generator() * &secret_key.0.sk;
+    encrypted_tallies
+        .iter()
+        .map(|encrypted_tally| encrypted_tally.finish_with(rng, secret_key, &pk).1)
+        .collect()
+}
+
+/// Decrypt the tally from the decryption shares of all the committee
+/// members, ordered by their index in the committee.
 pub fn result(
     max_votes: u64,
     table_size: usize,
@@ -227,7 +411,50 @@ pub fn result(
 ) -> TallyResult {
     let ris = (0..tally_state.r2s.len())
         .map(|i| gang::GroupElement::sum(decrypt_shares.iter().map(|ds| &ds.r1s[i])));
+    decrypt_result(max_votes, table_size, tally_state, ris)
+}
+
+/// Decrypt the tally from the decryption shares of some of the committee
+/// members, each given along with the index of its member in the committee.
+///
+/// The members hold a threshold secret key (see
+/// `MemberState::threshold_secret_key`) and the tally was encrypted to
+/// `EncryptingVoteKey::from_threshold_participants`, so the shares of any
+/// threshold of them are enough. Whether the members reach the threshold is
+/// checked beforehand with `EncryptingVoteKey::reached_by`, the decrypted
+/// values are meaningless otherwise.
+pub fn result_from_members(
+    max_votes: u64,
+    table_size: usize,
+    tally_state: &TallyState,
+    decrypt_shares: &[(usize, &TallyDecryptShare)],
+) -> TallyResult {
+    let points = decrypt_shares
+        .iter()
+        .map(|(member_index, _)| committee::member_point(*member_index))
+        .collect::<Vec<_>>();
+    let lagranges = points
+        .iter()
+        .map(|point| math::lagrange_at_zero(point, &points))
+        .collect::<Vec<_>>();
+
+    let ris = (0..tally_state.r2s.len()).map(|i| {
+        let weighted = decrypt_shares
+            .iter()
+            .zip(lagranges.iter())
+            .map(|((_, ds), lagrange)| &ds.r1s[i] * lagrange)
+            .collect::<Vec<_>>();
+        gang::GroupElement::sum(weighted.iter())
+    });
+    decrypt_result(max_votes, table_size, tally_state, ris)
+}
 
+fn decrypt_result(
+    max_votes: u64,
+    table_size: usize,
+    tally_state: &TallyState,
+    ris: impl Iterator<Item = gang::GroupElement>,
+) -> TallyResult {
     let mut r_results = tally_state
         .r2s
         .iter()
@@ -321,7 +548,7 @@ mod tests {
         tally.add(&e2.0, 5);
         tally.add(&e3.0, 4);
 
-        let (ts, tds1) = tally.finish(m1.secret_key());
+        let (ts, tds1) = tally.finish(&mut rng, m1.secret_key());
 
         let max_votes = 20;
 
@@ -336,6 +563,59 @@ mod tests {
         assert_eq!(tr.votes[1], Some(5), "vote for option 1");
     }
 
+    #[test]
+    fn merge_tallies() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        let h = CRS::random(&mut rng);
+        let mc1 = MemberCommunicationKey::new(&mut rng);
+        let m1 = MemberState::new(&mut rng, 1, &h, &[mc1.to_public()], 0);
+        let ek = EncryptingVoteKey::from_participants(&[m1.public_key()]);
+
+        let vote_options = 3;
+        let votes = [(0, 6), (1, 5), (0, 4), (2, 3)]
+            .iter()
+            .map(|(choice, weight)| {
+                let (vote, _) = encrypt_vote(&mut rng, &ek, Vote::new(vote_options, *choice));
+                (vote, *weight)
+            })
+            .collect::<Vec<_>>();
+
+        let mut tally = EncryptedTally::new(vote_options);
+        for (vote, weight) in &votes {
+            tally.add(vote, *weight);
+        }
+
+        // the same votes collected in two partial tallies
+        let mut shard1 = EncryptedTally::new(vote_options);
+        let mut shard2 = EncryptedTally::new(vote_options);
+        for (vote, weight) in &votes[..2] {
+            shard1.add(vote, *weight);
+        }
+        for (vote, weight) in &votes[2..] {
+            shard2.add(vote, *weight);
+        }
+        shard1.merge(&shard2).unwrap();
+        assert_eq!(shard1, tally);
+
+        let (ts, tds1) = shard1.finish(&mut rng, m1.secret_key());
+        let tr = result(20, 5, &ts, &[tds1]);
+        assert_eq!(tr.votes, vec![Some(10), Some(5), Some(3)]);
+    }
+
+    #[test]
+    fn merge_tallies_of_different_options() {
+        let mut tally = EncryptedTally::new(2);
+        assert_eq!(
+            tally.merge(&EncryptedTally::new(3)),
+            Err(TallyMergeError {
+                expected: 2,
+                actual: 3
+            })
+        );
+        assert_eq!(tally, EncryptedTally::new(2));
+    }
+
     #[test]
     fn encdec3() {
         let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
@@ -371,9 +651,14 @@ mod tests {
         tally.add(&e2.0, 3);
         tally.add(&e3.0, 4);
 
-        let (_, tds1) = tally.finish(m1.secret_key());
-        let (_, tds2) = tally.finish(m2.secret_key());
-        let (ts, tds3) = tally.finish(m3.secret_key());
+        let (_, tds1) = tally.finish(&mut rng, m1.secret_key());
+        let (_, tds2) = tally.finish(&mut rng, m2.secret_key());
+        let (ts, tds3) = tally.finish(&mut rng, m3.secret_key());
+
+        assert!(tds1.verify(&tally, &m1.public_key()));
+        assert!(tds2.verify(&tally, &m2.public_key()));
+        assert!(tds3.verify(&tally, &m3.public_key()));
+        assert!(!tds1.verify(&tally, &m2.public_key()));
 
         let max_votes = 20;
 
@@ -387,4 +672,417 @@ mod tests {
         assert_eq!(tr.votes[0], Some(5), "vote for option 0");
         assert_eq!(tr.votes[1], Some(3), "vote for option 1");
     }
+
+    #[test]
+    fn threshold_decryption() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        let h = CRS::random(&mut rng);
+
+        let mcs = (0..3)
+            .map(|_| MemberCommunicationKey::new(&mut rng))
+            .collect::<Vec<_>>();
+        let mc = mcs.iter().map(|mc| mc.to_public()).collect::<Vec<_>>();
+
+        let threshold = 2;
+
+        let states = (0..mc.len())
+            .map(|i| MemberState::new(&mut rng, threshold, &h, &mc, i))
+            .collect::<Vec<_>>();
+
+        let secret_keys = states
+            .iter()
+            .zip(mcs.iter())
+            .enumerate()
+            .map(|(i, (state, mc))| {
+                let shares = states
+                    .iter()
+                    .map(|dealer| dealer.share_for(i).unwrap())
+                    .collect::<Vec<_>>();
+                state.threshold_secret_key(mc, &shares).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // a member cannot use the shares dealt to another member
+        let shares_for_m2 = states
+            .iter()
+            .map(|dealer| dealer.share_for(1).unwrap())
+            .collect::<Vec<_>>();
+        assert!(states[0]
+            .threshold_secret_key(&mcs[0], &shares_for_m2)
+            .is_none());
+
+        let participants = secret_keys
+            .iter()
+            .map(|sk| sk.to_public())
+            .collect::<Vec<_>>();
+        let ek = EncryptingVoteKey::from_threshold_participants(&participants);
+
+        let vote_options = 2;
+        let (e1, _) = encrypt_vote(&mut rng, &ek, Vote::new(vote_options, 0));
+        let (e2, _) = encrypt_vote(&mut rng, &ek, Vote::new(vote_options, 1));
+        let mut tally = EncryptedTally::new(vote_options);
+        tally.add(&e1, 2);
+        tally.add(&e2, 3);
+
+        let shares = secret_keys
+            .iter()
+            .map(|sk| tally.finish(&mut rng, sk).1)
+            .collect::<Vec<_>>();
+        for (share, pk) in shares.iter().zip(participants.iter()) {
+            assert!(share.verify(&tally, pk));
+        }
+
+        let ts = tally.state();
+        let all = shares.iter().enumerate().collect::<Vec<_>>();
+        let tr = result_from_members(10, 3, &ts, &all);
+        assert_eq!(tr.votes, vec![Some(2), Some(3)]);
+
+        // the sum of the shares does not decrypt a threshold election key
+        let tr = result(10, 3, &ts, &shares);
+        assert_ne!(tr.votes, vec![Some(2), Some(3)]);
+
+        // any 2 members out of 3 are enough to decrypt the tally
+        for members in [[0, 1], [0, 2], [1, 2]].iter() {
+            let subset = members
+                .iter()
+                .map(|i| (*i, &shares[*i]))
+                .collect::<Vec<_>>();
+            let tr = result_from_members(10, 3, &ts, &subset);
+            assert_eq!(tr.votes, vec![Some(2), Some(3)]);
+        }
+
+        // but a single member is not
+        let tr = result_from_members(10, 3, &ts, &[(1, &shares[1])]);
+        assert_ne!(tr.votes, vec![Some(2), Some(3)]);
+
+        // which is known before decrypting
+        for members in [[0, 1], [0, 2], [1, 2]].iter() {
+            assert!(EncryptingVoteKey::reached_by(&participants, members));
+        }
+        for members in [&[0][..], &[2], &[1, 1]].iter() {
+            assert!(!EncryptingVoteKey::reached_by(&participants, members));
+        }
+        assert!(!EncryptingVoteKey::reached_by(&participants, &[0, 3]));
+
+        // a member needs a share from every dealer
+        let shares_for_m1 = states
+            .iter()
+            .take(threshold)
+            .map(|dealer| dealer.share_for(0).unwrap())
+            .collect::<Vec<_>>();
+        assert!(states[0]
+            .threshold_secret_key(&mcs[0], &shares_for_m1)
+            .is_none());
+    }
+
+    #[test]
+    fn committee_handover() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        let h = CRS::random(&mut rng);
+        let threshold = 2;
+
+        let mcs = (0..3)
+            .map(|_| MemberCommunicationKey::new(&mut rng))
+            .collect::<Vec<_>>();
+        let mc = mcs.iter().map(|mc| mc.to_public()).collect::<Vec<_>>();
+        let states = (0..mc.len())
+            .map(|i| MemberState::new(&mut rng, threshold, &h, &mc, i))
+            .collect::<Vec<_>>();
+        let secret_keys = states
+            .iter()
+            .zip(mcs.iter())
+            .enumerate()
+            .map(|(i, (state, mc))| {
+                let shares = states
+                    .iter()
+                    .map(|dealer| dealer.share_for(i).unwrap())
+                    .collect::<Vec<_>>();
+                state.threshold_secret_key(mc, &shares).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let participants = secret_keys
+            .iter()
+            .map(|sk| sk.to_public())
+            .collect::<Vec<_>>();
+        let ek = EncryptingVoteKey::from_threshold_participants(&participants);
+
+        // the first and last members hand over to a new committee
+        let new_mcs = (0..3)
+            .map(|_| MemberCommunicationKey::new(&mut rng))
+            .collect::<Vec<_>>();
+        let new_mc = new_mcs.iter().map(|mc| mc.to_public()).collect::<Vec<_>>();
+        let dealt = [0, 2]
+            .iter()
+            .map(|i| (*i, secret_keys[*i].reshare(&mut rng, threshold, &new_mc)))
+            .collect::<Vec<_>>();
+        let new_secret_keys = new_mcs
+            .iter()
+            .enumerate()
+            .map(|(j, mc)| {
+                let shares = dealt
+                    .iter()
+                    .map(|(i, shares)| (*i, shares[j].clone()))
+                    .collect::<Vec<_>>();
+                OpeningVoteKey::from_handover(mc, j, &participants, &shares).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let new_participants = new_secret_keys
+            .iter()
+            .map(|sk| sk.to_public())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            EncryptingVoteKey::from_threshold_participants(&new_participants).to_bytes(),
+            ek.to_bytes()
+        );
+
+        let vote_options = 2;
+        let (e1, _) = encrypt_vote(&mut rng, &ek, Vote::new(vote_options, 0));
+        let (e2, _) = encrypt_vote(&mut rng, &ek, Vote::new(vote_options, 1));
+        let mut tally = EncryptedTally::new(vote_options);
+        tally.add(&e1, 2);
+        tally.add(&e2, 3);
+        let ts = tally.state();
+
+        // any 2 members of the new committee decrypt the tally
+        let shares = new_secret_keys
+            .iter()
+            .map(|sk| tally.finish(&mut rng, sk).1)
+            .collect::<Vec<_>>();
+        for members in [[0, 1], [0, 2], [1, 2]].iter() {
+            assert!(EncryptingVoteKey::reached_by(&new_participants, members));
+            let subset = members
+                .iter()
+                .map(|i| (*i, &shares[*i]))
+                .collect::<Vec<_>>();
+            let tr = result_from_members(10, 3, &ts, &subset);
+            assert_eq!(tr.votes, vec![Some(2), Some(3)]);
+        }
+
+        // a single previous member cannot hand over on its own
+        let (dealer, single) = &dealt[0];
+        assert!(OpeningVoteKey::from_handover(
+            &new_mcs[0],
+            0,
+            &participants,
+            &[(*dealer, single[0].clone())]
+        )
+        .is_none());
+
+        // and the shares must be attributed to the member which dealt them
+        let swapped = [(2, dealt[0].1[0].clone()), (0, dealt[1].1[0].clone())];
+        assert!(OpeningVoteKey::from_handover(&new_mcs[0], 0, &participants, &swapped).is_none());
+    }
+
+    #[test]
+    fn decrypt_share_serialization() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        let h = CRS::random(&mut rng);
+        let mc1 = MemberCommunicationKey::new(&mut rng);
+        let mc = [mc1.to_public()];
+        let m1 = MemberState::new(&mut rng, 1, &h, &mc, 0);
+        let ek = EncryptingVoteKey::from_participants(&[m1.public_key()]);
+
+        let vote_options = 3;
+        let (e1, _) = encrypt_vote(&mut rng, &ek, Vote::new(vote_options, 1));
+        let mut tally = EncryptedTally::new(vote_options);
+        tally.add(&e1, 1);
+
+        let (_, tds1) = tally.finish(&mut rng, m1.secret_key());
+        let bytes = tds1.to_bytes();
+        assert_eq!(bytes.len(), TallyDecryptShare::bytes_len(vote_options));
+        let tds1_decoded = TallyDecryptShare::from_bytes(&bytes).unwrap();
+        assert_eq!(tds1, tds1_decoded);
+        assert!(tds1_decoded.verify(&tally, &m1.public_key()));
+
+        // the shares encoded before they carried proofs still decrypt
+        let bytes = tds1.to_bytes_without_proofs();
+        assert_eq!(
+            bytes.len(),
+            TallyDecryptShare::bytes_len_without_proofs(vote_options)
+        );
+        assert!(TallyDecryptShare::from_bytes(&bytes).is_none());
+        let tds1_legacy = TallyDecryptShare::from_bytes_without_proofs(&bytes).unwrap();
+        assert!(!tds1_legacy.has_proofs());
+        assert!(!tds1_legacy.verify(&tally, &m1.public_key()));
+        assert_eq!(tds1_legacy.to_bytes_without_proofs(), bytes);
+        assert_eq!(
+            result(10, 3, &tally.state(), &[tds1_legacy]),
+            result(10, 3, &tally.state(), &[tds1])
+        );
+    }
+
+    #[test]
+    fn encrypted_tally_serialization() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        let h = CRS::random(&mut rng);
+        let mc1 = MemberCommunicationKey::new(&mut rng);
+        let mc = [mc1.to_public()];
+        let m1 = MemberState::new(&mut rng, 1, &h, &mc, 0);
+        let ek = EncryptingVoteKey::from_participants(&[m1.public_key()]);
+
+        let vote_options = 3;
+
+        // a freshly started tally only holds zero ciphertexts
+        let tally = EncryptedTally::new(vote_options);
+        let bytes = tally.to_bytes();
+        assert_eq!(bytes.len(), EncryptedTally::bytes_len(vote_options));
+        assert_eq!(EncryptedTally::from_bytes(&bytes).unwrap(), tally);
+
+        let (e1, _) = encrypt_vote(&mut rng, &ek, Vote::new(vote_options, 0));
+        let (e2, _) = encrypt_vote(&mut rng, &ek, Vote::new(vote_options, 1));
+        let mut tally = EncryptedTally::new(vote_options);
+        tally.add(&e1, 2);
+
+        // reload the tally in the middle of the voting and keep accumulating
+        let mut reloaded = EncryptedTally::from_bytes(&tally.to_bytes()).unwrap();
+        assert_eq!(reloaded, tally);
+        assert_eq!(reloaded.options(), vote_options);
+        tally.add(&e2, 3);
+        reloaded.add(&e2, 3);
+        assert_eq!(reloaded, tally);
+
+        let (ts, tds1) = tally.finish(&mut rng, m1.secret_key());
+        assert!(tds1.verify(&reloaded, &m1.public_key()));
+
+        let shares = vec![tds1];
+        let tr = result(10, 3, &ts, &shares);
+        let tr_reloaded = result(10, 3, &reloaded.state(), &shares);
+        assert_eq!(tr, tr_reloaded);
+        assert_eq!(tr.votes, vec![Some(2), Some(3), Some(0)]);
+    }
+
+    #[test]
+    fn encrypted_tally_from_invalid_bytes() {
+        assert!(EncryptedTally::from_bytes(&[0u8; Ciphertext::BYTES_LEN + 1]).is_none());
+        assert!(EncryptedTally::from_bytes(&[1u8; Ciphertext::BYTES_LEN]).is_none());
+    }
+
+    #[test]
+    fn crs_serialization() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let h = CRS::random(&mut rng);
+        let h_bytes = h.to_bytes();
+        let h_decoded = CRS::from_bytes(&h_bytes).unwrap();
+        assert_eq!(h, h_decoded);
+
+        let mc1 = MemberCommunicationKey::new(&mut rng);
+        let mc1_public = MemberCommunicationPublicKey::from_bytes(&mc1.to_public().to_bytes());
+        let mc = [mc1_public.unwrap()];
+
+        // members generating their state from the same seed with the
+        // original and the reloaded CRS end up with the same keys
+        let m1 = MemberState::new(&mut ChaCha20Rng::from_seed([1u8; 32]), 1, &h, &mc, 0);
+        let m1_reloaded = MemberState::new(
+            &mut ChaCha20Rng::from_seed([1u8; 32]),
+            1,
+            &h_decoded,
+            &mc,
+            0,
+        );
+        assert_eq!(m1.public_key(), m1_reloaded.public_key());
+
+        let ek = EncryptingVoteKey::from_participants(&[m1_reloaded.public_key()]);
+        let vote_options = 2;
+        let (e1, _) = encrypt_vote(&mut rng, &ek, Vote::new(vote_options, 1));
+        let mut tally = EncryptedTally::new(vote_options);
+        tally.add(&e1, 3);
+
+        let (ts, tds1) = tally.finish(&mut rng, m1.secret_key());
+        let (_, tds1_reloaded) = tally.finish(&mut rng, m1_reloaded.secret_key());
+        let tr = result(10, 3, &ts, &[tds1]);
+        let tr_reloaded = result(10, 3, &ts, &[tds1_reloaded]);
+        assert_eq!(tr, tr_reloaded);
+        assert_eq!(tr.votes, vec![Some(0), Some(3)]);
+    }
+
+    #[test]
+    fn member_secret_key_zeroize() {
+        use zeroize::Zeroize;
+
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        let h = CRS::random(&mut rng);
+        let mc1 = MemberCommunicationKey::new(&mut rng);
+        let m1 = MemberState::new(&mut rng, 1, &h, &[mc1.to_public()], 0);
+
+        let mut sk = committee::MemberSecretKey::from_bytes(&m1.secret_key().to_bytes()).unwrap();
+        assert_ne!(sk.to_bytes(), [0u8; 32]);
+        sk.zeroize();
+        assert_eq!(sk.to_bytes(), [0u8; 32]);
+
+        // the key is zeroed by the drop of its inner secret key
+        assert!(std::mem::needs_drop::<committee::MemberSecretKey>());
+        assert!(std::mem::needs_drop::<MemberState>());
+    }
+
+    #[test]
+    fn member_state_save_load() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        let h = CRS::random(&mut rng);
+        let mc1 = MemberCommunicationKey::new(&mut rng);
+        let mc2 = MemberCommunicationKey::new(&mut rng);
+        let mc = [mc1.to_public(), mc2.to_public()];
+        let m1 = MemberState::new(&mut rng, 2, &h, &mc, 0);
+
+        let saved = m1.save(&mut rng, b"passphrase");
+        assert!(MemberState::load(b"wrong passphrase", &saved).is_none());
+        assert!(MemberState::load(b"passphrase", &saved[..saved.len() - 1]).is_none());
+
+        let m1_loaded = MemberState::load(b"passphrase", &saved).unwrap();
+        assert_eq!(m1.public_key(), m1_loaded.public_key());
+
+        let ek = EncryptingVoteKey::from_participants(&[m1.public_key()]);
+        let vote_options = 2;
+        let (e1, _) = encrypt_vote(&mut rng, &ek, Vote::new(vote_options, 0));
+        let mut tally = EncryptedTally::new(vote_options);
+        tally.add(&e1, 2);
+
+        let (_, tds1) = tally.finish(&mut ChaCha20Rng::from_seed([1u8; 32]), m1.secret_key());
+        let (_, tds1_loaded) = tally.finish(
+            &mut ChaCha20Rng::from_seed([1u8; 32]),
+            m1_loaded.secret_key(),
+        );
+        assert_eq!(tds1, tds1_loaded);
+        assert!(tds1_loaded.verify(&tally, &m1.public_key()));
+    }
+
+    #[test]
+    fn finish_all_tallies() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        let h = CRS::random(&mut rng);
+        let mc1 = MemberCommunicationKey::new(&mut rng);
+        let mc = [mc1.to_public()];
+        let m1 = MemberState::new(&mut rng, 1, &h, &mc, 0);
+        let ek = EncryptingVoteKey::from_participants(&[m1.public_key()]);
+
+        let vote_options = 2;
+        let tallies = (0..3)
+            .map(|i| {
+                let (e, _) = encrypt_vote(&mut rng, &ek, Vote::new(vote_options, i % 2));
+                let mut tally = EncryptedTally::new(vote_options);
+                tally.add(&e, 1);
+                tally
+            })
+            .collect::<Vec<_>>();
+
+        let mut rng_all = ChaCha20Rng::from_seed([1u8; 32]);
+        let shares = finish_all(
+            &mut rng_all,
+            m1.secret_key(),
+            &tallies.iter().collect::<Vec<_>>(),
+        );
+
+        let mut rng_one = ChaCha20Rng::from_seed([1u8; 32]);
+        assert_eq!(shares.len(), tallies.len());
+        for (tally, share) in tallies.iter().zip(shares.iter()) {
+            let (_, expected) = tally.finish(&mut rng_one, m1.secret_key());
+            assert_eq!(share, &expected);
+        }
+    }
 }
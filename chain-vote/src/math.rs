@@ -103,6 +103,21 @@ impl Polynomial {
     }
 }
 
+/// Lagrange basis polynomial of the point `at` over the given `points`,
+/// evaluated at x=0
+///
+/// This is the weight of the value at `at` when interpolating at zero the
+/// polynomial going through all the `points`.
+pub fn lagrange_at_zero(at: &Scalar, points: &[Scalar]) -> Scalar {
+    let mut numerator = Scalar::one();
+    let mut denominator = Scalar::one();
+    for point in points.iter().filter(|point| *point != at) {
+        numerator = &numerator * point;
+        denominator = &denominator * &(point - at);
+    }
+    numerator * denominator.inverse()
+}
+
 impl std::ops::Add<Polynomial> for Polynomial {
     type Output = Polynomial;
 
@@ -142,6 +157,28 @@ impl std::ops::Mul<Polynomial> for Polynomial {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
     #[test]
     fn mul_degree() {}
+
+    #[test]
+    fn lagrange_interpolation_at_zero() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let polynomial = Polynomial::random(&mut rng, 2);
+
+        // any 3 points are enough to recover a polynomial of degree 2
+        for xs in [[1, 2, 3], [1, 3, 5], [2, 4, 5]].iter() {
+            let points = xs.iter().map(|x| Scalar::from_u64(*x)).collect::<Vec<_>>();
+            let at_zero = Scalar::sum(
+                points
+                    .iter()
+                    .map(|x| polynomial.evaluate(x) * lagrange_at_zero(x, &points)),
+            )
+            .unwrap();
+            assert_eq!(at_zero, polynomial.at_zero());
+        }
+    }
 }
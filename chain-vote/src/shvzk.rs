@@ -416,6 +416,138 @@ pub(crate) fn verify(public_key: &PublicKey, ciphertexts: &[Ciphertext], proof:
     true
 }
 
+/// Derive the context from which the batch verification coefficients are
+/// drawn, committing to every element of every proof in the batch.
+fn batch_context(public_key: &PublicKey, votes: &[(&[Ciphertext], &Proof)]) -> Blake2b {
+    let mut ctx = Blake2b::new(32);
+    ctx.input(&public_key.to_bytes());
+    for (ciphertexts, proof) in votes {
+        for c in ciphertexts.iter() {
+            ctx.input(&c.to_bytes());
+        }
+        for iba in &proof.ibas {
+            ctx.input(&iba.to_bytes());
+        }
+        for d in &proof.ds {
+            ctx.input(&d.to_bytes());
+        }
+        for zwv in &proof.zwvs {
+            ctx.input(&zwv.to_bytes());
+        }
+        ctx.input(&proof.r.to_bytes());
+    }
+    ctx
+}
+
+fn batch_coefficient(ctx: &Blake2b, index: usize) -> Scalar {
+    let mut i = 0u32;
+    let mut h = [0u8; 32];
+    loop {
+        let mut c = ctx.clone();
+        c.input(&(index as u64).to_be_bytes());
+        c.input(&i.to_be_bytes());
+        c.result(&mut h);
+        match Scalar::from_bytes(&h) {
+            None => i += 1,
+            Some(fe) => break fe,
+        }
+    }
+}
+
+/// Verify many proofs of unit vector against the same public key at once.
+///
+/// Every equation checked by `verify` is of the form `P = 0` for some group
+/// element `P`. Instead of checking them one by one, the equations of the k-th
+/// proof are weighted by the successive powers of a coefficient `rho_k`
+/// derived from the whole batch, and only the sum of all the weighted
+/// equations is checked. The terms in the generator, the commitment key and
+/// the public key are accumulated as scalars and multiplied only once.
+///
+/// If any proof of the batch is invalid the batch is rejected, except with
+/// negligible probability.
+pub(crate) fn verify_batch(public_key: &PublicKey, votes: &[(&[Ciphertext], &Proof)]) -> bool {
+    let ck = commitkey(public_key);
+    let batch_ctx = batch_context(public_key, votes);
+
+    let mut acc = GroupElement::zero();
+    let mut g_scalar = Scalar::zero();
+    let mut h_scalar = Scalar::zero();
+    let mut pk_scalar = Scalar::zero();
+
+    for (k, (ciphertexts, proof)) in votes.iter().enumerate() {
+        let ciphertexts = PTP::new(ciphertexts.to_vec(), Ciphertext::zero);
+        let bits = ciphertexts.bits();
+
+        if proof.ibas.len() != bits || proof.zwvs.len() != bits {
+            return false;
+        }
+
+        let cc = ChallengeContext::new(public_key, ciphertexts.as_ref(), &proof.ibas);
+        let cy = cc.first_challenge();
+        let cx = cc.second_challenge(&proof.ds);
+
+        let rho = batch_coefficient(&batch_ctx, k);
+        let mut coefficients = std::iter::successors(Some(rho.clone()), |c| Some(c * &rho));
+        let mut next_coefficient = || coefficients.next().unwrap();
+
+        // commitments are 0 / 1:
+        //   i * x + b - com(z, w) = 0
+        //   i * (x - z) + a - com(0, v) = 0
+        for (iba, zwv) in proof.ibas.iter().zip(proof.zwvs.iter()) {
+            let a1 = next_coefficient();
+            let a2 = next_coefficient();
+
+            let i_scalar = &(&a1 * &cx) + &(&a2 * &(&cx - &zwv.z));
+            let i_term = iba.i.as_group_element() * &i_scalar;
+            let b_term = iba.b.as_group_element() * &a1;
+            let a_term = iba.a.as_group_element() * &a2;
+            acc = &(&acc + &i_term) + &(&b_term + &a_term);
+
+            g_scalar = &g_scalar + &(&a1 * &zwv.z);
+            h_scalar = &(&h_scalar + &(&a1 * &zwv.w)) + &(&a2 * &zwv.v);
+        }
+
+        // product: sum(y^i * (x^bits * c_i - enc(multz_i, 0))) + sum(x^l * d_l) - enc(0, r) = 0
+        let b1 = next_coefficient();
+        let b2 = next_coefficient();
+        let cx_pow = cx.power(bits);
+
+        let mut e1 = GroupElement::zero();
+        let mut e2 = GroupElement::zero();
+        for (i, c) in ciphertexts.iter().enumerate() {
+            let idx = binrep(i, bits as u32);
+            let multz = proof
+                .zwvs
+                .iter()
+                .enumerate()
+                .fold(Scalar::one(), |acc, (j, zwv)| {
+                    let m = if idx[j] { zwv.z.clone() } else { &cx - &zwv.z };
+                    &acc * m
+                });
+            let y_pow_i = cy.power(i);
+            let s = &cx_pow * &y_pow_i;
+            let (c1, c2) = c.elements();
+            e1 = &e1 + &(c1 * &s);
+            e2 = &e2 + &(c2 * &s);
+            g_scalar = &g_scalar + &(&b2 * &(&y_pow_i * &multz));
+        }
+        for (l, d) in proof.ds.iter().enumerate() {
+            let x_pow_l = cx.power(l);
+            let (d1, d2) = d.elements();
+            e1 = &e1 + &(d1 * &x_pow_l);
+            e2 = &e2 + &(d2 * &x_pow_l);
+        }
+        acc = &(&acc + &(&e1 * &b1)) + &(&e2 * &b2);
+
+        g_scalar = &g_scalar + &(&b1 * &proof.r);
+        pk_scalar = &pk_scalar + &(&b2 * &proof.r);
+    }
+
+    let expected = &(&(&GroupElement::generator() * &g_scalar) + &(&ck.h * &h_scalar))
+        + &(&public_key.pk * &pk_scalar);
+    acc == expected
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -446,4 +578,41 @@ mod tests {
         let proof = prove(&mut r, &public_key, ev.clone());
         assert!(verify(&public_key, &ev.ciphertexts, &proof))
     }
+
+    #[test]
+    fn prove_verify_batch() {
+        let mut r = ChaCha20Rng::from_seed([0u8; 32]);
+        let public_key = gargamel::generate(&mut r).public_key;
+
+        let votes = [
+            UnitVector::new(5, 1),
+            UnitVector::new(3, 2),
+            UnitVector::new(2, 0),
+        ]
+        .iter()
+        .map(|unit_vector| {
+            let ev = EncryptingVote::prepare(&mut r, &public_key, unit_vector);
+            let proof = prove(&mut r, &public_key, ev.clone());
+            (ev.ciphertexts, proof)
+        })
+        .collect::<Vec<_>>();
+
+        let batch = votes
+            .iter()
+            .map(|(c, p)| (c.as_slice(), p))
+            .collect::<Vec<_>>();
+        assert!(verify_batch(&public_key, &batch));
+        assert!(verify_batch(&public_key, &[]));
+
+        // one vote encrypted with a proof made for different ciphertexts
+        let mut tampered = batch.clone();
+        tampered[1].1 = batch[2].1;
+        assert!(!verify_batch(&public_key, &tampered));
+
+        // valid proof but for another vote
+        let ev = EncryptingVote::prepare(&mut r, &public_key, &UnitVector::new(5, 1));
+        let mut tampered = batch.clone();
+        tampered[0].0 = &ev.ciphertexts;
+        assert!(!verify_batch(&public_key, &tampered));
+    }
 }